@@ -15,6 +15,11 @@ object AgentCore {
     private const val TAG = "AgentCore"
     private var isLoaded = false
     
+    /**
+     * Schema version of the {"version", "data"} envelope native JSON results come in
+     */
+    const val SCHEMA_VERSION = 4
+    
     /**
      * Load the native library
      */
@@ -38,6 +43,26 @@ object AgentCore {
      */
     fun isAvailable(): Boolean = isLoaded
     
+    /**
+     * Unwrap a native JSON result
+     * @return the "data" payload as JSON, or null for an error, a null result
+     *         or a schema version this build doesn't understand
+     */
+    internal fun unwrapResult(json: String): String? {
+        val obj = JSONObject(json)
+        if (obj.has("error")) {
+            Log.w(TAG, "Native error: ${obj.optString("error")}")
+            return null
+        }
+        val version = obj.optInt("version", -1)
+        if (version != SCHEMA_VERSION) {
+            Log.e(TAG, "Unsupported result schema version $version, expected $SCHEMA_VERSION")
+            return null
+        }
+        if (obj.isNull("data")) return null
+        return obj.get("data").toString()
+    }
+    
     // Native methods
     private external fun init()
     external fun getVersion(): String
@@ -106,8 +131,8 @@ object ImageEngineNative {
         if (!AgentCore.isAvailable()) return null
         
         return try {
-            val json = detectJoystickNative(pixels, width, height)
-            if (json == "null" || json.contains("error")) null
+            val json = AgentCore.unwrapResult(detectJoystickNative(pixels, width, height))
+            if (json == null) null
             else parseDetectedElement(JSONObject(json))
         } catch (e: Exception) {
             Log.e("ImageEngineNative", "detectJoystick error: ${e.message}")
@@ -133,7 +158,9 @@ object ImageEngineNative {
         if (!AgentCore.isAvailable()) return Array(rows) { IntArray(cols) }
         
         return try {
-            val json = analyzeEliminateBoardNative(pixels, width, height, gridX, gridY, gridW, gridH, rows, cols)
+            val json = AgentCore.unwrapResult(
+                analyzeEliminateBoardNative(pixels, width, height, gridX, gridY, gridW, gridH, rows, cols)
+            ) ?: return Array(rows) { IntArray(cols) }
             val arr = JSONArray(json)
             Array(arr.length()) { i ->
                 val row = arr.getJSONArray(i)
@@ -147,7 +174,7 @@ object ImageEngineNative {
     
     private fun parseDetectedElements(json: String): List<DetectedElement> {
         return try {
-            val arr = JSONArray(json)
+            val arr = JSONArray(AgentCore.unwrapResult(json) ?: return emptyList())
             (0 until arr.length()).map { parseDetectedElement(arr.getJSONObject(it)) }
         } catch (e: Exception) {
             emptyList()
@@ -218,8 +245,8 @@ object StrategyEngineNative {
         
         return try {
             val boardJson = JSONArray(board.map { JSONArray(it.toList()) }).toString()
-            val json = findBestEliminateMoveNative(boardJson)
-            if (json == "null" || json.contains("error")) null
+            val json = AgentCore.unwrapResult(findBestEliminateMoveNative(boardJson))
+            if (json == null) null
             else parseEliminateMove(JSONObject(json))
         } catch (e: Exception) {
             Log.e("StrategyEngineNative", "findBestEliminateMove error: ${e.message}")
@@ -235,7 +262,8 @@ object StrategyEngineNative {
         
         return try {
             val boardJson = JSONArray(board.map { JSONArray(it.toList()) }).toString()
-            val json = findBestEliminateMovesNative(boardJson, n)
+            val json = AgentCore.unwrapResult(findBestEliminateMovesNative(boardJson, n))
+                ?: return emptyList()
             val arr = JSONArray(json)
             (0 until arr.length()).map { parseEliminateMove(arr.getJSONObject(it)) }
         } catch (e: Exception) {
@@ -261,10 +289,10 @@ object StrategyEngineNative {
         
         return try {
             val obstaclesJson = JSONArray(obstacles.map { JSONArray(listOf(it.x, it.y)) }).toString()
-            val json = findPathNative(
+            val json = AgentCore.unwrapResult(findPathNative(
                 start.x, start.y, goal.x, goal.y,
                 obstaclesJson, gridWidth, gridHeight, use8Dir
-            )
+            )) ?: return PathResult(emptyList(), -1, false)
             parsePathResult(JSONObject(json))
         } catch (e: Exception) {
             Log.e("StrategyEngineNative", "findPath error: ${e.message}")
@@ -294,10 +322,10 @@ object StrategyEngineNative {
             }).toString()
             val skillJson = JSONArray(skillReady).toString()
             
-            val json = analyzeCombatNative(
+            val json = AgentCore.unwrapResult(analyzeCombatNative(
                 selfPos.x, selfPos.y, selfHpPercent,
                 enemiesJson, alliesJson, skillJson, inTowerRange
-            )
+            )) ?: return emptyList()
             
             val arr = JSONArray(json)
             (0 until arr.length()).map { parseCombatDecision(arr.getJSONObject(it)) }
@@ -402,7 +430,7 @@ object MemoryEngineNative {
         if (!AgentCore.isAvailable()) return emptyList()
         
         return try {
-            val json = parseMemoryMapsNative(pid)
+            val json = AgentCore.unwrapResult(parseMemoryMapsNative(pid)) ?: return emptyList()
             val arr = JSONArray(json)
             (0 until arr.length()).map { i ->
                 val obj = arr.getJSONObject(i)
@@ -500,8 +528,8 @@ object MemoryEngineNative {
         if (!AgentCore.isAvailable()) return null
         
         return try {
-            val json = parseUnityStatsNative(data)
-            if (json == "null" || json.contains("error")) null
+            val json = AgentCore.unwrapResult(parseUnityStatsNative(data))
+            if (json == null) null
             else {
                 val obj = JSONObject(json)
                 UnityStats(
@@ -523,8 +551,8 @@ object MemoryEngineNative {
         if (!AgentCore.isAvailable()) return null
         
         return try {
-            val json = parsePositionNative(data)
-            if (json == "null" || json.contains("error")) null
+            val json = AgentCore.unwrapResult(parsePositionNative(data))
+            if (json == null) null
             else {
                 val obj = JSONObject(json)
                 Position3D(
@@ -556,7 +584,7 @@ object MemoryEngineNative {
     
     private fun parsePatternMatches(json: String): List<PatternMatch> {
         return try {
-            val arr = JSONArray(json)
+            val arr = JSONArray(AgentCore.unwrapResult(json) ?: return emptyList())
             (0 until arr.length()).map { i ->
                 val obj = arr.getJSONObject(i)
                 val bytesArr = obj.getJSONArray("matched_bytes")
@@ -0,0 +1,120 @@
+//! Async Jobs - Bookkeeping for long-running background requests
+//! 
+//! Provides:
+//! - Request ID allocation
+//! - Per-request cancellation flags checked by scan loops
+//! - Guards that unregister a job however it exits
+
+use rustc_hash::FxHashMap;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Registry of in-flight background jobs
+pub struct JobRegistry {
+    next_id: AtomicI32,
+    jobs: Mutex<FxHashMap<i32, Arc<AtomicBool>>>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self {
+            next_id: AtomicI32::new(1),
+            jobs: Mutex::new(FxHashMap::default()),
+        }
+    }
+
+    /// Process-wide registry used by the JNI layer
+    pub fn global() -> &'static JobRegistry {
+        static REGISTRY: OnceLock<JobRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(JobRegistry::new)
+    }
+
+    /// Register a new job, returning its request ID and cancellation flag
+    pub fn register(&self) -> (i32, Arc<AtomicBool>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let flag = Arc::new(AtomicBool::new(false));
+        self.jobs.lock().unwrap().insert(id, flag.clone());
+        (id, flag)
+    }
+
+    /// Request cancellation; returns false if the job is unknown or already finished
+    pub fn cancel(&self, id: i32) -> bool {
+        match self.jobs.lock().unwrap().get(&id) {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget a finished job
+    pub fn finish(&self, id: i32) {
+        self.jobs.lock().unwrap().remove(&id);
+    }
+
+    /// Guard that calls `finish(id)` when dropped, including during a panic
+    pub fn guard(&self, id: i32) -> JobGuard<'_> {
+        JobGuard { registry: self, id }
+    }
+
+    /// Number of jobs still running
+    pub fn active_count(&self) -> usize {
+        self.jobs.lock().unwrap().len()
+    }
+}
+
+impl Default for JobRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Keeps a job registered for as long as it's alive
+pub struct JobGuard<'a> {
+    registry: &'a JobRegistry,
+    id: i32,
+}
+
+impl Drop for JobGuard<'_> {
+    fn drop(&mut self) {
+        // A poisoned map still has to lose the entry
+        let mut jobs = self.registry.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        jobs.remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_flag_plumbing() {
+        let registry = JobRegistry::new();
+        let (id1, flag1) = registry.register();
+        let (id2, flag2) = registry.register();
+        assert_ne!(id1, id2);
+        assert_eq!(registry.active_count(), 2);
+
+        assert!(registry.cancel(id1));
+        assert!(flag1.load(Ordering::Relaxed));
+        assert!(!flag2.load(Ordering::Relaxed));
+
+        registry.finish(id1);
+        assert!(!registry.cancel(id1));
+        assert_eq!(registry.active_count(), 1);
+    }
+
+    #[test]
+    fn test_guard_finishes_panicking_job() {
+        let registry = JobRegistry::new();
+        let (id, _) = registry.register();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _guard = registry.guard(id);
+            panic!("job failed");
+        }));
+        assert!(result.is_err());
+        assert_eq!(registry.active_count(), 0);
+        assert!(!registry.cancel(id));
+    }
+}
@@ -9,7 +9,7 @@
 use rayon::prelude::*;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
-use std::cmp::Ordering;
+use std::cmp::Reverse;
 
 /// RGB color representation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -99,6 +99,26 @@ impl Hsv {
     pub fn is_bright(&self) -> bool {
         self.v > 0.7 && self.s < 0.3
     }
+
+    /// Convert back to RGB
+    /// Hue wraps into [0, 360); s and v are clamped to [0, 1] against float drift.
+    pub fn to_rgb(&self) -> Rgb {
+        let (s, v) = (self.s.clamp(0.0, 1.0), self.v.clamp(0.0, 1.0));
+        let c = v * s;
+        let h = self.h.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = v - c;
+        let channel = |v: f32| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+        Rgb::new(channel(r), channel(g), channel(b))
+    }
 }
 
 /// Rectangle region
@@ -134,6 +154,35 @@ impl Rect {
     pub fn area(&self) -> i32 {
         self.width * self.height
     }
+
+    /// Scale the rect (e.g. from a downscaled frame back to full resolution).
+    /// Edges are scaled and rounded to the nearest pixel, so adjacent rects stay adjacent.
+    pub fn scale(&self, sx: f32, sy: f32) -> Rect {
+        let left = (self.x as f32 * sx).round() as i32;
+        let top = (self.y as f32 * sy).round() as i32;
+        let right = ((self.x + self.width) as f32 * sx).round() as i32;
+        let bottom = ((self.y + self.height) as f32 * sy).round() as i32;
+        Rect::new(left, top, right - left, bottom - top)
+    }
+
+    /// Offset the rect (e.g. from crop coordinates back to frame coordinates)
+    pub fn translate(&self, dx: i32, dy: i32) -> Rect {
+        Rect::new(self.x + dx, self.y + dy, self.width, self.height)
+    }
+
+    /// Intersection over union with another rect (0.0 when disjoint)
+    pub fn iou(&self, other: &Rect) -> f32 {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        if right <= left || bottom <= top {
+            return 0.0;
+        }
+
+        let intersection = ((right - left) * (bottom - top)) as f32;
+        intersection / ((self.area() + other.area()) as f32 - intersection)
+    }
 }
 
 /// Detected element in image
@@ -143,6 +192,31 @@ pub struct DetectedElement {
     pub bounds: Rect,
     pub confidence: f32,
     pub extra_data: Option<String>,
+    #[serde(default)]
+    pub state: Option<ButtonState>,
+    /// Share of a health bar's track still filled (0-1); None for other elements
+    #[serde(default)]
+    pub fill_percent: Option<f32>,
+}
+
+/// Which end of a health bar stays filled as it depletes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FillDirection {
+    /// Anchored on the left, empties from the right (most games)
+    #[default]
+    LeftToRight,
+    /// Anchored on the right, empties from the left
+    RightToLeft,
+}
+
+/// Visual state of a button
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ButtonState {
+    Normal,
+    Pressed,
+    Disabled,
+    Highlighted,
+    CooldownActive,
 }
 
 /// Types of detectable elements
@@ -159,31 +233,165 @@ pub enum ElementType {
     Unknown,
 }
 
+/// Orientation of grid lines searched by `find_grid_lines`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineDirection {
+    Horizontal,
+    Vertical,
+}
+
+/// Thresholds for classifying eliminate pieces by color
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassifyConfig {
+    /// Pixels darker than this V are treated as empty/blocker cells
+    pub dark_threshold: f32,
+    /// Hue upper bounds for red, orange, yellow, green, cyan, blue, and the
+    /// start of red again (purple lies between the last two)
+    pub hue_bounds: [f32; 7],
+}
+
+impl Default for ClassifyConfig {
+    fn default() -> Self {
+        Self {
+            dark_threshold: 0.2,
+            hue_bounds: [30.0, 60.0, 90.0, 150.0, 210.0, 270.0, 330.0],
+        }
+    }
+}
+
+/// Which detectors `analyze_full` runs and how it preprocesses the frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectionConfig {
+    pub detect_health_bars: bool,
+    pub detect_skill_buttons: bool,
+    pub detect_joystick: bool,
+    /// Gaussian blur applied once before HSV conversion (None = no blur)
+    pub blur_sigma: Option<f32>,
+    /// Overlapping detections with IoU above this are suppressed
+    pub nms_iou_threshold: f32,
+    /// How health bars deplete, for `DetectedElement::fill_percent`
+    #[serde(default)]
+    pub fill_direction: FillDirection,
+}
+
+impl Default for DetectionConfig {
+    fn default() -> Self {
+        Self {
+            detect_health_bars: true,
+            detect_skill_buttons: true,
+            detect_joystick: true,
+            blur_sigma: None,
+            nms_iou_threshold: 0.5,
+            fill_direction: FillDirection::LeftToRight,
+        }
+    }
+}
+
+/// Combined output of `analyze_full`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisResult {
+    pub health_bars: Vec<DetectedElement>,
+    pub skill_buttons: Vec<DetectedElement>,
+    pub joystick: Option<DetectedElement>,
+    pub elapsed_ms: f32,
+}
+
+/// Kind of full-screen UI layered over gameplay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverlayType {
+    DeathScreen,
+    PauseMenu,
+    ScoreScreen,
+    SkillUpgradeMenu,
+    AdsOverlay,
+    Unknown,
+}
+
+/// Large uniform region covering the game view; combat and movement should
+/// pause while any is present
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlayDetection {
+    pub bounds: Rect,
+    pub overlay_type: OverlayType,
+    /// Fraction of `bounds` covered by uniform blocks (0-1)
+    pub opacity: f32,
+}
+
+/// Byte layout of raw pixel buffers handed to `ImageData::from_bytes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PixelFormat {
+    /// `[A, R, G, B]` (Android Bitmap)
+    Argb,
+    /// `[R, G, B, A]`
+    Rgba,
+    /// `[B, G, R, A]` (some MediaProjection encoders)
+    Bgra,
+    /// `[R, G, B]`
+    Rgb,
+}
+
+impl PixelFormat {
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Rgb => 3,
+            PixelFormat::Argb | PixelFormat::Rgba | PixelFormat::Bgra => 4,
+        }
+    }
+
+    /// Byte offsets of red, green and blue within one pixel
+    fn rgb_offsets(self) -> (usize, usize, usize) {
+        match self {
+            PixelFormat::Argb => (1, 2, 3),
+            PixelFormat::Rgba | PixelFormat::Rgb => (0, 1, 2),
+            PixelFormat::Bgra => (2, 1, 0),
+        }
+    }
+}
+
 /// Image data wrapper for processing
 pub struct ImageData {
     pub width: usize,
     pub height: usize,
     pub pixels: Vec<Rgb>,
+    /// Per-pixel alpha, when the source had one and it was kept
+    pub alpha: Option<Vec<u8>>,
 }
 
 impl ImageData {
+    /// Create from a raw byte array in any `PixelFormat`; alpha is dropped
+    pub fn from_bytes(data: &[u8], width: usize, height: usize, format: PixelFormat) -> Self {
+        let (r, g, b) = format.rgb_offsets();
+        let pixels = data
+            .chunks_exact(format.bytes_per_pixel())
+            .map(|chunk| Rgb::new(chunk[r], chunk[g], chunk[b]))
+            .collect();
+        Self { width, height, pixels, alpha: None }
+    }
+
     /// Create from raw ARGB byte array (Android Bitmap format)
     pub fn from_argb_bytes(data: &[u8], width: usize, height: usize) -> Self {
+        Self::from_bytes(data, width, height, PixelFormat::Argb)
+    }
+
+    /// Create from raw BGRA byte array
+    pub fn from_bgra_bytes(data: &[u8], width: usize, height: usize) -> Self {
+        Self::from_bytes(data, width, height, PixelFormat::Bgra)
+    }
+
+    /// Create from raw ARGB byte array, keeping the alpha channel
+    pub fn from_argb_bytes_with_alpha(data: &[u8], width: usize, height: usize) -> Self {
         let mut pixels = Vec::with_capacity(width * height);
+        let mut alpha = Vec::with_capacity(width * height);
         for chunk in data.chunks_exact(4) {
-            // ARGB format: [A, R, G, B]
+            alpha.push(chunk[0]);
             pixels.push(Rgb::new(chunk[1], chunk[2], chunk[3]));
         }
-        Self { width, height, pixels }
+        Self { width, height, pixels, alpha: Some(alpha) }
     }
 
     /// Create from raw RGB byte array
     pub fn from_rgb_bytes(data: &[u8], width: usize, height: usize) -> Self {
-        let mut pixels = Vec::with_capacity(width * height);
-        for chunk in data.chunks_exact(3) {
-            pixels.push(Rgb::new(chunk[0], chunk[1], chunk[2]));
-        }
-        Self { width, height, pixels }
+        Self::from_bytes(data, width, height, PixelFormat::Rgb)
     }
 
     /// Get pixel at coordinates
@@ -197,83 +405,671 @@ impl ImageData {
     }
 
     /// Get pixel at coordinates (unsafe, no bounds check)
+    ///
+    /// # Safety
+    /// `x` must be less than `width` and `y` less than `height`.
     #[inline]
     pub unsafe fn get_pixel_unchecked(&self, x: usize, y: usize) -> &Rgb {
         self.pixels.get_unchecked(y * self.width + x)
     }
+
+    /// Alpha at coordinates (opaque when no alpha channel was kept)
+    #[inline]
+    pub fn get_alpha(&self, x: usize, y: usize) -> u8 {
+        match &self.alpha {
+            Some(alpha) if x < self.width && y < self.height => {
+                alpha.get(y * self.width + x).copied().unwrap_or(255)
+            }
+            _ => 255,
+        }
+    }
+
+    /// Whether the pixel is fully transparent (a gap in an overlay)
+    #[inline]
+    pub fn is_transparent(&self, x: usize, y: usize) -> bool {
+        self.get_alpha(x, y) == 0
+    }
+
+    /// Alpha-blend `top` onto this image with its top-left at `at`, clipped to bounds
+    /// `alpha` (0-1) scales `top`'s own per-pixel alpha, if it has one.
+    pub fn composite_over(&mut self, top: &ImageData, at: (i32, i32), alpha: f32) {
+        let alpha = alpha.clamp(0.0, 1.0);
+        let (ox, oy) = at;
+
+        for ty in 0..top.height {
+            let y = oy + ty as i32;
+            if y < 0 || y >= self.height as i32 {
+                continue;
+            }
+            for tx in 0..top.width {
+                let x = ox + tx as i32;
+                if x < 0 || x >= self.width as i32 {
+                    continue;
+                }
+
+                let a = alpha * top.get_alpha(tx, ty) as f32 / 255.0;
+                let src = &top.pixels[ty * top.width + tx];
+                let dst = &mut self.pixels[y as usize * self.width + x as usize];
+                let blend = |s: u8, d: u8| (s as f32 * a + d as f32 * (1.0 - a)).round() as u8;
+                *dst = Rgb::new(blend(src.r, dst.r), blend(src.g, dst.g), blend(src.b, dst.b));
+            }
+        }
+    }
+
+    /// Color at fractional pixel coordinates, blending the four surrounding pixels
+    /// Pixel (x, y) sits at exactly (x.0, y.0); None outside [0, width-1] x [0, height-1].
+    pub fn sample_bilinear(&self, x: f32, y: f32) -> Option<Rgb> {
+        if self.width == 0 || self.height == 0 || !(x >= 0.0 && y >= 0.0) {
+            return None;
+        }
+        if x > (self.width - 1) as f32 || y > (self.height - 1) as f32 {
+            return None;
+        }
+
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+        let p = |px: usize, py: usize| self.pixels[py * self.width + px];
+        let (tl, tr, bl, br) = (p(x0, y0), p(x1, y0), p(x0, y1), p(x1, y1));
+        let lerp = |a: u8, b: u8, c: u8, d: u8| {
+            let top = a as f32 + (b as f32 - a as f32) * fx;
+            let bottom = c as f32 + (d as f32 - c as f32) * fx;
+            (top + (bottom - top) * fy).round() as u8
+        };
+
+        Some(Rgb::new(
+            lerp(tl.r, tr.r, bl.r, br.r),
+            lerp(tl.g, tr.g, bl.g, br.g),
+            lerp(tl.b, tr.b, bl.b, br.b),
+        ))
+    }
+
+    /// `(x, y, pixel)` for every pixel of `rect` clipped to the image, row by row
+    pub fn pixels_in<'a>(&'a self, rect: &Rect) -> impl Iterator<Item = (usize, usize, &'a Rgb)> + 'a {
+        let (x0, y0, x1, y1) = ImageEngine::clamp_region(self, rect);
+        (y0..y1).flat_map(move |y| {
+            self.pixels[y * self.width + x0..y * self.width + x1]
+                .iter()
+                .enumerate()
+                .map(move |(i, p)| (x0 + i, y, p))
+        })
+    }
+}
+
+/// Typical mean V of a skill button in its normal state
+const DEFAULT_BUTTON_V: f32 = 0.8;
+
+/// Side of the Gaussian window used by `ssim`
+const SSIM_WINDOW: usize = 11;
+
+/// Size of the digit templates used by `recognize_digit_sequence`
+const DIGIT_WIDTH: usize = 8;
+const DIGIT_HEIGHT: usize = 12;
+
+/// Minimum fraction of template cells a glyph must agree on to count as a digit
+const DIGIT_MIN_SIMILARITY: f32 = 0.75;
+
+/// Per-pixel color distance that `detect_number_regions` treats as a change
+const NUMBER_DIFF_THRESHOLD: u32 = 40;
+
+/// Brightest V an empty health bar track may have
+const BAR_TRACK_MAX_V: f32 = 0.45;
+
+/// Max V spread within one column of an empty health bar track
+const BAR_TRACK_V_TOLERANCE: f32 = 0.1;
+
+/// Min V difference between an empty track and the pixels just above/below it
+/// (its frame); dark columns without it are background, not track
+const BAR_TRACK_EDGE_MIN_V: f32 = 0.08;
+
+/// Largest mean luminance gap (0-255) between a window and the template that `match_template` still scores
+const TEMPLATE_MAX_MEAN_DIFF: f64 = 64.0;
+
+/// Side of the blocks `detect_overlays` measures variance over
+const OVERLAY_BLOCK: usize = 16;
+/// Max luminance std-dev for a block to count as flat overlay fill
+const OVERLAY_MAX_STD: f32 = 10.0;
+/// Max squared RGB distance between a block and its region's seed block
+const OVERLAY_COLOR_TOLERANCE: u32 = 30 * 30;
+/// Fraction of the frame an overlay must cover
+const OVERLAY_MIN_COVERAGE: f32 = 0.4;
+
+/// Mean luminance step a row/column boundary needs to count as a grid line
+const GRID_LINE_MIN_STRENGTH: u8 = 20;
+
+/// Per-pixel RGB distance that counts as motion for `MotionTracker`
+const MOTION_DIFF_THRESHOLD: u32 = 40;
+
+/// Change regions this close are one object (the spot it left and the spot it entered)
+const MOTION_MERGE_GAP: i32 = 16;
+
+/// Furthest (Chebyshev, px) a centroid may move between frames and stay on its track
+const MOTION_MAX_JUMP: i32 = 64;
+
+/// Frames a track survives without a matching change region
+const MOTION_MAX_MISSES: usize = 2;
+
+const fn digit_glyph(rows: [&[u8; DIGIT_WIDTH]; DIGIT_HEIGHT]) -> [bool; DIGIT_WIDTH * DIGIT_HEIGHT] {
+    let mut glyph = [false; DIGIT_WIDTH * DIGIT_HEIGHT];
+    let mut y = 0;
+    while y < DIGIT_HEIGHT {
+        let mut x = 0;
+        while x < DIGIT_WIDTH {
+            glyph[y * DIGIT_WIDTH + x] = rows[y][x] == b'#';
+            x += 1;
+        }
+        y += 1;
+    }
+    glyph
+}
+
+/// Built-in 8x12 glyphs for 0-9, row-major, `true` = stroke
+static DIGIT_TEMPLATES: [&[bool]; 10] = [
+    &digit_glyph([
+        b"..####..",
+        b".##..##.",
+        b"##....##",
+        b"##....##",
+        b"##....##",
+        b"##....##",
+        b"##....##",
+        b"##....##",
+        b"##....##",
+        b"##....##",
+        b".##..##.",
+        b"..####..",
+    ]),
+    &digit_glyph([
+        b"...##...",
+        b"..###...",
+        b".####...",
+        b"...##...",
+        b"...##...",
+        b"...##...",
+        b"...##...",
+        b"...##...",
+        b"...##...",
+        b"...##...",
+        b"...##...",
+        b".######.",
+    ]),
+    &digit_glyph([
+        b"..####..",
+        b".##..##.",
+        b"##....##",
+        b"......##",
+        b".....##.",
+        b"....##..",
+        b"...##...",
+        b"..##....",
+        b".##.....",
+        b"##......",
+        b"##......",
+        b"########",
+    ]),
+    &digit_glyph([
+        b"..####..",
+        b".##..##.",
+        b"......##",
+        b"......##",
+        b".....##.",
+        b"...###..",
+        b".....##.",
+        b"......##",
+        b"......##",
+        b"##....##",
+        b".##..##.",
+        b"..####..",
+    ]),
+    &digit_glyph([
+        b".....##.",
+        b"....###.",
+        b"...####.",
+        b"..##.##.",
+        b".##..##.",
+        b"##...##.",
+        b"########",
+        b".....##.",
+        b".....##.",
+        b".....##.",
+        b".....##.",
+        b".....##.",
+    ]),
+    &digit_glyph([
+        b"########",
+        b"##......",
+        b"##......",
+        b"##......",
+        b"######..",
+        b".....##.",
+        b"......##",
+        b"......##",
+        b"......##",
+        b"##....##",
+        b".##..##.",
+        b"..####..",
+    ]),
+    &digit_glyph([
+        b"..####..",
+        b".##.....",
+        b"##......",
+        b"##......",
+        b"######..",
+        b"###..##.",
+        b"##....##",
+        b"##....##",
+        b"##....##",
+        b"##....##",
+        b".##..##.",
+        b"..####..",
+    ]),
+    &digit_glyph([
+        b"########",
+        b"......##",
+        b"......##",
+        b".....##.",
+        b".....##.",
+        b"....##..",
+        b"....##..",
+        b"...##...",
+        b"...##...",
+        b"..##....",
+        b"..##....",
+        b"..##....",
+    ]),
+    &digit_glyph([
+        b"..####..",
+        b".##..##.",
+        b"##....##",
+        b"##....##",
+        b".##..##.",
+        b"..####..",
+        b".##..##.",
+        b"##....##",
+        b"##....##",
+        b"##....##",
+        b".##..##.",
+        b"..####..",
+    ]),
+    &digit_glyph([
+        b"..####..",
+        b".##..##.",
+        b"##....##",
+        b"##....##",
+        b"##....##",
+        b".##..###",
+        b"..######",
+        b"......##",
+        b"......##",
+        b"......##",
+        b".....##.",
+        b"..####..",
+    ]),
+];
+
+/// Reusable per-frame buffers for the detectors
+/// Buffers only grow, so once warmed up on a frame size the `_with`
+/// detector variants allocate nothing for HSV conversion or flood fill.
+#[derive(Debug, Default)]
+pub struct ImageScratch {
+    hsv: Vec<Hsv>,
+    visited: Vec<bool>,
+}
+
+impl ImageScratch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Convert `image` into the HSV buffer and size the visited mask for it
+    fn prepare(&mut self, image: &ImageData) {
+        self.hsv.clear();
+        self.hsv.par_extend(image.pixels.par_iter().map(|rgb| rgb.to_hsv()));
+        self.visited.clear();
+        self.visited.resize(image.width * image.height, false);
+    }
 }
 
 /// Image processing engine
 pub struct ImageEngine;
 
 impl ImageEngine {
+    /// Run the configured detectors sharing one (optionally blurred) HSV conversion
+    pub fn analyze_full(image: &ImageData, config: &DetectionConfig) -> AnalysisResult {
+        Self::analyze_full_with(&mut ImageScratch::new(), image, config)
+    }
+
+    /// `analyze_full` reusing the buffers in `scratch`
+    pub fn analyze_full_with(scratch: &mut ImageScratch, image: &ImageData, config: &DetectionConfig) -> AnalysisResult {
+        let start = std::time::Instant::now();
+
+        let blurred = config.blur_sigma
+            .filter(|&sigma| sigma > 0.0)
+            .map(|sigma| Self::gaussian_blur(image, sigma));
+        scratch.prepare(blurred.as_ref().unwrap_or(image));
+        let (hsv_image, visited) = (&scratch.hsv, &mut scratch.visited);
+
+        let health_bars = if config.detect_health_bars {
+            let bars = Self::health_bars_from_hsv(hsv_image, visited, image.width, image.height, config.fill_direction);
+            Self::non_max_suppression(bars, config.nms_iou_threshold)
+        } else {
+            Vec::new()
+        };
+
+        let skill_buttons = if config.detect_skill_buttons {
+            let buttons = Self::skill_buttons_from_hsv(image, hsv_image, visited);
+            Self::non_max_suppression(buttons, config.nms_iou_threshold)
+        } else {
+            Vec::new()
+        };
+
+        let joystick = if config.detect_joystick {
+            Self::joystick_from_hsv(hsv_image, visited, image.width, image.height)
+        } else {
+            None
+        };
+
+        AnalysisResult {
+            health_bars,
+            skill_buttons,
+            joystick,
+            elapsed_ms: start.elapsed().as_secs_f32() * 1000.0,
+        }
+    }
+
+    /// Drop detections overlapping a higher-confidence one by more than `iou_threshold`
+    /// Survivors come back in reading order like every other detector.
+    pub fn non_max_suppression(mut elements: Vec<DetectedElement>, iou_threshold: f32) -> Vec<DetectedElement> {
+        elements.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut kept: Vec<DetectedElement> = Vec::with_capacity(elements.len());
+        for element in elements {
+            if kept.iter().all(|k| k.bounds.iou(&element.bounds) <= iou_threshold) {
+                kept.push(element);
+            }
+        }
+        Self::sort_by_position(&mut kept);
+        kept
+    }
+
+    /// Separable Gaussian blur (kernel radius 3 sigma, edges clamped)
+    pub fn gaussian_blur(image: &ImageData, sigma: f32) -> ImageData {
+        let radius = (sigma * 3.0).ceil().max(1.0) as i32;
+        let mut kernel: Vec<f32> = (-radius..=radius)
+            .map(|d| (-(d * d) as f32 / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let sum: f32 = kernel.iter().sum();
+        kernel.iter_mut().for_each(|k| *k /= sum);
+
+        let (width, height) = (image.width, image.height);
+        let blur_pass = |pixels: &[Rgb], horizontal: bool| -> Vec<Rgb> {
+            (0..width * height)
+                .into_par_iter()
+                .map(|idx| {
+                    let (x, y) = ((idx % width) as i32, (idx / width) as i32);
+                    let (mut r, mut g, mut b) = (0.0f32, 0.0f32, 0.0f32);
+                    for (i, k) in kernel.iter().enumerate() {
+                        let d = i as i32 - radius;
+                        let (sx, sy) = if horizontal {
+                            ((x + d).clamp(0, width as i32 - 1), y)
+                        } else {
+                            (x, (y + d).clamp(0, height as i32 - 1))
+                        };
+                        let p = &pixels[sy as usize * width + sx as usize];
+                        r += k * p.r as f32;
+                        g += k * p.g as f32;
+                        b += k * p.b as f32;
+                    }
+                    Rgb::new(r.round() as u8, g.round() as u8, b.round() as u8)
+                })
+                .collect()
+        };
+
+        let horizontal = blur_pass(&image.pixels, true);
+        let pixels = blur_pass(&horizontal, false);
+        ImageData { width, height, pixels, alpha: image.alpha.clone() }
+    }
+
+    /// Multiply HSV saturation by `factor` (clamped to 1.0) to undo washed-out captures
+    pub fn boost_saturation(image: &ImageData, factor: f32) -> ImageData {
+        let pixels = image.pixels
+            .par_iter()
+            .map(|p| {
+                let mut hsv = p.to_hsv();
+                hsv.s = (hsv.s * factor).clamp(0.0, 1.0);
+                hsv.to_rgb()
+            })
+            .collect();
+        ImageData { width: image.width, height: image.height, pixels, alpha: image.alpha.clone() }
+    }
+
+    /// Stretch levels so the 5th luminance percentile maps to 0 and the 95th to 255
+    /// The same linear map is applied to every channel, so hues are preserved.
+    /// Images with no spread between the percentiles are returned unchanged.
+    pub fn normalize_brightness(image: &ImageData) -> ImageData {
+        let mut histogram = [0usize; 256];
+        for p in &image.pixels {
+            let luma = 0.299 * p.r as f32 + 0.587 * p.g as f32 + 0.114 * p.b as f32;
+            histogram[luma.round() as usize] += 1;
+        }
+
+        let percentile = |fraction: f32| {
+            let target = (image.pixels.len() as f32 * fraction).ceil().max(1.0) as usize;
+            let mut seen = 0;
+            histogram.iter().position(|&count| {
+                seen += count;
+                seen >= target
+            }).unwrap_or(255) as f32
+        };
+        let (low, high) = (percentile(0.05), percentile(0.95));
+        if high <= low {
+            return Self::adjust_contrast(image, 1.0, 0);
+        }
+
+        let alpha = 255.0 / (high - low);
+        let pixels = image.pixels
+            .par_iter()
+            .map(|p| {
+                let channel = |c: u8| ((c as f32 - low) * alpha).round().clamp(0.0, 255.0) as u8;
+                Rgb::new(channel(p.r), channel(p.g), channel(p.b))
+            })
+            .collect();
+        ImageData { width: image.width, height: image.height, pixels, alpha: image.alpha.clone() }
+    }
+
+    /// `pixel = alpha * pixel + beta` per channel, clamped to 0-255
+    pub fn adjust_contrast(image: &ImageData, alpha: f32, beta: i32) -> ImageData {
+        let pixels = image.pixels
+            .par_iter()
+            .map(|p| {
+                let channel = |c: u8| (alpha * c as f32 + beta as f32).round().clamp(0.0, 255.0) as u8;
+                Rgb::new(channel(p.r), channel(p.g), channel(p.b))
+            })
+            .collect();
+        ImageData { width: image.width, height: image.height, pixels, alpha: image.alpha.clone() }
+    }
+
+    /// Sort top-to-bottom, then left-to-right, so output order never depends on
+    /// scan or thread scheduling; ties keep their detection order
+    fn sort_by_position(elements: &mut [DetectedElement]) {
+        elements.sort_by_key(|e| (e.bounds.y, e.bounds.x));
+    }
+
     /// Detect health bars in image (parallel processing)
     pub fn detect_health_bars(image: &ImageData) -> Vec<DetectedElement> {
+        Self::detect_health_bars_with(&mut ImageScratch::new(), image)
+    }
+
+    /// `detect_health_bars` reusing the buffers in `scratch`
+    pub fn detect_health_bars_with(scratch: &mut ImageScratch, image: &ImageData) -> Vec<DetectedElement> {
+        // Convert to HSV and find colored regions
+        scratch.prepare(image);
+        Self::health_bars_from_hsv(&scratch.hsv, &mut scratch.visited, image.width, image.height, FillDirection::default())
+    }
+
+    /// `detect_health_bars` for games whose bars deplete the other way
+    pub fn detect_health_bars_with_direction(image: &ImageData, direction: FillDirection) -> Vec<DetectedElement> {
+        let mut scratch = ImageScratch::new();
+        scratch.prepare(image);
+        Self::health_bars_from_hsv(&scratch.hsv, &mut scratch.visited, image.width, image.height, direction)
+    }
+
+    /// Detected bars' `bounds` cover the colored part; `fill_percent` compares it
+    /// with the whole track, see `bar_fill_from_hsv`
+    fn health_bars_from_hsv(
+        hsv_image: &[Hsv],
+        visited: &mut [bool],
+        width: usize,
+        height: usize,
+        direction: FillDirection,
+    ) -> Vec<DetectedElement> {
         let mut results = Vec::new();
-        
+
         // Scan for horizontal colored bars
         // Health bars are typically 50-300px wide, 5-20px tall
         let min_bar_width = 50;
         let max_bar_height = 25;
-        
-        // Convert to HSV and find colored regions
-        let hsv_image: Vec<Hsv> = image.pixels.par_iter()
-            .map(|rgb| rgb.to_hsv())
-            .collect();
 
         // Find red bars (enemy health)
-        let red_regions = Self::find_colored_regions(&hsv_image, image.width, image.height, 
+        let red_regions = Self::find_colored_regions(hsv_image, visited, width, height,
             |hsv| hsv.is_red(), min_bar_width, max_bar_height);
-        for region in red_regions {
+        for (region, confidence) in red_regions {
             results.push(DetectedElement {
                 element_type: ElementType::HealthBarEnemy,
                 bounds: region,
-                confidence: 0.85,
+                confidence,
                 extra_data: None,
+                state: None,
+                fill_percent: Some(Self::bar_fill_from_hsv(hsv_image, width, &region, direction)),
             });
         }
 
         // Find blue bars (ally health)
-        let blue_regions = Self::find_colored_regions(&hsv_image, image.width, image.height,
+        let blue_regions = Self::find_colored_regions(hsv_image, visited, width, height,
             |hsv| hsv.is_blue(), min_bar_width, max_bar_height);
-        for region in blue_regions {
+        for (region, confidence) in blue_regions {
             results.push(DetectedElement {
                 element_type: ElementType::HealthBarAlly,
                 bounds: region,
-                confidence: 0.85,
+                confidence,
                 extra_data: None,
+                state: None,
+                fill_percent: Some(Self::bar_fill_from_hsv(hsv_image, width, &region, direction)),
             });
         }
 
         // Find green bars (self health)
-        let green_regions = Self::find_colored_regions(&hsv_image, image.width, image.height,
+        let green_regions = Self::find_colored_regions(hsv_image, visited, width, height,
             |hsv| hsv.is_green(), min_bar_width, max_bar_height);
-        for region in green_regions {
+        for (region, confidence) in green_regions {
             results.push(DetectedElement {
                 element_type: ElementType::HealthBarSelf,
                 bounds: region,
-                confidence: 0.85,
+                confidence,
                 extra_data: None,
+                state: None,
+                fill_percent: Some(Self::bar_fill_from_hsv(hsv_image, width, &region, direction)),
             });
         }
 
+        Self::sort_by_position(&mut results);
         results
     }
 
+    /// Fraction of a bar's track covered by its colored part `bar`
+    /// The empty remainder is walked from the depleting end of `bar`: columns
+    /// that are uniformly dark across the bar's rows (V below `BAR_TRACK_MAX_V`)
+    /// and stand out from the pixels just above or below (the track's frame)
+    /// count as track. The walk stops where the frame ends, so dark background
+    /// beside a full bar isn't read as empty track. A bar with no track reads 1.0.
+    fn bar_fill_from_hsv(hsv_image: &[Hsv], width: usize, bar: &Rect, direction: FillDirection) -> f32 {
+        let height = hsv_image.len() / width.max(1);
+        let top = bar.y.max(0) as usize;
+        let bottom = ((bar.y + bar.height).max(0) as usize).min(height);
+        let is_track = |x: i32| {
+            if x < 0 || x >= width as i32 || top >= bottom {
+                return false;
+            }
+            let v_at = |y: usize| hsv_image[y * width + x as usize].v;
+            let first_v = v_at(top);
+            let uniform_dark = (top..bottom).all(|y| v_at(y) < BAR_TRACK_MAX_V && (v_at(y) - first_v).abs() <= BAR_TRACK_V_TOLERANCE);
+            let framed = [top.checked_sub(1), (bottom < height).then_some(bottom)]
+                .into_iter()
+                .flatten()
+                .any(|y| (v_at(y) - first_v).abs() > BAR_TRACK_EDGE_MIN_V);
+            uniform_dark && framed
+        };
+
+        let (mut x, step) = match direction {
+            FillDirection::LeftToRight => (bar.x + bar.width, 1),
+            FillDirection::RightToLeft => (bar.x - 1, -1),
+        };
+        let mut empty = 0;
+        while is_track(x) {
+            empty += 1;
+            x += step;
+        }
+
+        bar.width as f32 / (bar.width + empty).max(1) as f32
+    }
+
+    /// Number of lit segments in a pip/stack bar ("3 of 5 pips lit")
+    /// A column is lit when most of its pixels have the bar's color: red, blue or
+    /// green for the health bar types, any saturated non-dark color otherwise.
+    /// Unlit runs of at most `gap_tolerance` columns are bridged, not counted as gaps.
+    pub fn count_segments(image: &ImageData, bar: &Rect, color: ElementType, gap_tolerance: i32) -> usize {
+        let is_lit = |hsv: &Hsv| match color {
+            ElementType::HealthBarEnemy => hsv.is_red(),
+            ElementType::HealthBarAlly => hsv.is_blue(),
+            ElementType::HealthBarSelf => hsv.is_green(),
+            _ => hsv.s > 0.4 && hsv.v > 0.3,
+        };
+
+        let (x0, y0, x1, y1) = Self::clamp_region(image, bar);
+        let rows = y1 - y0;
+        if rows == 0 {
+            return 0;
+        }
+
+        let mut segments = 0;
+        let mut gap = 0;
+        let mut in_segment = false;
+        for x in x0..x1 {
+            let lit = (y0..y1).filter(|&y| is_lit(&image.pixels[y * image.width + x].to_hsv())).count();
+            if lit * 2 > rows {
+                if !in_segment {
+                    segments += 1;
+                    in_segment = true;
+                }
+                gap = 0;
+            } else if in_segment {
+                gap += 1;
+                if gap > gap_tolerance {
+                    in_segment = false;
+                }
+            }
+        }
+
+        segments
+    }
+
     /// Find colored regions matching a predicate
+    /// Confidence is the fraction of the bounding box filled by matching pixels.
     fn find_colored_regions<F>(
         hsv_image: &[Hsv],
+        visited: &mut [bool],
         width: usize,
         height: usize,
         predicate: F,
         min_width: usize,
         max_height: usize,
-    ) -> Vec<Rect>
+    ) -> Vec<(Rect, f32)>
     where
         F: Fn(&Hsv) -> bool + Sync,
     {
         let mut regions = Vec::new();
-        let mut visited = vec![false; width * height];
+        visited.fill(false);
 
         for y in 0..height {
             for x in 0..width {
@@ -292,6 +1088,7 @@ impl ImageEngine {
                 let mut max_x = x;
                 let mut min_y = y;
                 let mut max_y = y;
+                let mut pixel_count = 0usize;
                 let mut stack = vec![(x, y)];
 
                 while let Some((cx, cy)) = stack.pop() {
@@ -304,6 +1101,7 @@ impl ImageEngine {
                     }
 
                     visited[cidx] = true;
+                    pixel_count += 1;
                     min_x = min_x.min(cx);
                     max_x = max_x.max(cx);
                     min_y = min_y.min(cy);
@@ -321,11 +1119,10 @@ impl ImageEngine {
 
                 // Filter by size constraints (health bars are wide and short)
                 if region_width >= min_width && region_height <= max_height && region_width > region_height * 3 {
-                    regions.push(Rect::new(
-                        min_x as i32,
-                        min_y as i32,
-                        region_width as i32,
-                        region_height as i32,
+                    let fill_ratio = pixel_count as f32 / (region_width * region_height) as f32;
+                    regions.push((
+                        Rect::new(min_x as i32, min_y as i32, region_width as i32, region_height as i32),
+                        fill_ratio.clamp(0.0, 1.0),
                     ));
                 }
             }
@@ -336,43 +1133,107 @@ impl ImageEngine {
 
     /// Detect skill buttons (circular/rounded elements in right side of screen)
     pub fn detect_skill_buttons(image: &ImageData) -> Vec<DetectedElement> {
+        Self::detect_skill_buttons_with(&mut ImageScratch::new(), image)
+    }
+
+    /// `detect_skill_buttons` reusing the buffers in `scratch`
+    pub fn detect_skill_buttons_with(scratch: &mut ImageScratch, image: &ImageData) -> Vec<DetectedElement> {
+        // Look for bright circular regions
+        scratch.prepare(image);
+        Self::skill_buttons_from_hsv(image, &scratch.hsv, &mut scratch.visited)
+    }
+
+    fn skill_buttons_from_hsv(image: &ImageData, hsv_image: &[Hsv], visited: &mut [bool]) -> Vec<DetectedElement> {
         let mut results = Vec::new();
-        
+
         // Skill buttons are typically in the right 1/3 of the screen
         let search_x_start = image.width * 2 / 3;
-        
-        // Look for bright circular regions
-        let hsv_image: Vec<Hsv> = image.pixels.par_iter()
-            .map(|rgb| rgb.to_hsv())
-            .collect();
 
         // Find bright regions
-        let bright_regions = Self::find_circular_regions(&hsv_image, image.width, image.height,
+        let bright_regions = Self::find_circular_regions(hsv_image, visited, image.width, image.height,
             search_x_start, 40, 120); // 40-120px diameter
 
-        for region in bright_regions {
+        for (region, confidence) in bright_regions {
             results.push(DetectedElement {
                 element_type: ElementType::SkillButton,
                 bounds: region,
-                confidence: 0.75,
+                confidence,
                 extra_data: None,
+                state: Some(Self::detect_button_state(image, &region)),
+                fill_percent: None,
             });
         }
 
+        Self::sort_by_position(&mut results);
         results
     }
 
+    /// Classify a button's visual state assuming a typical normal brightness (V = 0.8)
+    pub fn detect_button_state(image: &ImageData, button_rect: &Rect) -> ButtonState {
+        Self::detect_button_state_relative(image, button_rect, DEFAULT_BUTTON_V)
+    }
+
+    /// Classify a button's visual state against its calibrated normal mean V
+    /// - CooldownActive: a dark sweep overlay covers part (not all) of the button
+    /// - Disabled: desaturated (mean S < 0.15)
+    /// - Highlighted: noticeably brighter than normal
+    /// - Pressed: at least 15% darker than normal
+    pub fn detect_button_state_relative(image: &ImageData, button_rect: &Rect, normal_v: f32) -> ButtonState {
+        let x_start = button_rect.x.max(0) as usize;
+        let y_start = button_rect.y.max(0) as usize;
+        let x_end = ((button_rect.x + button_rect.width).max(0) as usize).min(image.width);
+        let y_end = ((button_rect.y + button_rect.height).max(0) as usize).min(image.height);
+
+        let mut count = 0usize;
+        let mut dark = 0usize;
+        let mut sum_s = 0.0f32;
+        let mut sum_v = 0.0f32;
+        for y in y_start..y_end {
+            for x in x_start..x_end {
+                let hsv = image.pixels[y * image.width + x].to_hsv();
+                count += 1;
+                sum_s += hsv.s;
+                sum_v += hsv.v;
+                if hsv.v < 0.25 {
+                    dark += 1;
+                }
+            }
+        }
+
+        if count == 0 {
+            return ButtonState::Normal;
+        }
+
+        let mean_s = sum_s / count as f32;
+        let mean_v = sum_v / count as f32;
+        let dark_fraction = dark as f32 / count as f32;
+
+        if (0.15..0.95).contains(&dark_fraction) {
+            ButtonState::CooldownActive
+        } else if mean_s < 0.15 {
+            ButtonState::Disabled
+        } else if mean_v >= (normal_v * 1.15).min(0.95) {
+            ButtonState::Highlighted
+        } else if mean_v <= normal_v * 0.85 {
+            ButtonState::Pressed
+        } else {
+            ButtonState::Normal
+        }
+    }
+
     /// Find approximately circular bright regions
+    /// Confidence is how closely the filled area matches a disk of the same diameter.
     fn find_circular_regions(
         hsv_image: &[Hsv],
+        visited: &mut [bool],
         width: usize,
         height: usize,
         x_start: usize,
         min_diameter: usize,
         max_diameter: usize,
-    ) -> Vec<Rect> {
+    ) -> Vec<(Rect, f32)> {
         let mut regions = Vec::new();
-        let mut visited = vec![false; width * height];
+        visited.fill(false);
 
         for y in 0..height {
             for x in x_start..width {
@@ -431,11 +1292,9 @@ impl ImageEngine {
                     && ratio > 0.7 && ratio < 1.4  // Roughly square
                     && area_ratio > 0.5  // Filled enough
                 {
-                    regions.push(Rect::new(
-                        min_x as i32,
-                        min_y as i32,
-                        region_width as i32,
-                        region_height as i32,
+                    regions.push((
+                        Rect::new(min_x as i32, min_y as i32, region_width as i32, region_height as i32),
+                        (1.0 - (1.0 - area_ratio).abs()).clamp(0.0, 1.0),
                     ));
                 }
             }
@@ -446,22 +1305,61 @@ impl ImageEngine {
 
     /// Detect joystick (circular element in left side of screen)
     pub fn detect_joystick(image: &ImageData) -> Option<DetectedElement> {
+        Self::detect_joystick_with(&mut ImageScratch::new(), image)
+    }
+
+    /// `detect_joystick` reusing the buffers in `scratch`
+    pub fn detect_joystick_with(scratch: &mut ImageScratch, image: &ImageData) -> Option<DetectedElement> {
+        scratch.prepare(image);
+        Self::joystick_from_hsv(&scratch.hsv, &mut scratch.visited, image.width, image.height)
+    }
+
+    /// Detect joysticks in both bottom corners (move stick left, aim stick right)
+    /// `extra_data` is `"left"` or `"right"`
+    pub fn detect_joysticks(image: &ImageData) -> Vec<DetectedElement> {
+        Self::detect_joysticks_with(&mut ImageScratch::new(), image)
+    }
+
+    /// `detect_joysticks` reusing the buffers in `scratch`
+    pub fn detect_joysticks_with(scratch: &mut ImageScratch, image: &ImageData) -> Vec<DetectedElement> {
+        scratch.prepare(image);
+        let (width, height) = (image.width, image.height);
+        let mut joysticks = Vec::new();
+        joysticks.extend(Self::joystick_from_hsv(&scratch.hsv, &mut scratch.visited, width, height));
+        joysticks.extend(Self::joystick_in_columns(
+            &scratch.hsv, &mut scratch.visited, width, height, width - width / 3..width, "right",
+        ));
+        joysticks
+    }
+
+    fn joystick_from_hsv(
+        hsv_image: &[Hsv],
+        visited: &mut [bool],
+        width: usize,
+        height: usize,
+    ) -> Option<DetectedElement> {
         // Joystick is in the left 1/3, bottom half of screen
-        let search_x_end = image.width / 3;
-        let search_y_start = image.height / 2;
+        Self::joystick_in_columns(hsv_image, visited, width, height, 0..width / 3, "left")
+    }
 
-        let hsv_image: Vec<Hsv> = image.pixels.par_iter()
-            .map(|rgb| rgb.to_hsv())
-            .collect();
+    fn joystick_in_columns(
+        hsv_image: &[Hsv],
+        visited: &mut [bool],
+        width: usize,
+        height: usize,
+        columns: std::ops::Range<usize>,
+        side: &str,
+    ) -> Option<DetectedElement> {
+        let search_y_start = height / 2;
 
         // Look for large circular region (80-200px diameter)
-        let mut visited = vec![false; image.width * image.height];
-        let mut best_region: Option<Rect> = None;
+        visited.fill(false);
+        let mut best_region: Option<(Rect, f32)> = None;
         let mut best_area = 0;
 
-        for y in search_y_start..image.height {
-            for x in 0..search_x_end {
-                let idx = y * image.width + x;
+        for y in search_y_start..height {
+            for x in columns.clone() {
+                let idx = y * width + x;
                 if visited[idx] {
                     continue;
                 }
@@ -476,10 +1374,11 @@ impl ImageEngine {
                 let mut max_x = x;
                 let mut min_y = y;
                 let mut max_y = y;
+                let mut pixel_count = 0usize;
                 let mut stack = vec![(x, y)];
 
                 while let Some((cx, cy)) = stack.pop() {
-                    let cidx = cy * image.width + cx;
+                    let cidx = cy * width + cx;
                     if visited[cidx] {
                         continue;
                     }
@@ -490,15 +1389,16 @@ impl ImageEngine {
                     }
 
                     visited[cidx] = true;
+                    pixel_count += 1;
                     min_x = min_x.min(cx);
                     max_x = max_x.max(cx);
                     min_y = min_y.min(cy);
                     max_y = max_y.max(cy);
 
                     if cx > 0 { stack.push((cx - 1, cy)); }
-                    if cx + 1 < image.width { stack.push((cx + 1, cy)); }
+                    if cx + 1 < width { stack.push((cx + 1, cy)); }
                     if cy > 0 { stack.push((cx, cy - 1)); }
-                    if cy + 1 < image.height { stack.push((cx, cy + 1)); }
+                    if cy + 1 < height { stack.push((cx, cy + 1)); }
                 }
 
                 let region_width = max_x - min_x + 1;
@@ -507,23 +1407,26 @@ impl ImageEngine {
                 let diameter = region_width.max(region_height);
 
                 let ratio = region_width as f32 / region_height as f32;
-                if diameter >= 80 && diameter <= 200 && ratio > 0.7 && ratio < 1.4 && area > best_area {
+                if (80..=200).contains(&diameter) && ratio > 0.7 && ratio < 1.4 && area > best_area {
                     best_area = area;
-                    best_region = Some(Rect::new(
-                        min_x as i32,
-                        min_y as i32,
-                        region_width as i32,
-                        region_height as i32,
+                    // Circularity: filled area relative to the ellipse inscribed in the bounds
+                    let ellipse_area = std::f32::consts::PI * region_width as f32 * region_height as f32 / 4.0;
+                    let circularity = 1.0 - (1.0 - pixel_count as f32 / ellipse_area).abs();
+                    best_region = Some((
+                        Rect::new(min_x as i32, min_y as i32, region_width as i32, region_height as i32),
+                        circularity.clamp(0.0, 1.0),
                     ));
                 }
             }
         }
 
-        best_region.map(|bounds| DetectedElement {
+        best_region.map(|(bounds, confidence)| DetectedElement {
             element_type: ElementType::Joystick,
             bounds,
-            confidence: 0.80,
-            extra_data: None,
+            confidence,
+            extra_data: Some(side.to_string()),
+            state: None,
+            fill_percent: None,
         })
     }
 
@@ -535,78 +1438,444 @@ impl ImageEngine {
         rows: usize,
         cols: usize,
     ) -> Vec<Vec<u8>> {
-        let cell_width = grid_bounds.width as usize / cols;
-        let cell_height = grid_bounds.height as usize / rows;
+        Self::analyze_eliminate_board_with_config(image, grid_bounds, rows, cols, &ClassifyConfig::default())
+    }
 
-        let mut board = vec![vec![0u8; cols]; rows];
+    /// Analyze eliminate game board with calibrated color classification thresholds
+    pub fn analyze_eliminate_board_with_config(
+        image: &ImageData,
+        grid_bounds: &Rect,
+        rows: usize,
+        cols: usize,
+        config: &ClassifyConfig,
+    ) -> Vec<Vec<u8>> {
+        Self::analyze_eliminate_board_conf(image, grid_bounds, rows, cols, config)
+            .into_iter()
+            .map(|row| row.into_iter().map(|(color, _)| color).collect())
+            .collect()
+    }
 
-        // Parallel process each cell
-        let results: Vec<((usize, usize), u8)> = (0..rows)
-            .into_par_iter()
-            .flat_map(|row| {
-                (0..cols).into_par_iter().map(move |col| {
-                    let cell_x = grid_bounds.x as usize + col * cell_width + cell_width / 2;
-                    let cell_y = grid_bounds.y as usize + row * cell_height + cell_height / 2;
-                    
-                    // Sample center region of cell
-                    let sample_size = 10;
-                    let mut color_counts: FxHashMap<u8, usize> = FxHashMap::default();
-                    
-                    for dy in 0..sample_size {
-                        for dx in 0..sample_size {
-                            let px = cell_x + dx - sample_size / 2;
-                            let py = cell_y + dy - sample_size / 2;
-                            if let Some(rgb) = image.get_pixel(px, py) {
-                                let color_id = Self::classify_chess_color(rgb);
-                                *color_counts.entry(color_id).or_insert(0) += 1;
-                            }
-                        }
+    /// Like `analyze_eliminate_board_with_config`, paired with each cell's confidence
+    /// Confidence is the share of sampled pixels that voted for the winning color,
+    /// so a cell straddling two pieces or an animation lands near 0.5.
+    /// Cell edges snap to nearby lines from `find_grid_lines`, so UI scaling that
+    /// makes cells unequal doesn't drift the samples. The sample square is clipped
+    /// to the cell and the image; a cell with no pixels in view is (0, 0.0).
+    pub fn analyze_eliminate_board_conf(
+        image: &ImageData,
+        grid_bounds: &Rect,
+        rows: usize,
+        cols: usize,
+        config: &ClassifyConfig,
+    ) -> Vec<Vec<(u8, f32)>> {
+        const SAMPLE_SIZE: usize = 10;
+
+        Self::sample_grid_rects(image, grid_bounds, rows, cols, SAMPLE_SIZE, |image, sample| {
+            let mut color_counts: FxHashMap<u8, usize> = FxHashMap::default();
+            for (_, _, rgb) in image.pixels_in(sample) {
+                *color_counts.entry(Self::classify_chess_color(rgb, config)).or_insert(0) += 1;
+            }
+
+            let total: usize = color_counts.values().sum();
+            color_counts
+                .into_iter()
+                .max_by_key(|&(color, count)| (count, std::cmp::Reverse(color)))
+                .map(|(color, count)| (color, count as f32 / total as f32))
+                .unwrap_or((0, 0.0))
+        })
+    }
+
+    /// `count + 1` cell edges over `[start, start + length)`, evenly spaced, with each
+    /// interior edge moved to the closest of `lines` within a quarter cell
+    fn snap_cell_edges(start: i32, length: i32, count: usize, lines: &[i32]) -> Vec<i32> {
+        let cell = length.max(0) / count as i32;
+        let tolerance = cell / 4;
+
+        (0..=count as i32)
+            .map(|i| {
+                let uniform = start + i * cell;
+                if i == 0 || i == count as i32 {
+                    return uniform;
+                }
+                lines
+                    .iter()
+                    .copied()
+                    .filter(|line| (line - uniform).abs() <= tolerance)
+                    .min_by_key(|line| (line - uniform).abs())
+                    .unwrap_or(uniform)
+            })
+            .collect()
+    }
+
+    /// Positions inside `region` where the mean luminance gradient across the
+    /// line peaks (y for horizontal lines, x for vertical), sorted ascending
+    /// A position is the first row/column past the step; steps closer than 3px
+    /// (both edges of a thin drawn line) merge into their strongest one.
+    pub fn find_grid_lines(
+        image: &ImageData,
+        region: &Rect,
+        direction: LineDirection,
+        min_line_strength: u8,
+    ) -> Vec<i32> {
+        let (x0, y0, x1, y1) = Self::clamp_region(image, region);
+        if x1 <= x0 || y1 <= y0 {
+            return Vec::new();
+        }
+
+        let luma = |x: usize, y: usize| {
+            let p = &image.pixels[y * image.width + x];
+            0.299 * p.r as f32 + 0.587 * p.g as f32 + 0.114 * p.b as f32
+        };
+        let strengths: Vec<(usize, f32)> = match direction {
+            LineDirection::Horizontal => (y0 + 1..y1)
+                .map(|y| {
+                    let sum: f32 = (x0..x1).map(|x| (luma(x, y) - luma(x, y - 1)).abs()).sum();
+                    (y, sum / (x1 - x0) as f32)
+                })
+                .collect(),
+            LineDirection::Vertical => (x0 + 1..x1)
+                .map(|x| {
+                    let sum: f32 = (y0..y1).map(|y| (luma(x, y) - luma(x - 1, y)).abs()).sum();
+                    (x, sum / (y1 - y0) as f32)
+                })
+                .collect(),
+        };
+
+        // (last position in group, strongest position, its strength)
+        let mut lines = Vec::new();
+        let mut group: Option<(usize, usize, f32)> = None;
+        for (pos, strength) in strengths {
+            if strength < min_line_strength as f32 {
+                continue;
+            }
+            group = match group {
+                Some((last, best_pos, best)) if pos - last <= 2 => {
+                    Some((pos, if strength > best { pos } else { best_pos }, strength.max(best)))
+                }
+                previous => {
+                    if let Some((_, best_pos, _)) = previous {
+                        lines.push(best_pos as i32);
                     }
+                    Some((pos, pos, strength))
+                }
+            };
+        }
+        if let Some((_, best_pos, _)) = group {
+            lines.push(best_pos as i32);
+        }
+        lines
+    }
+
+    /// Classify every cell of a grid in parallel
+    /// `classify` receives each cell's center pixel, moved inward if needed so
+    /// a `sample_size` square around it stays inside the image.
+    pub fn sample_grid<T: Send>(
+        image: &ImageData,
+        grid_bounds: &Rect,
+        rows: usize,
+        cols: usize,
+        sample_size: usize,
+        classify: impl Fn(&ImageData, usize, usize) -> T + Sync,
+    ) -> Vec<Vec<T>> {
+        if rows == 0 || cols == 0 {
+            return Vec::new();
+        }
 
-                    let dominant_color = color_counts
-                        .into_iter()
-                        .max_by_key(|(_, count)| *count)
-                        .map(|(color, _)| color)
-                        .unwrap_or(0);
+        let cell_width = grid_bounds.width.max(0) as usize / cols;
+        let cell_height = grid_bounds.height.max(0) as usize / rows;
+        let grid_x = grid_bounds.x.max(0) as usize;
+        let grid_y = grid_bounds.y.max(0) as usize;
 
-                    ((row, col), dominant_color)
+        let half = sample_size / 2;
+        let max_x = image.width.saturating_sub(sample_size - half).max(half);
+        let max_y = image.height.saturating_sub(sample_size - half).max(half);
+
+        (0..rows)
+            .into_par_iter()
+            .map(|row| {
+                (0..cols).into_par_iter().map(|col| {
+                    let cell_x = grid_x + col * cell_width + cell_width / 2;
+                    let cell_y = grid_y + row * cell_height + cell_height / 2;
+                    classify(image, cell_x.clamp(half, max_x), cell_y.clamp(half, max_y))
                 })
+                .collect()
             })
-            .collect();
+            .collect()
+    }
 
-        for ((row, col), color) in results {
-            board[row][col] = color;
+    /// Like `sample_grid`, but `classify` receives a sample rectangle
+    /// Cell edges snap to nearby lines from `find_grid_lines`. The rectangle is the
+    /// `sample_size` square at each cell's center, clipped to the cell and to the
+    /// image, so every pixel in it is in bounds (it is empty for a cell entirely
+    /// off-screen).
+    pub fn sample_grid_rects<T: Send>(
+        image: &ImageData,
+        grid_bounds: &Rect,
+        rows: usize,
+        cols: usize,
+        sample_size: usize,
+        classify: impl Fn(&ImageData, &Rect) -> T + Sync,
+    ) -> Vec<Vec<T>> {
+        if rows == 0 || cols == 0 {
+            return Vec::new();
         }
 
-        board
+        let horizontal = Self::find_grid_lines(image, grid_bounds, LineDirection::Horizontal, GRID_LINE_MIN_STRENGTH);
+        let vertical = Self::find_grid_lines(image, grid_bounds, LineDirection::Vertical, GRID_LINE_MIN_STRENGTH);
+        let row_edges = Self::snap_cell_edges(grid_bounds.y, grid_bounds.height, rows, &horizontal);
+        let col_edges = Self::snap_cell_edges(grid_bounds.x, grid_bounds.width, cols, &vertical);
+        let size = sample_size.min(i32::MAX as usize) as i32;
+
+        (0..rows)
+            .into_par_iter()
+            .map(|row| {
+                (0..cols).into_par_iter().map(|col| {
+                    let cell = Rect::new(
+                        col_edges[col],
+                        row_edges[row],
+                        col_edges[col + 1] - col_edges[col],
+                        row_edges[row + 1] - row_edges[row],
+                    );
+
+                    // Center square in signed coordinates, clipped to the cell, then the image
+                    let left = cell.center_x().saturating_sub(size / 2).max(cell.x);
+                    let top = cell.center_y().saturating_sub(size / 2).max(cell.y);
+                    let right = cell.center_x().saturating_add(size - size / 2).min(cell.x + cell.width);
+                    let bottom = cell.center_y().saturating_add(size - size / 2).min(cell.y + cell.height);
+                    let (x0, y0, x1, y1) = Self::clamp_region(image, &Rect::new(left, top, right - left, bottom - top));
+                    let sample = Rect::new(x0 as i32, y0 as i32, (x1 - x0) as i32, (y1 - y0) as i32);
+
+                    classify(image, &sample)
+                })
+                .collect()
+            })
+            .collect()
     }
 
     /// Classify chess piece color into discrete categories
-    fn classify_chess_color(rgb: &Rgb) -> u8 {
+    fn classify_chess_color(rgb: &Rgb, config: &ClassifyConfig) -> u8 {
         let hsv = rgb.to_hsv();
         
-        if hsv.v < 0.2 {
+        if hsv.v < config.dark_threshold {
             return 0; // Empty/dark
         }
 
         // Classify by hue
-        if hsv.h < 30.0 || hsv.h >= 330.0 {
+        let bounds = &config.hue_bounds;
+        if hsv.h < bounds[0] || hsv.h >= bounds[6] {
             1 // Red
-        } else if hsv.h < 60.0 {
+        } else if hsv.h < bounds[1] {
             2 // Orange
-        } else if hsv.h < 90.0 {
+        } else if hsv.h < bounds[2] {
             3 // Yellow
-        } else if hsv.h < 150.0 {
+        } else if hsv.h < bounds[3] {
             4 // Green
-        } else if hsv.h < 210.0 {
+        } else if hsv.h < bounds[4] {
             5 // Cyan
-        } else if hsv.h < 270.0 {
+        } else if hsv.h < bounds[5] {
             6 // Blue
         } else {
             7 // Purple
         }
     }
 
+    /// Mean color of `region` (clamped to the image); black if it's empty
+    pub fn average_color(image: &ImageData, region: &Rect) -> Rgb {
+        let (x0, y0, x1, y1) = Self::clamp_region(image, region);
+        let count = ((x1 - x0) * (y1 - y0)) as u64;
+        if count == 0 {
+            return Rgb::new(0, 0, 0);
+        }
+
+        let (mut r, mut g, mut b) = (0u64, 0u64, 0u64);
+        for (_, _, p) in image.pixels_in(region) {
+            r += p.r as u64;
+            g += p.g as u64;
+            b += p.b as u64;
+        }
+
+        Rgb::new((r / count) as u8, (g / count) as u8, (b / count) as u8)
+    }
+
+    /// Most frequent color in `region` with each channel quantized to `bins` levels
+    /// Returns the center of the winning bin; black if the region is empty.
+    pub fn dominant_color(image: &ImageData, region: &Rect, bins: usize) -> Rgb {
+        let (x0, y0, x1, y1) = Self::clamp_region(image, region);
+        let bins = bins.clamp(1, 256);
+        let quantize = |v: u8| v as usize * bins / 256;
+
+        let mut counts: FxHashMap<(usize, usize, usize), usize> = FxHashMap::default();
+        for y in y0..y1 {
+            for p in &image.pixels[y * image.width + x0..y * image.width + x1] {
+                *counts.entry((quantize(p.r), quantize(p.g), quantize(p.b))).or_insert(0) += 1;
+            }
+        }
+
+        // Ties resolve to the lowest bin so the result doesn't depend on hash order
+        let Some(((r, g, b), _)) = counts.into_iter().max_by_key(|&(bin, count)| (count, Reverse(bin))) else {
+            return Rgb::new(0, 0, 0);
+        };
+        let center = |level: usize| ((level * 256 + 128) / bins).min(255) as u8;
+        Rgb::new(center(r), center(g), center(b))
+    }
+
+    /// Sample `num_samples` pixels at equal angles on a circle around (cx, cy)
+    /// Angle 0 points right and grows clockwise on screen (y is down).
+    /// Samples that fall outside the image are skipped.
+    pub fn sample_radial(
+        image: &ImageData,
+        cx: usize,
+        cy: usize,
+        radius: usize,
+        num_samples: usize,
+    ) -> Vec<(f32, Rgb)> {
+        let step = std::f32::consts::TAU / num_samples.max(1) as f32;
+        (0..num_samples)
+            .filter_map(|i| {
+                let angle = i as f32 * step;
+                let x = (cx as f32 + radius as f32 * angle.cos()).round();
+                let y = (cy as f32 + radius as f32 * angle.sin()).round();
+                if x < 0.0 || y < 0.0 {
+                    return None;
+                }
+                image.get_pixel(x as usize, y as usize).map(|p| (angle, *p))
+            })
+            .collect()
+    }
+
+    /// `samples` bilinear samples evenly spaced from (x1, y1) to (x2, y2), endpoints included
+    /// For bars and grids that aren't axis-aligned; points outside the image are skipped.
+    pub fn sample_line_subpixel(
+        image: &ImageData,
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        samples: usize,
+    ) -> Vec<Rgb> {
+        let last = samples.saturating_sub(1).max(1) as f32;
+        (0..samples)
+            .filter_map(|i| {
+                let t = i as f32 / last;
+                image.sample_bilinear(x1 + (x2 - x1) * t, y1 + (y2 - y1) * t)
+            })
+            .collect()
+    }
+
+    /// Fraction of the circle around (cx, cy) whose pixels have HSV V below `dark_threshold`
+    /// Counts samples rather than tracing the sweep, so clockwise and
+    /// counter-clockwise cooldown overlays give the same answer.
+    pub fn estimate_dark_arc_fraction(
+        image: &ImageData,
+        cx: usize,
+        cy: usize,
+        radius: usize,
+        dark_threshold: f32,
+    ) -> f32 {
+        // Roughly one sample per pixel of circumference
+        let num_samples = ((std::f32::consts::TAU * radius as f32).ceil() as usize).max(8);
+        let samples = Self::sample_radial(image, cx, cy, radius, num_samples);
+        if samples.is_empty() {
+            return 0.0;
+        }
+
+        let dark = samples.iter().filter(|(_, color)| color.to_hsv().v < dark_threshold).count();
+        dark as f32 / samples.len() as f32
+    }
+
+    /// Clamp a rect to the image as (x0, y0, x1, y1) with x1/y1 exclusive
+    fn clamp_region(image: &ImageData, region: &Rect) -> (usize, usize, usize, usize) {
+        let x0 = region.x.clamp(0, image.width as i32) as usize;
+        let y0 = region.y.clamp(0, image.height as i32) as usize;
+        let x1 = (region.x + region.width).clamp(0, image.width as i32) as usize;
+        let y1 = (region.y + region.height).clamp(0, image.height as i32) as usize;
+        (x0, y0, x1.max(x0), y1.max(y0))
+    }
+
+    /// Find regions whose pixels change across frames (health bars draining, units moving)
+    /// Computes per-pixel temporal variance of luminance (0-255 scale) and
+    /// groups pixels above `variance_threshold` into bounding rects.
+    /// Static HUD areas have variance ~0 and can be analyzed once and cached.
+    pub fn find_animated_regions(frames: &[ImageData], variance_threshold: f32) -> Vec<Rect> {
+        if frames.len() < 2 {
+            return Vec::new();
+        }
+
+        let width = frames[0].width;
+        let height = frames[0].height;
+        if frames.iter().any(|f| f.width != width || f.height != height || f.pixels.len() != width * height) {
+            return Vec::new();
+        }
+
+        let n = frames.len() as f32;
+        let animated: Vec<bool> = (0..width * height)
+            .into_par_iter()
+            .map(|idx| {
+                let mut sum = 0.0f32;
+                let mut sum_sq = 0.0f32;
+                for frame in frames {
+                    let p = &frame.pixels[idx];
+                    let luma = 0.299 * p.r as f32 + 0.587 * p.g as f32 + 0.114 * p.b as f32;
+                    sum += luma;
+                    sum_sq += luma * luma;
+                }
+                let mean = sum / n;
+                (sum_sq / n - mean * mean) > variance_threshold
+            })
+            .collect();
+
+        Self::group_mask_regions(&animated, width, height)
+    }
+
+    /// Flood fill a pixel mask into bounding rects of its 4-connected components
+    fn group_mask_regions(mask: &[bool], width: usize, height: usize) -> Vec<Rect> {
+        let mut visited = vec![false; width * height];
+        let mut regions = Vec::new();
+
+        for y in 0..height {
+            for x in 0..width {
+                let idx = y * width + x;
+                if visited[idx] || !mask[idx] {
+                    continue;
+                }
+
+                let mut min_x = x;
+                let mut max_x = x;
+                let mut min_y = y;
+                let mut max_y = y;
+                let mut stack = vec![(x, y)];
+
+                while let Some((cx, cy)) = stack.pop() {
+                    let cidx = cy * width + cx;
+                    if visited[cidx] || !mask[cidx] {
+                        continue;
+                    }
+
+                    visited[cidx] = true;
+                    min_x = min_x.min(cx);
+                    max_x = max_x.max(cx);
+                    min_y = min_y.min(cy);
+                    max_y = max_y.max(cy);
+
+                    if cx > 0 { stack.push((cx - 1, cy)); }
+                    if cx + 1 < width { stack.push((cx + 1, cy)); }
+                    if cy > 0 { stack.push((cx, cy - 1)); }
+                    if cy + 1 < height { stack.push((cx, cy + 1)); }
+                }
+
+                regions.push(Rect::new(
+                    min_x as i32,
+                    min_y as i32,
+                    (max_x - min_x + 1) as i32,
+                    (max_y - min_y + 1) as i32,
+                ));
+            }
+        }
+
+        regions.sort_by_key(|r| (r.y, r.x));
+        regions
+    }
+
     /// Find differences between two images (for detecting changes)
     pub fn find_differences(image1: &ImageData, image2: &ImageData, threshold: u32) -> Vec<Rect> {
         if image1.width != image2.width || image1.height != image2.height {
@@ -672,39 +1941,1561 @@ impl ImageEngine {
             }
         }
 
+        regions.sort_by_key(|r| (r.y, r.x));
         regions
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Regions that appeared between frames and are shaped like a number (damage, gold, timers)
+    /// Changed blobs from `find_differences` on the same line are joined so a
+    /// multi-digit number comes back as one rect; results have width/height in [0.5, 8.0].
+    pub fn detect_number_regions(
+        prev: &ImageData,
+        curr: &ImageData,
+        min_width: usize,
+        min_height: usize,
+    ) -> Vec<Rect> {
+        let mut blobs = Self::find_differences(prev, curr, NUMBER_DIFF_THRESHOLD);
+        blobs.sort_by_key(|r| (r.x, r.y));
+
+        let mut merged: Vec<Rect> = Vec::new();
+        for blob in blobs {
+            let neighbour = merged.iter_mut().find(|m| {
+                let overlap = (m.y + m.height).min(blob.y + blob.height) - m.y.max(blob.y);
+                let gap = blob.x - (m.x + m.width);
+                overlap * 2 >= m.height.min(blob.height) && gap <= m.height.max(blob.height) / 2
+            });
 
-    #[test]
-    fn test_rgb_to_hsv() {
-        let red = Rgb::new(255, 0, 0);
-        let hsv = red.to_hsv();
-        assert!((hsv.h - 0.0).abs() < 1.0);
-        assert!((hsv.s - 1.0).abs() < 0.01);
-        assert!((hsv.v - 1.0).abs() < 0.01);
+            match neighbour {
+                Some(m) => {
+                    let x1 = (m.x + m.width).max(blob.x + blob.width);
+                    let y1 = (m.y + m.height).max(blob.y + blob.height);
+                    m.x = m.x.min(blob.x);
+                    m.y = m.y.min(blob.y);
+                    m.width = x1 - m.x;
+                    m.height = y1 - m.y;
+                }
+                None => merged.push(blob),
+            }
+        }
+
+        merged.retain(|r| {
+            let aspect = r.width as f32 / r.height as f32;
+            r.width as usize >= min_width && r.height as usize >= min_height && (0.5..=8.0).contains(&aspect)
+        });
+        merged.sort_by_key(|r| (r.y, r.x));
+        merged
     }
 
-    #[test]
-    fn test_color_distance() {
-        let c1 = Rgb::new(100, 100, 100);
-        let c2 = Rgb::new(100, 100, 100);
-        assert_eq!(c1.distance_sq(&c2), 0);
+    /// Read a row of digits in `region` using the built-in 8x12 templates
+    /// Digits are the brighter pixels (split at the midpoint of the region's
+    /// luminance range) and are separated by empty columns. Returns None if the
+    /// region has no contrast, any glyph isn't a confident digit, or the value overflows.
+    pub fn recognize_digit_sequence(image: &ImageData, region: &Rect) -> Option<u32> {
+        let (x0, y0, x1, y1) = Self::clamp_region(image, region);
+        let (width, height) = (x1 - x0, y1 - y0);
+        if width == 0 || height == 0 {
+            return None;
+        }
 
-        let c3 = Rgb::new(110, 100, 100);
-        assert_eq!(c1.distance_sq(&c3), 100);
+        let luma: Vec<f32> = (y0..y1)
+            .flat_map(|y| image.pixels[y * image.width + x0..y * image.width + x1].iter())
+            .map(|p| 0.299 * p.r as f32 + 0.587 * p.g as f32 + 0.114 * p.b as f32)
+            .collect();
+        let min = luma.iter().cloned().fold(f32::MAX, f32::min);
+        let max = luma.iter().cloned().fold(f32::MIN, f32::max);
+        if max - min < 32.0 {
+            return None;
+        }
+        let mid = (min + max) / 2.0;
+        let mask: Vec<bool> = luma.iter().map(|&l| l > mid).collect();
+
+        let column_has_ink = |x: usize| (0..height).any(|y| mask[y * width + x]);
+        let mut value: Option<u32> = None;
+        let mut x = 0;
+
+        while x < width {
+            if !column_has_ink(x) {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < width && column_has_ink(x) {
+                x += 1;
+            }
+
+            let rows: Vec<usize> = (0..height)
+                .filter(|&y| (start..x).any(|cx| mask[y * width + cx]))
+                .collect();
+            let glyph = Self::normalize_glyph(&mask, width, start, rows[0], x, rows[rows.len() - 1] + 1);
+            let digit = Self::classify_digit(&glyph)?;
+            value = Some(value.unwrap_or(0).checked_mul(10)?.checked_add(digit)?);
+        }
+
+        value
     }
 
-    #[test]
-    fn test_rect_operations() {
-        let rect = Rect::new(10, 20, 100, 50);
-        assert_eq!(rect.center_x(), 60);
-        assert_eq!(rect.center_y(), 45);
-        assert!(rect.contains(50, 30));
-        assert!(!rect.contains(5, 30));
+    /// Seconds left on a skill button's countdown (one or two digits)
+    /// Reads the middle 60% of `button` with `recognize_digit_sequence`, which
+    /// keeps the round edge and cooldown sweep out of the crop. None if no
+    /// confident number is printed there.
+    pub fn read_cooldown_digit(image: &ImageData, button: &Rect) -> Option<u32> {
+        let center = Rect::new(
+            button.x + button.width / 5,
+            button.y + button.height / 5,
+            button.width * 3 / 5,
+            button.height * 3 / 5,
+        );
+        Self::recognize_digit_sequence(image, &center).filter(|&seconds| seconds <= 99)
+    }
+
+    /// Best matching template digit for a normalized glyph, if confident enough
+    fn classify_digit(glyph: &[bool; DIGIT_WIDTH * DIGIT_HEIGHT]) -> Option<u32> {
+        let (digit, similarity) = DIGIT_TEMPLATES
+            .iter()
+            .enumerate()
+            .map(|(digit, template)| {
+                // Crop the template to its ink too, so both sides are scaled the same way
+                let cols: Vec<usize> = (0..DIGIT_WIDTH)
+                    .filter(|&x| (0..DIGIT_HEIGHT).any(|y| template[y * DIGIT_WIDTH + x]))
+                    .collect();
+                let rows: Vec<usize> = (0..DIGIT_HEIGHT)
+                    .filter(|&y| (0..DIGIT_WIDTH).any(|x| template[y * DIGIT_WIDTH + x]))
+                    .collect();
+                let normalized = Self::normalize_glyph(
+                    template,
+                    DIGIT_WIDTH,
+                    cols[0],
+                    rows[0],
+                    cols[cols.len() - 1] + 1,
+                    rows[rows.len() - 1] + 1,
+                );
+                let agree = normalized.iter().zip(glyph.iter()).filter(|(a, b)| a == b).count();
+                (digit as u32, agree as f32 / glyph.len() as f32)
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))?;
+
+        (similarity >= DIGIT_MIN_SIMILARITY).then_some(digit)
+    }
+
+    /// Nearest-neighbour resample of mask[x0..x1, y0..y1] to the template size
+    fn normalize_glyph(
+        mask: &[bool],
+        stride: usize,
+        x0: usize,
+        y0: usize,
+        x1: usize,
+        y1: usize,
+    ) -> [bool; DIGIT_WIDTH * DIGIT_HEIGHT] {
+        let (w, h) = (x1 - x0, y1 - y0);
+        let mut glyph = [false; DIGIT_WIDTH * DIGIT_HEIGHT];
+        for gy in 0..DIGIT_HEIGHT {
+            let sy = y0 + (2 * gy + 1) * h / (2 * DIGIT_HEIGHT);
+            for gx in 0..DIGIT_WIDTH {
+                let sx = x0 + (2 * gx + 1) * w / (2 * DIGIT_WIDTH);
+                glyph[gy * DIGIT_WIDTH + gx] = mask[sy * stride + sx];
+            }
+        }
+        glyph
+    }
+
+    /// Structural similarity of two frames (1.0 = identical, range [-1, 1])
+    /// Uses luminance and an 11x11 Gaussian window (sigma 1.5) over `region`,
+    /// or the whole frame. Frames of different sizes score 0.0.
+    pub fn ssim(img1: &ImageData, img2: &ImageData, region: Option<Rect>) -> f32 {
+        if img1.width != img2.width || img1.height != img2.height {
+            return 0.0;
+        }
+
+        let bounds = region.unwrap_or(Rect::new(0, 0, img1.width as i32, img1.height as i32));
+        let x0 = bounds.x.clamp(0, img1.width as i32) as usize;
+        let y0 = bounds.y.clamp(0, img1.height as i32) as usize;
+        let x1 = (bounds.x + bounds.width).clamp(0, img1.width as i32) as usize;
+        let y1 = (bounds.y + bounds.height).clamp(0, img1.height as i32) as usize;
+        if x1 <= x0 || y1 <= y0 {
+            return 0.0;
+        }
+
+        let (w, h) = (x1 - x0, y1 - y0);
+        let luma = |img: &ImageData| -> Vec<f32> {
+            (y0..y1).flat_map(|y| (x0..x1).map(move |x| (y, x)))
+                .map(|(y, x)| {
+                    let p = &img.pixels[y * img.width + x];
+                    0.299 * p.r as f32 + 0.587 * p.g as f32 + 0.114 * p.b as f32
+                })
+                .collect()
+        };
+        let a = luma(img1);
+        let b = luma(img2);
+
+        // Regions smaller than the window are compared as one window
+        if w < SSIM_WINDOW || h < SSIM_WINDOW {
+            let n = (w * h) as f32;
+            let mu1 = a.iter().sum::<f32>() / n;
+            let mu2 = b.iter().sum::<f32>() / n;
+            let (mut s11, mut s22, mut s12) = (0.0, 0.0, 0.0);
+            for (p, q) in a.iter().zip(&b) {
+                s11 += (p - mu1) * (p - mu1);
+                s22 += (q - mu2) * (q - mu2);
+                s12 += (p - mu1) * (q - mu2);
+            }
+            return Self::ssim_index(mu1, mu2, s11 / n, s22 / n, s12 / n);
+        }
+
+        let kernel = Self::gaussian_kernel();
+        let aa: Vec<f32> = a.iter().map(|v| v * v).collect();
+        let bb: Vec<f32> = b.iter().map(|v| v * v).collect();
+        let ab: Vec<f32> = a.iter().zip(&b).map(|(p, q)| p * q).collect();
+
+        let mu1 = Self::gaussian_blur_valid(&a, w, h, &kernel);
+        let mu2 = Self::gaussian_blur_valid(&b, w, h, &kernel);
+        let e11 = Self::gaussian_blur_valid(&aa, w, h, &kernel);
+        let e22 = Self::gaussian_blur_valid(&bb, w, h, &kernel);
+        let e12 = Self::gaussian_blur_valid(&ab, w, h, &kernel);
+
+        let total: f64 = (0..mu1.len())
+            .into_par_iter()
+            .map(|i| {
+                let (m1, m2) = (mu1[i], mu2[i]);
+                Self::ssim_index(m1, m2, e11[i] - m1 * m1, e22[i] - m2 * m2, e12[i] - m1 * m2) as f64
+            })
+            .sum();
+
+        (total / mu1.len() as f64).clamp(-1.0, 1.0) as f32
+    }
+
+    /// Places where `template` appears in `image`, by normalized cross-correlation
+    /// Both are compared as luminance and each match's confidence is its NCC score
+    /// (1.0 = same pattern up to contrast). Windows whose mean luminance is more than
+    /// `TEMPLATE_MAX_MEAN_DIFF` off the template's are skipped without scoring, so a
+    /// much brighter/darker copy isn't found. Overlapping hits keep only the best one;
+    /// flat templates match nothing.
+    pub fn match_template(image: &ImageData, template: &ImageData, threshold: f32) -> Vec<DetectedElement> {
+        let (tw, th) = (template.width, template.height);
+        if tw == 0 || th == 0 || tw > image.width || th > image.height {
+            return Vec::new();
+        }
+
+        let luma = |p: &Rgb| 0.299 * p.r as f32 + 0.587 * p.g as f32 + 0.114 * p.b as f32;
+        let gray: Vec<f32> = image.pixels.par_iter().map(luma).collect();
+        let n = (tw * th) as f64;
+        let t_mean = template.pixels.iter().map(|p| luma(p) as f64).sum::<f64>() / n;
+        let t_centered: Vec<f32> = template.pixels.iter().map(|p| luma(p) - t_mean as f32).collect();
+        let t_norm = t_centered.iter().map(|&v| v as f64 * v as f64).sum::<f64>().sqrt();
+        if t_norm < 1e-6 {
+            return Vec::new();
+        }
+
+        // Summed-area tables of luminance and its square, one row/column of zeros first
+        let stride = image.width + 1;
+        let mut sum = vec![0.0f64; stride * (image.height + 1)];
+        let mut sum_sq = vec![0.0f64; stride * (image.height + 1)];
+        for y in 0..image.height {
+            let (mut row, mut row_sq) = (0.0, 0.0);
+            for x in 0..image.width {
+                let v = gray[y * image.width + x] as f64;
+                row += v;
+                row_sq += v * v;
+                let i = (y + 1) * stride + x + 1;
+                sum[i] = sum[i - stride] + row;
+                sum_sq[i] = sum_sq[i - stride] + row_sq;
+            }
+        }
+        let window = |table: &[f64], x: usize, y: usize| {
+            table[(y + th) * stride + x + tw] - table[y * stride + x + tw] - table[(y + th) * stride + x]
+                + table[y * stride + x]
+        };
+
+        let (gray, t_centered, sum, sum_sq) = (&gray, &t_centered, &sum, &sum_sq);
+        let hits: Vec<DetectedElement> = (0..=image.height - th)
+            .into_par_iter()
+            .flat_map_iter(|y| {
+                (0..=image.width - tw).filter_map(move |x| {
+                    let w_sum = window(sum, x, y);
+                    let w_mean = w_sum / n;
+                    if (w_mean - t_mean).abs() > TEMPLATE_MAX_MEAN_DIFF {
+                        return None;
+                    }
+                    let w_norm = (window(sum_sq, x, y) - w_sum * w_mean).max(0.0).sqrt();
+                    if w_norm < 1e-6 {
+                        return None;
+                    }
+
+                    // The template is zero-mean, so the window's mean drops out
+                    let cross: f64 = (0..th)
+                        .map(|ty| {
+                            let row = &gray[(y + ty) * image.width + x..][..tw];
+                            let t_row = &t_centered[ty * tw..][..tw];
+                            row.iter().zip(t_row).map(|(a, b)| a * b).sum::<f32>() as f64
+                        })
+                        .sum();
+                    let score = ((cross / (t_norm * w_norm)) as f32).min(1.0);
+
+                    (score >= threshold).then(|| DetectedElement {
+                        element_type: ElementType::Unknown,
+                        bounds: Rect::new(x as i32, y as i32, tw as i32, th as i32),
+                        confidence: score,
+                        extra_data: None,
+                        state: None,
+                        fill_percent: None,
+                    })
+                })
+            })
+            .collect();
+
+        Self::non_max_suppression(hits, 0.0)
+    }
+
+    /// Full-screen overlays (death, pause, score, upgrade, ads) in a single frame
+    /// The frame is split into 16px blocks; flat, similarly colored blocks are
+    /// flood-filled together and any region covering > 40% of the frame is
+    /// classified by its mean color.
+    pub fn detect_overlays(image: &ImageData) -> Vec<OverlayDetection> {
+        let (width, height) = (image.width, image.height);
+        if width == 0 || height == 0 {
+            return Vec::new();
+        }
+
+        let cols = width.div_ceil(OVERLAY_BLOCK);
+        let rows = height.div_ceil(OVERLAY_BLOCK);
+        let blocks: Vec<(Rgb, bool)> = (0..rows * cols)
+            .into_par_iter()
+            .map(|i| {
+                let rect = Rect::new(
+                    ((i % cols) * OVERLAY_BLOCK) as i32,
+                    ((i / cols) * OVERLAY_BLOCK) as i32,
+                    OVERLAY_BLOCK as i32,
+                    OVERLAY_BLOCK as i32,
+                );
+                let (x0, y0, x1, y1) = Self::clamp_region(image, &rect);
+                let (mut sum, mut sum_sq, mut rgb) = (0.0f64, 0.0f64, [0u64; 3]);
+                for y in y0..y1 {
+                    for p in &image.pixels[y * width + x0..y * width + x1] {
+                        let luma = 0.299 * p.r as f64 + 0.587 * p.g as f64 + 0.114 * p.b as f64;
+                        sum += luma;
+                        sum_sq += luma * luma;
+                        rgb[0] += p.r as u64;
+                        rgb[1] += p.g as u64;
+                        rgb[2] += p.b as u64;
+                    }
+                }
+                let n = ((x1 - x0) * (y1 - y0)) as f64;
+                let variance = (sum_sq / n - (sum / n).powi(2)).max(0.0);
+                let mean = Rgb::new((rgb[0] as f64 / n) as u8, (rgb[1] as f64 / n) as u8, (rgb[2] as f64 / n) as u8);
+                (mean, (variance.sqrt() as f32) <= OVERLAY_MAX_STD)
+            })
+            .collect();
+
+        let mut visited = vec![false; blocks.len()];
+        let mut overlays = Vec::new();
+        for start in 0..blocks.len() {
+            if visited[start] || !blocks[start].1 {
+                continue;
+            }
+
+            let seed = blocks[start].0;
+            let (mut min_c, mut max_c, mut min_r, mut max_r) = (cols, 0, rows, 0);
+            let mut members = Vec::new();
+            let mut stack = vec![start];
+            visited[start] = true;
+            while let Some(i) = stack.pop() {
+                let (c, r) = (i % cols, i / cols);
+                min_c = min_c.min(c);
+                max_c = max_c.max(c);
+                min_r = min_r.min(r);
+                max_r = max_r.max(r);
+                members.push(i);
+
+                let neighbours = [
+                    (c > 0).then(|| i - 1),
+                    (c + 1 < cols).then(|| i + 1),
+                    (r > 0).then(|| i - cols),
+                    (r + 1 < rows).then(|| i + cols),
+                ];
+                for j in neighbours.into_iter().flatten() {
+                    if !visited[j] && blocks[j].1 && blocks[j].0.distance_sq(&seed) <= OVERLAY_COLOR_TOLERANCE {
+                        visited[j] = true;
+                        stack.push(j);
+                    }
+                }
+            }
+
+            let block_area = |i: usize| {
+                let w = OVERLAY_BLOCK.min(width - (i % cols) * OVERLAY_BLOCK);
+                let h = OVERLAY_BLOCK.min(height - (i / cols) * OVERLAY_BLOCK);
+                w * h
+            };
+            let covered: usize = members.iter().map(|&i| block_area(i)).sum();
+            if (covered as f32) <= OVERLAY_MIN_COVERAGE * (width * height) as f32 {
+                continue;
+            }
+
+            let x0 = min_c * OVERLAY_BLOCK;
+            let y0 = min_r * OVERLAY_BLOCK;
+            let x1 = ((max_c + 1) * OVERLAY_BLOCK).min(width);
+            let y1 = ((max_r + 1) * OVERLAY_BLOCK).min(height);
+            let bounds = Rect::new(x0 as i32, y0 as i32, (x1 - x0) as i32, (y1 - y0) as i32);
+
+            let n = members.len() as u32;
+            let (r, g, b) = members.iter().fold((0u32, 0u32, 0u32), |(r, g, b), &i| {
+                let c = blocks[i].0;
+                (r + c.r as u32, g + c.g as u32, b + c.b as u32)
+            });
+            let mean = Rgb::new((r / n) as u8, (g / n) as u8, (b / n) as u8);
+
+            overlays.push(OverlayDetection {
+                bounds,
+                overlay_type: Self::classify_overlay(&mean.to_hsv()),
+                opacity: covered as f32 / bounds.area() as f32,
+            });
+        }
+
+        overlays.sort_by_key(|o| (o.bounds.y, o.bounds.x));
+        overlays
+    }
+
+    /// `detect_overlays` restricted to overlays that appeared since `previous`
+    /// (an in-game frame); at least half of each overlay must be covered by
+    /// `find_differences` regions.
+    pub fn detect_overlays_since(previous: &ImageData, image: &ImageData) -> Vec<OverlayDetection> {
+        let changed = Self::find_differences(previous, image, NUMBER_DIFF_THRESHOLD);
+        Self::detect_overlays(image)
+            .into_iter()
+            .filter(|overlay| {
+                let b = &overlay.bounds;
+                let covered: i32 = changed
+                    .iter()
+                    .map(|c| {
+                        let w = (b.x + b.width).min(c.x + c.width) - b.x.max(c.x);
+                        let h = (b.y + b.height).min(c.y + c.height) - b.y.max(c.y);
+                        w.max(0) * h.max(0)
+                    })
+                    .sum();
+                covered * 2 >= b.area()
+            })
+            .collect()
+    }
+
+    /// Dark/gray or red tint = death, dimmed gray = pause, gold = score,
+    /// blue/purple = skill upgrade, near-white = ad
+    fn classify_overlay(hsv: &Hsv) -> OverlayType {
+        if hsv.s < 0.25 {
+            return match hsv.v {
+                v if v < 0.25 => OverlayType::DeathScreen,
+                v if v < 0.6 => OverlayType::PauseMenu,
+                v if v > 0.85 => OverlayType::AdsOverlay,
+                _ => OverlayType::Unknown,
+            };
+        }
+
+        match hsv.h {
+            h if !(20.0..=340.0).contains(&h) => OverlayType::DeathScreen,
+            h if (35.0..70.0).contains(&h) => OverlayType::ScoreScreen,
+            h if (200.0..300.0).contains(&h) => OverlayType::SkillUpgradeMenu,
+            _ => OverlayType::Unknown,
+        }
+    }
+
+    /// Whether two frames are similar enough (SSIM > `threshold`, e.g. 0.99) to skip detection
+    pub fn frames_are_identical(img1: &ImageData, img2: &ImageData, threshold: f32) -> bool {
+        Self::ssim(img1, img2, None) > threshold
+    }
+
+    fn ssim_index(mu1: f32, mu2: f32, var1: f32, var2: f32, cov: f32) -> f32 {
+        const C1: f32 = (0.01 * 255.0) * (0.01 * 255.0);
+        const C2: f32 = (0.03 * 255.0) * (0.03 * 255.0);
+        ((2.0 * mu1 * mu2 + C1) * (2.0 * cov + C2))
+            / ((mu1 * mu1 + mu2 * mu2 + C1) * (var1 + var2 + C2))
+    }
+
+    fn gaussian_kernel() -> [f32; SSIM_WINDOW] {
+        let sigma = 1.5f32;
+        let center = (SSIM_WINDOW / 2) as f32;
+        let mut kernel = [0.0; SSIM_WINDOW];
+        for (i, k) in kernel.iter_mut().enumerate() {
+            let d = i as f32 - center;
+            *k = (-d * d / (2.0 * sigma * sigma)).exp();
+        }
+        let sum: f32 = kernel.iter().sum();
+        kernel.iter_mut().for_each(|k| *k /= sum);
+        kernel
+    }
+
+    /// Separable blur keeping only positions where the whole window fits
+    fn gaussian_blur_valid(plane: &[f32], width: usize, height: usize, kernel: &[f32; SSIM_WINDOW]) -> Vec<f32> {
+        let out_w = width - SSIM_WINDOW + 1;
+        let out_h = height - SSIM_WINDOW + 1;
+
+        let horizontal: Vec<f32> = (0..height)
+            .into_par_iter()
+            .flat_map_iter(|y| {
+                let row = &plane[y * width..(y + 1) * width];
+                (0..out_w).map(move |x| {
+                    kernel.iter().zip(&row[x..x + SSIM_WINDOW]).map(|(k, v)| k * v).sum::<f32>()
+                })
+            })
+            .collect();
+
+        (0..out_h)
+            .into_par_iter()
+            .flat_map_iter(|y| {
+                let horizontal = &horizontal;
+                (0..out_w).map(move |x| {
+                    kernel.iter().enumerate()
+                        .map(|(i, k)| k * horizontal[(y + i) * out_w + x])
+                        .sum::<f32>()
+                })
+            })
+            .collect()
+    }
+}
+
+/// Anything that yields frames for analysis (screen capture, files, a socket...)
+pub trait FrameSource {
+    /// Next frame, or None when the source is exhausted
+    fn next_frame(&mut self) -> Option<ImageData>;
+}
+
+/// PNG files in a directory, in file-name order (for offline replay)
+#[derive(Debug)]
+pub struct FileFrameSource {
+    paths: std::vec::IntoIter<std::path::PathBuf>,
+}
+
+impl FileFrameSource {
+    /// List `*.png` in `dir`; files that fail to decode are skipped when reached
+    pub fn new(dir: &str) -> std::io::Result<Self> {
+        let mut paths: Vec<std::path::PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png")))
+            .collect();
+        paths.sort();
+        Ok(Self { paths: paths.into_iter() })
+    }
+}
+
+impl FrameSource for FileFrameSource {
+    fn next_frame(&mut self) -> Option<ImageData> {
+        for path in self.paths.by_ref() {
+            match ::image::open(&path) {
+                Ok(decoded) => {
+                    let rgb = decoded.to_rgb8();
+                    let (width, height) = (rgb.width() as usize, rgb.height() as usize);
+                    return Some(ImageData::from_rgb_bytes(rgb.as_raw(), width, height));
+                }
+                Err(e) => log::warn!("Skipping frame {}: {}", path.display(), e),
+            }
+        }
+        None
+    }
+}
+
+/// ARGB frames `(bytes, width, height)` pushed from another thread
+/// Blocks for the next frame; ends once every sender is dropped.
+#[derive(Debug)]
+pub struct RawBytesFrameSource {
+    receiver: std::sync::mpsc::Receiver<(Vec<u8>, usize, usize)>,
+}
+
+impl RawBytesFrameSource {
+    pub fn new(receiver: std::sync::mpsc::Receiver<(Vec<u8>, usize, usize)>) -> Self {
+        Self { receiver }
+    }
+}
+
+impl FrameSource for RawBytesFrameSource {
+    fn next_frame(&mut self) -> Option<ImageData> {
+        let (bytes, width, height) = self.receiver.recv().ok()?;
+        Some(ImageData::from_argb_bytes(&bytes, width, height))
+    }
+}
+
+/// Runs `analyze_full` on every frame of a `FrameSource`, reusing one scratch buffer
+pub struct FrameIterator<S: FrameSource> {
+    source: S,
+    config: DetectionConfig,
+    scratch: ImageScratch,
+}
+
+impl<S: FrameSource> FrameIterator<S> {
+    pub fn new(source: S, config: DetectionConfig) -> Self {
+        Self { source, config, scratch: ImageScratch::new() }
+    }
+}
+
+impl<S: FrameSource> Iterator for FrameIterator<S> {
+    type Item = AnalysisResult;
+
+    fn next(&mut self) -> Option<AnalysisResult> {
+        let frame = self.source.next_frame()?;
+        Some(ImageEngine::analyze_full_with(&mut self.scratch, &frame, &self.config))
+    }
+}
+
+struct MotionTrack {
+    points: std::collections::VecDeque<(i32, i32)>,
+    last_bounds: Rect,
+    missed: usize,
+}
+
+/// Follows moving objects (projectiles, units) across a stream of frames
+/// Each frame is diffed against the previous one and the change regions are
+/// chained into centroid trajectories by overlap, then by distance.
+pub struct MotionTracker {
+    previous: Option<ImageData>,
+    tracks: Vec<MotionTrack>,
+    max_history: usize,
+}
+
+impl MotionTracker {
+    /// Keep at most `max_history` centroids per track
+    pub fn new(max_history: usize) -> Self {
+        Self { previous: None, tracks: Vec::new(), max_history: max_history.max(1) }
+    }
+
+    /// Diff `image` against the previous frame and extend the tracks
+    /// A moving object changes both the spot it left and the one it entered; the
+    /// two are merged, so centroids trail the object by about half a step.
+    pub fn push_frame(&mut self, image: &ImageData) {
+        let regions = match &self.previous {
+            Some(previous) => {
+                Self::merge_nearby(ImageEngine::find_differences(previous, image, MOTION_DIFF_THRESHOLD))
+            }
+            None => Vec::new(),
+        };
+        self.previous = Some(ImageData {
+            width: image.width,
+            height: image.height,
+            pixels: image.pixels.clone(),
+            alpha: None,
+        });
+
+        let mut claimed = vec![false; self.tracks.len()];
+        let mut started = Vec::new();
+        for region in regions {
+            let center = (region.center_x(), region.center_y());
+            let best = self
+                .tracks
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| !claimed[i])
+                .filter_map(|(i, track)| {
+                    let &(x, y) = track.points.back()?;
+                    let jump = (x - center.0).abs().max((y - center.1).abs());
+                    let iou = track.last_bounds.iou(&region);
+                    (iou > 0.0 || jump <= MOTION_MAX_JUMP).then_some((i, iou, jump))
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1).then(b.2.cmp(&a.2)))
+                .map(|(i, _, _)| i);
+
+            match best {
+                Some(i) => {
+                    claimed[i] = true;
+                    let track = &mut self.tracks[i];
+                    track.points.push_back(center);
+                    if track.points.len() > self.max_history {
+                        track.points.pop_front();
+                    }
+                    track.last_bounds = region;
+                    track.missed = 0;
+                }
+                None => started.push(MotionTrack {
+                    points: std::collections::VecDeque::from([center]),
+                    last_bounds: region,
+                    missed: 0,
+                }),
+            }
+        }
+
+        for (track, claimed) in self.tracks.iter_mut().zip(claimed) {
+            if !claimed {
+                track.missed += 1;
+            }
+        }
+        self.tracks.retain(|track| track.missed <= MOTION_MAX_MISSES);
+        self.tracks.extend(started);
+    }
+
+    /// Centroid trajectory of every live track, oldest point first
+    pub fn tracks(&self) -> Vec<Vec<(i32, i32)>> {
+        self.tracks.iter().map(|track| track.points.iter().copied().collect()).collect()
+    }
+
+    /// Union regions whose gap is at most `MOTION_MERGE_GAP`
+    fn merge_nearby(mut regions: Vec<Rect>) -> Vec<Rect> {
+        let near = |a: &Rect, b: &Rect| {
+            a.x - MOTION_MERGE_GAP < b.x + b.width
+                && b.x < a.x + a.width + MOTION_MERGE_GAP
+                && a.y - MOTION_MERGE_GAP < b.y + b.height
+                && b.y < a.y + a.height + MOTION_MERGE_GAP
+        };
+
+        let mut i = 0;
+        while i < regions.len() {
+            match (i + 1..regions.len()).find(|&j| near(&regions[i], &regions[j])) {
+                Some(j) => {
+                    let (a, b) = (regions[i], regions.swap_remove(j));
+                    let (left, top) = (a.x.min(b.x), a.y.min(b.y));
+                    let right = (a.x + a.width).max(b.x + b.width);
+                    let bottom = (a.y + a.height).max(b.y + b.height);
+                    // The grown region may now reach earlier ones, so rescan
+                    regions[i] = Rect::new(left, top, right - left, bottom - top);
+                    i = 0;
+                }
+                None => i += 1,
+            }
+        }
+        regions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn test_rgb_to_hsv() {
+        let red = Rgb::new(255, 0, 0);
+        let hsv = red.to_hsv();
+        assert!((hsv.h - 0.0).abs() < 1.0);
+        assert!((hsv.s - 1.0).abs() < 0.01);
+        assert!((hsv.v - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_hsv_round_trip() {
+        // Spread over every hue sector, plus grays and extremes
+        let mut colors = vec![Rgb::new(0, 0, 0), Rgb::new(255, 255, 255), Rgb::new(128, 128, 128)];
+        for r in (0..=255).step_by(51) {
+            for g in (0..=255).step_by(51) {
+                for b in (0..=255).step_by(51) {
+                    colors.push(Rgb::new(r as u8, g as u8, b as u8));
+                }
+            }
+        }
+
+        for color in colors {
+            let back = color.to_hsv().to_rgb();
+            let close = |a: u8, b: u8| (a as i32 - b as i32).abs() <= 1;
+            assert!(close(back.r, color.r) && close(back.g, color.g) && close(back.b, color.b), "{:?} -> {:?}", color, back);
+        }
+
+        // Out-of-range s/v are clamped instead of overflowing a channel
+        assert_eq!(Hsv { h: 120.0, s: 1.2, v: 1.1 }.to_rgb(), Rgb::new(0, 255, 0));
+        assert_eq!(Hsv { h: -60.0, s: 1.0, v: 1.0 }.to_rgb(), Rgb::new(255, 0, 255));
+    }
+
+    #[test]
+    fn test_color_distance() {
+        let c1 = Rgb::new(100, 100, 100);
+        let c2 = Rgb::new(100, 100, 100);
+        assert_eq!(c1.distance_sq(&c2), 0);
+
+        let c3 = Rgb::new(110, 100, 100);
+        assert_eq!(c1.distance_sq(&c3), 100);
+    }
+
+    #[test]
+    fn test_rect_operations() {
+        let rect = Rect::new(10, 20, 100, 50);
+        assert_eq!(rect.center_x(), 60);
+        assert_eq!(rect.center_y(), 45);
+        assert!(rect.contains(50, 30));
+        assert!(!rect.contains(5, 30));
+    }
+
+    #[test]
+    fn test_rect_scale_translate() {
+        let rect = Rect::new(10, 20, 30, 40);
+        assert_eq!(rect.scale(2.0, 2.0), Rect::new(20, 40, 60, 80));
+        assert_eq!(rect.translate(5, -5), Rect::new(15, 15, 30, 40));
+
+        // Edges round to nearest (half away from zero): 1.5 -> 2, 4.0 -> 4
+        let odd = Rect::new(3, 3, 5, 5);
+        assert_eq!(odd.scale(0.5, 0.5), Rect::new(2, 2, 2, 2));
+
+        // Crop + resize round trip
+        let detected = Rect::new(4, 6, 10, 8);
+        assert_eq!(detected.scale(2.0, 2.0).translate(100, 50), Rect::new(108, 62, 20, 16));
+    }
+
+    #[test]
+    fn test_detect_button_state() {
+        let rect = Rect::new(0, 0, 20, 20);
+
+        // V = 0.8, saturated
+        let normal = make_solid_image(20, 20, Rgb::new(204, 102, 51));
+        assert_eq!(ImageEngine::detect_button_state(&normal, &rect), ButtonState::Normal);
+
+        // ~20% darker
+        let pressed = make_solid_image(20, 20, Rgb::new(163, 82, 41));
+        assert_eq!(ImageEngine::detect_button_state(&pressed, &rect), ButtonState::Pressed);
+
+        let disabled = make_solid_image(20, 20, Rgb::new(180, 180, 180));
+        assert_eq!(ImageEngine::detect_button_state(&disabled, &rect), ButtonState::Disabled);
+
+        let highlighted = make_solid_image(20, 20, Rgb::new(255, 128, 64));
+        assert_eq!(ImageEngine::detect_button_state(&highlighted, &rect), ButtonState::Highlighted);
+
+        // Dark sweep over the left half
+        let mut cooldown = make_solid_image(20, 20, Rgb::new(204, 102, 51));
+        for y in 0..20 {
+            for x in 0..10 {
+                cooldown.pixels[y * 20 + x] = Rgb::new(20, 10, 5);
+            }
+        }
+        assert_eq!(ImageEngine::detect_button_state(&cooldown, &rect), ButtonState::CooldownActive);
+    }
+
+    #[test]
+    fn test_classify_dark_threshold() {
+        // Dim green piece (V ~ 0.16) on a 1x1 board away from the image edge
+        let image = make_solid_image(40, 40, Rgb::new(10, 40, 10));
+        let grid = Rect::new(10, 10, 20, 20);
+
+        let board = ImageEngine::analyze_eliminate_board(&image, &grid, 1, 1);
+        assert_eq!(board, vec![vec![0]]);
+
+        let config = ClassifyConfig { dark_threshold: 0.1, ..ClassifyConfig::default() };
+        let board = ImageEngine::analyze_eliminate_board_with_config(&image, &grid, 1, 1, &config);
+        assert_eq!(board, vec![vec![4]]);
+    }
+
+    #[test]
+    fn test_eliminate_board_confidence() {
+        // Cell 0 is half red, half blue through its center; cell 1 is pure green
+        let mut image = make_game_board(1, 2, &[1, 4]);
+        let half = BOARD_CELL_SIZE / 2;
+        fill_rect(&mut image, Rect::new(half as i32, 0, half as i32, BOARD_CELL_SIZE as i32), Rgb::new(30, 60, 220));
+        let grid = Rect::new(0, 0, image.width as i32, image.height as i32);
+
+        let board = ImageEngine::analyze_eliminate_board_conf(&image, &grid, 1, 2, &ClassifyConfig::default());
+        let (_, mixed) = board[0][0];
+        assert!((mixed - 0.5).abs() < 0.05, "mixed cell confidence {}", mixed);
+        assert_eq!(board[0][1].0, 4);
+        assert!(board[0][1].1 > 0.99);
+    }
+
+    #[test]
+    fn test_eliminate_board_edge_cells() {
+        // 6px cells flush against the top-left corner: red corner cell, blue neighbours
+        let mut image = make_solid_image(12, 12, Rgb::new(30, 60, 220));
+        fill_rect(&mut image, Rect::new(0, 0, 6, 6), Rgb::new(220, 30, 30));
+        let grid = Rect::new(0, 0, 12, 12);
+
+        let board = ImageEngine::analyze_eliminate_board_conf(&image, &grid, 2, 2, &ClassifyConfig::default());
+        assert_eq!(board[0][0], (1, 1.0));
+        assert_eq!(board[1][1], (6, 1.0));
+
+        // A grid hanging off the image still classifies the visible part of the corner cell
+        let shifted = Rect::new(-3, -3, 12, 12);
+        let board = ImageEngine::analyze_eliminate_board(&image, &shifted, 2, 2);
+        assert_eq!(board[0][0], 1);
+    }
+
+    #[test]
+    fn test_find_grid_lines_uneven_cells() {
+        // Two 12px-uniform columns actually split at x = 9, marked by a 1px dark grid line
+        let mut image = make_solid_image(24, 12, Rgb::new(220, 30, 30));
+        fill_rect(&mut image, Rect::new(9, 0, 15, 12), Rgb::new(30, 60, 220));
+        fill_rect(&mut image, Rect::new(9, 0, 1, 12), Rgb::new(0, 0, 0));
+        let bounds = Rect::new(0, 0, 24, 12);
+
+        let vertical = ImageEngine::find_grid_lines(&image, &bounds, LineDirection::Vertical, 20);
+        assert_eq!(vertical, vec![9]);
+        assert!(ImageEngine::find_grid_lines(&image, &bounds, LineDirection::Horizontal, 20).is_empty());
+
+        // A uniform left cell (x 0..12) would sample across the line into the blue piece
+        let board = ImageEngine::analyze_eliminate_board_conf(&image, &bounds, 1, 2, &ClassifyConfig::default());
+        assert_eq!(board[0][0], (1, 1.0));
+        assert_eq!(board[0][1], (6, 1.0));
+    }
+
+    #[test]
+    fn test_motion_tracker_follows_blob() {
+        let frame_with_blob = |x: i32| {
+            // find_differences ignores changes 10px or smaller
+            let mut image = make_solid_image(120, 20, Rgb::new(0, 0, 0));
+            fill_rect(&mut image, Rect::new(x, 4, 12, 12), Rgb::new(255, 255, 255));
+            image
+        };
+
+        let mut tracker = MotionTracker::new(3);
+        for step in 0..3 {
+            tracker.push_frame(&frame_with_blob(10 + step * 14));
+        }
+        let tracks = tracker.tracks();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].len(), 2);
+        assert!(tracks[0][1].0 > tracks[0][0].0);
+
+        // History stays bounded as the blob keeps moving right
+        for step in 3..7 {
+            tracker.push_frame(&frame_with_blob(10 + step * 14));
+        }
+        let tracks = tracker.tracks();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].len(), 3);
+        assert!(tracks[0].windows(2).all(|pair| pair[1].0 > pair[0].0));
+    }
+
+    #[test]
+    fn test_find_animated_regions() {
+        let mut frames = Vec::new();
+        for i in 0..3 {
+            let mut frame = make_solid_image(30, 20, Rgb::new(50, 50, 50));
+            // A 4x3 blinking block at (10, 5)
+            let level = if i % 2 == 0 { 0 } else { 255 };
+            for y in 5..8 {
+                for x in 10..14 {
+                    frame.pixels[y * 30 + x] = Rgb::new(level, level, level);
+                }
+            }
+            frames.push(frame);
+        }
+
+        let regions = ImageEngine::find_animated_regions(&frames, 100.0);
+        assert_eq!(regions, vec![Rect::new(10, 5, 4, 3)]);
+        assert!(ImageEngine::find_animated_regions(&frames[..1], 100.0).is_empty());
+    }
+
+    #[test]
+    fn test_from_argb_bytes_with_alpha() {
+        // 2x2 ARGB: opaque red, half green, transparent blue, opaque white
+        let data = [
+            255, 255, 0, 0,
+            128, 0, 255, 0,
+            0, 0, 0, 255,
+            255, 255, 255, 255,
+        ];
+
+        let image = ImageData::from_argb_bytes_with_alpha(&data, 2, 2);
+        assert_eq!(image.alpha, Some(vec![255, 128, 0, 255]));
+        assert_eq!(image.get_pixel(0, 1), Some(&Rgb::new(0, 0, 255)));
+        assert!(image.is_transparent(0, 1));
+        assert_eq!(image.get_alpha(1, 0), 128);
+
+        let opaque = ImageData::from_argb_bytes(&data, 2, 2);
+        assert!(opaque.alpha.is_none());
+        assert!(!opaque.is_transparent(0, 1));
+    }
+
+    #[test]
+    fn test_from_bytes_pixel_formats() {
+        // One red pixel then one blue pixel in each layout
+        let red = Rgb::new(200, 10, 20);
+        let blue = Rgb::new(20, 10, 200);
+        let cases: [(PixelFormat, &[u8]); 4] = [
+            (PixelFormat::Argb, &[255, 200, 10, 20, 255, 20, 10, 200]),
+            (PixelFormat::Rgba, &[200, 10, 20, 255, 20, 10, 200, 255]),
+            (PixelFormat::Bgra, &[20, 10, 200, 255, 200, 10, 20, 255]),
+            (PixelFormat::Rgb, &[200, 10, 20, 20, 10, 200]),
+        ];
+        for (format, data) in cases {
+            let image = ImageData::from_bytes(data, 2, 1, format);
+            assert_eq!(image.pixels, vec![red, blue], "{:?}", format);
+        }
+
+        let bgra = ImageData::from_bgra_bytes(cases[2].1, 2, 1);
+        assert_eq!(bgra.pixels, vec![red, blue]);
+        assert_eq!(ImageData::from_argb_bytes(cases[0].1, 2, 1).pixels, bgra.pixels);
+    }
+
+    #[test]
+    fn test_confidence_from_fill() {
+        let red = Rgb::new(220, 30, 30);
+
+        // Clean 100x10 bar vs one where every other column is only half height
+        let mut clean = make_solid_image(200, 50, Rgb::new(40, 40, 40));
+        let mut ragged = make_solid_image(200, 50, Rgb::new(40, 40, 40));
+        for y in 20..30 {
+            for x in 50..150 {
+                clean.pixels[y * 200 + x] = red;
+                if x % 2 == 0 || y >= 25 {
+                    ragged.pixels[y * 200 + x] = red;
+                }
+            }
+        }
+        let clean_bar = ImageEngine::detect_health_bars(&clean);
+        let ragged_bar = ImageEngine::detect_health_bars(&ragged);
+        assert_eq!(clean_bar.len(), 1);
+        assert_eq!(ragged_bar.len(), 1);
+        assert!((clean_bar[0].confidence - 1.0).abs() < 1e-6);
+        assert!(ragged_bar[0].confidence < 0.8);
+
+        // Solid disk vs a disk with every third column cut (except the middle row)
+        let mut disk = make_solid_image(300, 200, Rgb::new(0, 0, 0));
+        let mut striped = make_solid_image(300, 200, Rgb::new(0, 0, 0));
+        for y in 70..130i32 {
+            for x in 220..280i32 {
+                if (x - 250).pow(2) + (y - 100).pow(2) <= 900 {
+                    disk.pixels[y as usize * 300 + x as usize] = Rgb::new(255, 255, 255);
+                    if x % 3 != 0 || y == 100 {
+                        striped.pixels[y as usize * 300 + x as usize] = Rgb::new(255, 255, 255);
+                    }
+                }
+            }
+        }
+        let clean_button = ImageEngine::detect_skill_buttons(&disk);
+        let ragged_button = ImageEngine::detect_skill_buttons(&striped);
+        assert_eq!(clean_button.len(), 1);
+        assert_eq!(ragged_button.len(), 1);
+        assert!(clean_button[0].confidence > 0.9);
+        assert!(clean_button[0].confidence > ragged_button[0].confidence);
+    }
+
+    #[test]
+    fn test_ssim() {
+        let mut frame = make_solid_image(40, 30, Rgb::new(0, 0, 0));
+        for (i, p) in frame.pixels.iter_mut().enumerate() {
+            let v = ((i % 40) * 6) as u8;
+            *p = Rgb::new(v, v, v);
+        }
+        let same = ImageData { width: 40, height: 30, pixels: frame.pixels.clone(), alpha: None };
+        let mut changed = ImageData { width: 40, height: 30, pixels: frame.pixels.clone(), alpha: None };
+        for y in 5..25 {
+            for x in 10..30 {
+                changed.pixels[y * 40 + x] = Rgb::new(255, 0, 0);
+            }
+        }
+
+        assert!((ImageEngine::ssim(&frame, &same, None) - 1.0).abs() < 1e-4);
+        assert!(ImageEngine::ssim(&frame, &changed, None) < 0.9);
+        // The changed block lies outside this region
+        assert!(ImageEngine::ssim(&frame, &changed, Some(Rect::new(0, 0, 40, 5))) > 0.999);
+
+        assert!(ImageEngine::frames_are_identical(&frame, &same, 0.99));
+        assert!(!ImageEngine::frames_are_identical(&frame, &changed, 0.99));
+    }
+
+    #[test]
+    fn test_match_template() {
+        let pattern = |x: usize, y: usize| ((x * 7 + y * 13) % 5 * 50) as u8;
+        let template = ImageData {
+            width: 12,
+            height: 10,
+            pixels: (0..120).map(|i| pattern(i % 12, i / 12)).map(|v| Rgb::new(v, v, v)).collect(),
+            alpha: None,
+        };
+        let mut image = make_gradient_image(80, 60, Rgb::new(20, 20, 20), Rgb::new(220, 220, 220));
+        for y in 0..10 {
+            for x in 0..12 {
+                image.pixels[(21 + y) * 80 + 37 + x] = template.pixels[y * 12 + x];
+            }
+        }
+
+        let matches = ImageEngine::match_template(&image, &template, 0.9);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].bounds, Rect::new(37, 21, 12, 10));
+        assert!(matches[0].confidence > 0.99);
+
+        // Flat or oversized templates can't be matched
+        assert!(ImageEngine::match_template(&image, &make_solid_image(4, 4, Rgb::new(90, 90, 90)), 0.5).is_empty());
+        assert!(ImageEngine::match_template(&template, &image, 0.5).is_empty());
+    }
+
+    #[test]
+    fn test_sample_grid_mean_brightness() {
+        // 2x2 grid of 20px cells with different gray levels
+        let mut image = make_solid_image(40, 40, Rgb::new(0, 0, 0));
+        let levels = [[10u8, 60], [120, 250]];
+        for y in 0..40 {
+            for x in 0..40 {
+                let v = levels[y / 20][x / 20];
+                image.pixels[y * 40 + x] = Rgb::new(v, v, v);
+            }
+        }
+
+        let brightness = ImageEngine::sample_grid(&image, &Rect::new(0, 0, 40, 40), 2, 2, 6, |img, cx, cy| {
+            let mut sum = 0u32;
+            for y in cy - 3..cy + 3 {
+                for x in cx - 3..cx + 3 {
+                    sum += img.get_pixel(x, y).unwrap().r as u32;
+                }
+            }
+            sum / 36
+        });
+        assert_eq!(brightness, vec![vec![10, 60], vec![120, 250]]);
+
+        // Centers near the edge are pulled inward so the sample stays in bounds
+        let corner = ImageEngine::sample_grid(&image, &Rect::new(0, 0, 4, 4), 1, 1, 10, |_, cx, cy| (cx, cy));
+        assert_eq!(corner, vec![vec![(5, 5)]]);
+
+        let brightness = ImageEngine::sample_grid_rects(&image, &Rect::new(0, 0, 40, 40), 2, 2, 6, |img, sample| {
+            assert_eq!(sample.area(), 36);
+            img.pixels_in(sample).map(|(_, _, p)| p.r as u32).sum::<u32>() / 36
+        });
+        assert_eq!(brightness, vec![vec![10, 60], vec![120, 250]]);
+
+        // Sample rectangles are clipped to the cell and to the image, never wrapping past 0
+        let corner = ImageEngine::sample_grid_rects(&image, &Rect::new(0, 0, 4, 4), 1, 1, 10, |_, sample| *sample);
+        assert_eq!(corner, vec![vec![Rect::new(0, 0, 4, 4)]]);
+        let off_screen = ImageEngine::sample_grid_rects(&image, &Rect::new(-10, -10, 14, 14), 1, 1, 10, |_, sample| *sample);
+        assert_eq!(off_screen, vec![vec![Rect::new(0, 0, 2, 2)]]);
+    }
+
+    #[test]
+    fn test_average_and_dominant_color() {
+        // 10x10 red cell with a white highlight on its top two rows
+        let mut image = make_solid_image(10, 10, Rgb::new(200, 20, 20));
+        for p in &mut image.pixels[..20] {
+            *p = Rgb::new(255, 255, 255);
+        }
+
+        let avg = ImageEngine::average_color(&image, &Rect::new(0, 0, 10, 10));
+        assert_eq!(avg, Rgb::new(211, 67, 67));
+
+        let dominant = ImageEngine::dominant_color(&image, &Rect::new(-5, -5, 20, 20), 8);
+        assert_eq!(dominant, Rgb::new(208, 16, 16));
+
+        assert_eq!(ImageEngine::average_color(&image, &Rect::new(20, 20, 5, 5)), Rgb::new(0, 0, 0));
+    }
+
+    #[test]
+    fn test_deserialize_old_detected_element() {
+        // Shape emitted before `state` existed
+        let json = r#"{"element_type":"SkillButton","bounds":{"x":1,"y":2,"width":3,"height":4},
+            "confidence":0.75,"extra_data":null}"#;
+        let element: DetectedElement = serde_json::from_str(json).unwrap();
+        assert_eq!(element.bounds, Rect::new(1, 2, 3, 4));
+        assert_eq!(element.state, None);
+    }
+
+    #[test]
+    fn test_analyze_full_matches_individual_detectors() {
+        // Red bar on the left, bright disk button on the right
+        let mut image = make_solid_image(300, 200, Rgb::new(0, 0, 0));
+        for y in 20..30 {
+            for x in 20..120 {
+                image.pixels[y * 300 + x] = Rgb::new(220, 30, 30);
+            }
+        }
+        for y in 70..130i32 {
+            for x in 220..280i32 {
+                if (x - 250).pow(2) + (y - 100).pow(2) <= 900 {
+                    image.pixels[y as usize * 300 + x as usize] = Rgb::new(255, 255, 255);
+                }
+            }
+        }
+
+        let result = ImageEngine::analyze_full(&image, &DetectionConfig::default());
+        let bars = ImageEngine::detect_health_bars(&image);
+        let buttons = ImageEngine::detect_skill_buttons(&image);
+        assert_eq!(result.health_bars.len(), 1);
+        assert_eq!(result.health_bars[0].bounds, bars[0].bounds);
+        assert_eq!(result.skill_buttons.len(), 1);
+        assert_eq!(result.skill_buttons[0].bounds, buttons[0].bounds);
+        assert!(result.joystick.is_none());
+
+        let config = DetectionConfig { detect_health_bars: false, blur_sigma: Some(1.0), ..Default::default() };
+        let result = ImageEngine::analyze_full(&image, &config);
+        assert!(result.health_bars.is_empty());
+        assert_eq!(result.skill_buttons.len(), 1);
+    }
+
+    #[test]
+    fn test_non_max_suppression() {
+        let element = |x, confidence| DetectedElement {
+            element_type: ElementType::SkillButton,
+            bounds: Rect::new(x, 0, 10, 10),
+            confidence,
+            extra_data: None,
+            state: None,
+            fill_percent: None,
+        };
+
+        assert!((Rect::new(0, 0, 10, 10).iou(&Rect::new(5, 0, 10, 10)) - 1.0 / 3.0).abs() < 1e-6);
+
+        let kept = ImageEngine::non_max_suppression(vec![element(0, 0.6), element(1, 0.9), element(30, 0.5)], 0.5);
+        let xs: Vec<i32> = kept.iter().map(|e| e.bounds.x).collect();
+        assert_eq!(xs, vec![1, 30]);
+    }
+
+    #[test]
+    fn test_scratch_reuse() {
+        let large = make_solid_image(120, 80, Rgb::new(220, 30, 30));
+        let small = make_solid_image(60, 40, Rgb::new(220, 30, 30));
+        let mut scratch = ImageScratch::new();
+
+        ImageEngine::detect_health_bars_with(&mut scratch, &large);
+        let hsv_capacity = scratch.hsv.capacity();
+        let visited_capacity = scratch.visited.capacity();
+        let hsv_ptr = scratch.hsv.as_ptr();
+
+        for image in [&large, &small, &large] {
+            ImageEngine::detect_health_bars_with(&mut scratch, image);
+            ImageEngine::detect_skill_buttons_with(&mut scratch, image);
+            ImageEngine::detect_joystick_with(&mut scratch, image);
+        }
+
+        assert_eq!(scratch.hsv.capacity(), hsv_capacity);
+        assert_eq!(scratch.visited.capacity(), visited_capacity);
+        assert_eq!(scratch.hsv.as_ptr(), hsv_ptr);
+        assert_eq!(
+            ImageEngine::detect_health_bars_with(&mut scratch, &small).len(),
+            ImageEngine::detect_health_bars(&small).len()
+        );
+    }
+
+    #[test]
+    fn test_detect_joysticks_both_corners() {
+        let mut image = make_solid_image(900, 400, Rgb::new(0, 0, 0));
+        for (cx, cy) in [(120i32, 300i32), (780, 300)] {
+            for y in cy - 50..=cy + 50 {
+                for x in cx - 50..=cx + 50 {
+                    if (x - cx).pow(2) + (y - cy).pow(2) <= 2500 {
+                        image.pixels[y as usize * 900 + x as usize] = Rgb::new(128, 128, 128);
+                    }
+                }
+            }
+        }
+
+        let joysticks = ImageEngine::detect_joysticks(&image);
+        assert_eq!(joysticks.len(), 2);
+        assert_eq!(joysticks[0].extra_data.as_deref(), Some("left"));
+        assert_eq!(joysticks[0].bounds.x, 70);
+        assert_eq!(joysticks[1].extra_data.as_deref(), Some("right"));
+        assert_eq!(joysticks[1].bounds.x, 730);
+
+        let left = ImageEngine::detect_joystick(&image).unwrap();
+        assert_eq!(left.bounds.x, joysticks[0].bounds.x);
+    }
+
+    #[test]
+    fn test_estimate_dark_arc_fraction() {
+        let samples = ImageEngine::sample_radial(&make_solid_image(50, 50, Rgb::new(1, 2, 3)), 25, 25, 10, 4);
+        assert_eq!(samples.len(), 4);
+        assert!((samples[1].0 - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert_eq!(samples[1].1, Rgb::new(1, 2, 3));
+
+        // Quarter sweep from 12 o'clock, clockwise vs counter-clockwise
+        let bright = Rgb::new(200, 200, 200);
+        let mut clockwise = make_solid_image(50, 50, bright);
+        let mut counter = make_solid_image(50, 50, bright);
+        for y in 0..25 {
+            for x in 25..50 {
+                clockwise.pixels[y * 50 + x] = Rgb::new(10, 10, 10);
+            }
+            for x in 0..25 {
+                counter.pixels[y * 50 + x] = Rgb::new(10, 10, 10);
+            }
+        }
+
+        let cw = ImageEngine::estimate_dark_arc_fraction(&clockwise, 25, 25, 15, 0.3);
+        let ccw = ImageEngine::estimate_dark_arc_fraction(&counter, 25, 25, 15, 0.3);
+        assert!((cw - 0.25).abs() < 0.05, "cw = {}", cw);
+        assert!((ccw - 0.25).abs() < 0.05, "ccw = {}", ccw);
+        assert_eq!(ImageEngine::estimate_dark_arc_fraction(&make_solid_image(50, 50, bright), 25, 25, 15, 0.3), 0.0);
+    }
+
+    /// Draw `digits` with the built-in templates at `scale`, 2 * scale apart
+    fn draw_digits(image: &mut ImageData, digits: &[usize], x: usize, y: usize, scale: usize) {
+        for (n, &digit) in digits.iter().enumerate() {
+            let left = x + n * (DIGIT_WIDTH + 2) * scale;
+            for ty in 0..DIGIT_HEIGHT * scale {
+                for tx in 0..DIGIT_WIDTH * scale {
+                    if DIGIT_TEMPLATES[digit][(ty / scale) * DIGIT_WIDTH + tx / scale] {
+                        image.pixels[(y + ty) * image.width + left + tx] = Rgb::new(255, 255, 255);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_number_regions_and_digits() {
+        let mut all = make_solid_image(260, 40, Rgb::new(20, 20, 40));
+        draw_digits(&mut all, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 0], 4, 4, 2);
+        let full = Rect::new(0, 0, 260, 40);
+        assert_eq!(ImageEngine::recognize_digit_sequence(&all, &full), Some(1234567890));
+        assert_eq!(ImageEngine::recognize_digit_sequence(&make_solid_image(20, 20, Rgb::new(9, 9, 9)), &full), None);
+
+        let prev = make_solid_image(200, 100, Rgb::new(20, 20, 40));
+        let mut curr = make_solid_image(200, 100, Rgb::new(20, 20, 40));
+        draw_digits(&mut curr, &[4, 2], 50, 30, 2);
+
+        let regions = ImageEngine::detect_number_regions(&prev, &curr, 8, 8);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].y, 30);
+        assert_eq!(regions[0].height, 24);
+        assert_eq!(ImageEngine::recognize_digit_sequence(&curr, &regions[0]), Some(42));
+
+        assert!(ImageEngine::detect_number_regions(&prev, &prev, 8, 8).is_empty());
+    }
+
+    #[test]
+    fn test_read_cooldown_digit() {
+        let button = Rect::new(100, 20, 80, 80);
+        let draw_button = |digits: &[usize]| {
+            let mut image = make_solid_image(200, 120, Rgb::new(30, 30, 30));
+            fill_rect(&mut image, button, Rgb::new(60, 60, 70));
+            let left = 100 + (80 - (digits.len() * 20).saturating_sub(4)) / 2;
+            draw_digits(&mut image, digits, left, 48, 2);
+            image
+        };
+
+        assert_eq!(ImageEngine::read_cooldown_digit(&draw_button(&[5]), &button), Some(5));
+        assert_eq!(ImageEngine::read_cooldown_digit(&draw_button(&[1, 2]), &button), Some(12));
+        assert_eq!(ImageEngine::read_cooldown_digit(&draw_button(&[9, 0]), &button), Some(90));
+        assert_eq!(ImageEngine::read_cooldown_digit(&draw_button(&[]), &button), None);
+    }
+
+    #[test]
+    fn test_color_preprocessing() {
+        for color in [Rgb::new(200, 40, 90), Rgb::new(10, 250, 128), Rgb::new(77, 77, 77)] {
+            assert_eq!(color.to_hsv().to_rgb(), color);
+        }
+
+        let washed = make_solid_image(4, 4, Rgb::new(150, 100, 100));
+        let boosted = ImageEngine::boost_saturation(&washed, 2.0);
+        let (before, after) = (washed.pixels[0].to_hsv(), boosted.pixels[0].to_hsv());
+        assert!((after.s - before.s * 2.0).abs() < 0.01);
+        assert!((after.v - before.v).abs() < 0.01);
+        assert_eq!(ImageEngine::boost_saturation(&washed, 10.0).pixels[0], Rgb::new(150, 0, 0));
+
+        // Gray ramp 50..=149: percentiles stretch to the full range
+        let mut ramp = make_solid_image(100, 1, Rgb::new(0, 0, 0));
+        for (i, p) in ramp.pixels.iter_mut().enumerate() {
+            *p = Rgb::new(50 + i as u8, 50 + i as u8, 50 + i as u8);
+        }
+        let normalized = ImageEngine::normalize_brightness(&ramp);
+        assert_eq!(normalized.pixels[4].r, 0);
+        assert_eq!(normalized.pixels[94].r, 255);
+        assert!(normalized.pixels[50].r > 100 && normalized.pixels[50].r < 155);
+
+        let contrast = ImageEngine::adjust_contrast(&make_solid_image(1, 1, Rgb::new(100, 10, 200)), 1.5, -20);
+        assert_eq!(contrast.pixels[0], Rgb::new(130, 0, 255));
+    }
+
+    #[test]
+    fn test_synthetic_image_helpers() {
+        let gradient = make_gradient_image(11, 2, Rgb::new(0, 0, 0), Rgb::new(200, 100, 0));
+        assert_eq!(gradient.pixels[0], Rgb::new(0, 0, 0));
+        assert_eq!(gradient.pixels[5], Rgb::new(100, 50, 0));
+        assert_eq!(gradient.pixels[11 + 10], Rgb::new(200, 100, 0));
+
+        let bar = make_health_bar_image(200, 0.5, Rgb::new(220, 30, 30));
+        let bars = ImageEngine::detect_health_bars(&bar);
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].bounds.width, 100);
+        assert_eq!(bars[0].fill_percent, Some(0.5));
+
+        let colors = [1, 2, 3, 4, 5, 6, 7, 0, 1];
+        let board = make_game_board(3, 3, &colors);
+        let size = (3 * BOARD_CELL_SIZE) as i32;
+        let grid = ImageEngine::analyze_eliminate_board(&board, &Rect::new(0, 0, size, size), 3, 3);
+        assert_eq!(grid, vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 0, 1]]);
+    }
+
+    #[test]
+    fn test_count_segments() {
+        // Five 20px pips with 4px separators; the last two are depleted (dark)
+        let mut image = make_solid_image(120, 10, Rgb::new(30, 30, 30));
+        for pip in 0..3 {
+            for y in 0..10 {
+                let start = y * 120 + pip * 24;
+                image.pixels[start..start + 20].fill(Rgb::new(230, 200, 30));
+            }
+        }
+        // A one-pixel scratch inside the first pip is bridged by the tolerance
+        for y in 0..10 {
+            image.pixels[y * 120 + 10] = Rgb::new(30, 30, 30);
+        }
+
+        let bar = Rect::new(0, 0, 120, 10);
+        assert_eq!(ImageEngine::count_segments(&image, &bar, ElementType::Unknown, 2), 3);
+        assert_eq!(ImageEngine::count_segments(&image, &bar, ElementType::Unknown, 0), 4);
+        assert_eq!(ImageEngine::count_segments(&image, &bar, ElementType::HealthBarEnemy, 2), 0);
+    }
+
+    #[test]
+    fn test_composite_over() {
+        let mut background = make_solid_image(20, 20, Rgb::new(0, 0, 255));
+        let square = make_solid_image(10, 10, Rgb::new(255, 0, 0));
+        background.composite_over(&square, (5, 5), 0.5);
+
+        assert_eq!(background.pixels[10 * 20 + 10], Rgb::new(128, 0, 128));
+        assert_eq!(background.pixels[2 * 20 + 2], Rgb::new(0, 0, 255));
+
+        // Clipped at the corner, fully opaque
+        background.composite_over(&square, (-5, 15), 1.0);
+        assert_eq!(background.pixels[19 * 20], Rgb::new(255, 0, 0));
+        assert_eq!(background.pixels[19 * 20 + 5], Rgb::new(0, 0, 255));
+    }
+
+    #[test]
+    fn test_sample_bilinear() {
+        // Red channel is 10 * x
+        let image = make_gradient_image(11, 3, Rgb::new(0, 0, 0), Rgb::new(100, 0, 0));
+        assert_eq!(image.sample_bilinear(2.5, 1.0), Some(Rgb::new(25, 0, 0)));
+        assert_eq!(image.sample_bilinear(10.0, 2.0), Some(Rgb::new(100, 0, 0)));
+        assert_eq!(image.sample_bilinear(10.5, 0.0), None);
+        assert_eq!(image.sample_bilinear(-0.1, 0.0), None);
+
+        // Blend in both directions
+        let mut checker = make_solid_image(2, 2, Rgb::new(0, 0, 0));
+        checker.pixels[3] = Rgb::new(200, 100, 40);
+        assert_eq!(checker.sample_bilinear(0.5, 0.5), Some(Rgb::new(50, 25, 10)));
+
+        let line = ImageEngine::sample_line_subpixel(&image, 0.0, 1.0, 10.0, 1.5, 5);
+        let reds: Vec<u8> = line.iter().map(|p| p.r).collect();
+        assert_eq!(reds, vec![0, 25, 50, 75, 100]);
+        assert_eq!(ImageEngine::sample_line_subpixel(&image, 8.0, 0.0, 14.0, 0.0, 4).len(), 2);
+    }
+
+    #[test]
+    fn test_pixels_in() {
+        let image = make_gradient_image(4, 3, Rgb::new(0, 0, 0), Rgb::new(30, 0, 0));
+        let cells: Vec<(usize, usize, u8)> = image.pixels_in(&Rect::new(1, 1, 2, 2))
+            .map(|(x, y, p)| (x, y, p.r))
+            .collect();
+        assert_eq!(cells, vec![(1, 1, 10), (2, 1, 20), (1, 2, 10), (2, 2, 20)]);
+
+        // Clipped to the image; fully outside yields nothing
+        assert_eq!(image.pixels_in(&Rect::new(-2, 2, 4, 5)).count(), 2);
+        assert_eq!(image.pixels_in(&Rect::new(10, 10, 2, 2)).count(), 0);
+    }
+
+    /// Busy "gameplay" frame: every pixel differs sharply from its neighbours
+    fn make_noise_image(width: usize, height: usize) -> ImageData {
+        let pixels = (0..width * height)
+            .map(|i| {
+                let v = ((i * 7919) % 251) as u8;
+                Rgb::new(v, v.wrapping_mul(3), 255 - v)
+            })
+            .collect();
+        ImageData { width, height, pixels, alpha: None }
+    }
+
+    fn fill_rect(image: &mut ImageData, rect: Rect, color: Rgb) {
+        for y in rect.y as usize..(rect.y + rect.height) as usize {
+            let row = y * image.width;
+            image.pixels[row + rect.x as usize..row + (rect.x + rect.width) as usize].fill(color);
+        }
+    }
+
+    #[test]
+    fn test_detect_overlays_classifies_large_panels() {
+        let gameplay = make_noise_image(192, 128);
+        assert!(ImageEngine::detect_overlays(&gameplay).is_empty());
+
+        let panel = Rect::new(16, 16, 160, 96);
+        let cases = [
+            (Rgb::new(15, 15, 15), OverlayType::DeathScreen),
+            (Rgb::new(100, 100, 100), OverlayType::PauseMenu),
+            (Rgb::new(230, 190, 40), OverlayType::ScoreScreen),
+            (Rgb::new(60, 60, 200), OverlayType::SkillUpgradeMenu),
+            (Rgb::new(250, 250, 250), OverlayType::AdsOverlay),
+        ];
+        for (color, expected) in cases {
+            let mut frame = make_noise_image(192, 128);
+            fill_rect(&mut frame, panel, color);
+
+            let overlays = ImageEngine::detect_overlays(&frame);
+            assert_eq!(overlays.len(), 1, "{:?}", expected);
+            assert_eq!(overlays[0].overlay_type, expected);
+            assert_eq!(overlays[0].bounds, panel);
+            assert!((overlays[0].opacity - 1.0).abs() < 1e-6);
+
+            assert_eq!(ImageEngine::detect_overlays_since(&gameplay, &frame).len(), 1);
+            assert!(ImageEngine::detect_overlays_since(&frame, &frame).is_empty());
+        }
+
+        // A small uniform panel is HUD, not an overlay
+        let mut hud = make_noise_image(192, 128);
+        fill_rect(&mut hud, Rect::new(0, 0, 64, 64), Rgb::new(15, 15, 15));
+        assert!(ImageEngine::detect_overlays(&hud).is_empty());
+    }
+
+    #[test]
+    fn test_health_bar_fill_percent() {
+        let bar = make_health_bar_image(160, 0.75, Rgb::new(30, 200, 60));
+        let bars = ImageEngine::detect_health_bars(&bar);
+        assert_eq!(bars[0].element_type, ElementType::HealthBarSelf);
+        assert!((bars[0].fill_percent.unwrap() - 0.75).abs() < 0.01);
+
+        // Anchored right: the empty track is on the left of the colored part
+        let mut mirrored = make_solid_image(200, 30, BAR_BACKGROUND);
+        fill_rect(&mut mirrored, Rect::new(0, 10, 120, 10), BAR_TRACK);
+        fill_rect(&mut mirrored, Rect::new(120, 10, 80, 10), Rgb::new(220, 30, 30));
+        let right = ImageEngine::detect_health_bars_with_direction(&mirrored, FillDirection::RightToLeft);
+        assert!((right[0].fill_percent.unwrap() - 0.4).abs() < 0.01);
+        let left = ImageEngine::detect_health_bars(&mirrored);
+        assert_eq!(left[0].fill_percent, Some(1.0));
+
+        // Bright scenery next to the bar isn't mistaken for empty track
+        let mut scene = make_solid_image(200, 30, Rgb::new(230, 230, 230));
+        fill_rect(&mut scene, Rect::new(0, 10, 100, 10), Rgb::new(220, 30, 30));
+        assert_eq!(ImageEngine::detect_health_bars(&scene)[0].fill_percent, Some(1.0));
+
+        // Dark background beside a full bar has no frame, so it isn't empty track
+        let mut dark = make_solid_image(200, 30, Rgb::new(10, 10, 10));
+        fill_rect(&mut dark, Rect::new(0, 10, 100, 10), Rgb::new(220, 30, 30));
+        assert_eq!(ImageEngine::detect_health_bars(&dark)[0].fill_percent, Some(1.0));
+
+        // The walk stops where the track's frame ends, not at the image edge
+        let mut framed = make_solid_image(200, 30, BAR_BACKGROUND);
+        fill_rect(&mut framed, Rect::new(0, 10, 150, 10), BAR_TRACK);
+        fill_rect(&mut framed, Rect::new(0, 10, 75, 10), Rgb::new(220, 30, 30));
+        assert!((ImageEngine::detect_health_bars(&framed)[0].fill_percent.unwrap() - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_detections_in_reading_order() {
+        // Red (enemy) bar lowest, green (self) highest: output follows position, not type
+        let mut image = make_solid_image(240, 80, BAR_BACKGROUND);
+        fill_rect(&mut image, Rect::new(20, 60, 100, 8), Rgb::new(220, 30, 30));
+        fill_rect(&mut image, Rect::new(130, 35, 100, 8), Rgb::new(30, 60, 220));
+        fill_rect(&mut image, Rect::new(10, 35, 100, 8), Rgb::new(30, 200, 60));
+        fill_rect(&mut image, Rect::new(60, 10, 100, 8), Rgb::new(30, 200, 60));
+
+        let first = ImageEngine::detect_health_bars(&image);
+        let second = ImageEngine::detect_health_bars(&image);
+        let positions: Vec<(i32, i32)> = first.iter().map(|e| (e.bounds.y, e.bounds.x)).collect();
+        assert_eq!(positions, vec![(10, 60), (35, 10), (35, 130), (60, 20)]);
+        assert_eq!(
+            positions,
+            second.iter().map(|e| (e.bounds.y, e.bounds.x)).collect::<Vec<_>>()
+        );
+
+        let analysis = ImageEngine::analyze_full(&image, &DetectionConfig::default());
+        let nms: Vec<(i32, i32)> = analysis.health_bars.iter().map(|e| (e.bounds.y, e.bounds.x)).collect();
+        assert_eq!(nms, positions);
+    }
+
+    #[test]
+    fn test_frame_iterator_sources() {
+        let bar = make_health_bar_image(120, 1.0, Rgb::new(220, 30, 30));
+        let empty = make_solid_image(120, 30, BAR_BACKGROUND);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        for frame in [&bar, &empty] {
+            let argb: Vec<u8> = frame.pixels.iter().flat_map(|p| [255, p.r, p.g, p.b]).collect();
+            sender.send((argb, frame.width, frame.height)).unwrap();
+        }
+        drop(sender);
+
+        let results: Vec<AnalysisResult> =
+            FrameIterator::new(RawBytesFrameSource::new(receiver), DetectionConfig::default()).collect();
+        let bar_counts: Vec<usize> = results.iter().map(|r| r.health_bars.len()).collect();
+        assert_eq!(bar_counts, vec![1, 0]);
+
+        let dir = std::env::temp_dir().join(format!("frame_source_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, frame) in [("b.png", &empty), ("a.png", &bar)] {
+            let bytes: Vec<u8> = frame.pixels.iter().flat_map(|p| [p.r, p.g, p.b]).collect();
+            ::image::RgbImage::from_raw(frame.width as u32, frame.height as u32, bytes)
+                .unwrap()
+                .save(dir.join(name))
+                .unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), "not a frame").unwrap();
+
+        let source = FileFrameSource::new(dir.to_str().unwrap()).unwrap();
+        let bar_counts: Vec<usize> = FrameIterator::new(source, DetectionConfig::default())
+            .map(|r| r.health_bars.len())
+            .collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(bar_counts, vec![1, 0]);
     }
 }
@@ -3,18 +3,48 @@
 //! This module provides the JNI interface for calling Rust functions from Android.
 //! All functions follow the JNI naming convention: Java_<package>_<class>_<method>
 
-use jni::objects::{JByteArray, JClass, JIntArray, JObject, JString};
-use jni::sys::{jboolean, jbyteArray, jfloat, jint, jlong, jstring, JNI_TRUE, JNI_FALSE};
-use jni::JNIEnv;
+use jni::objects::{GlobalRef, JByteArray, JClass, JObject, JString, JValue};
+use jni::sys::{jboolean, jfloat, jint, jstring, JNI_FALSE, JNI_TRUE};
+#[cfg(feature = "memory")]
+use jni::sys::{jdouble, jlong};
+use jni::{JNIEnv, JavaVM};
 
-use crate::image_engine::{DetectedElement, ElementType, ImageData, ImageEngine, Rect};
-use crate::strategy_engine::{CombatEngine, EliminateEngine, EliminateMove, GridPos, PathfindingEngine};
-use crate::memory_engine::{GameDataStructures, MemoryEngine, MemoryRegion};
+use crate::image_engine::{FillDirection, ImageData, ImageEngine, Rect};
+use crate::strategy_engine::{
+    CombatDecision, CombatEngine, EliminateEngine, EliminateMove, EnemyCast, GridPos, ObjectiveTimers, PathResult,
+    PathfindingEngine, StunSkill,
+};
+#[cfg(feature = "memory")]
+use crate::memory_engine::{
+    GameDataStructures, GameSignature, GameValueType, LibraryWatcher, MemoryEngine, MemoryError, MemoryRegion,
+    PointerChain, Watchpoint,
+};
+use crate::async_jobs::JobRegistry;
+use crate::{JsonEnvelope, SessionStats};
+use serde::Serialize;
 use rustc_hash::FxHashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+#[cfg(feature = "memory")]
+use std::time::Duration;
 
 // Package path for JNI functions
+#[allow(dead_code)]
 const PACKAGE: &str = "com_example_deepseekaiassistant_agent";
 
+/// Serialize a result wrapped in a versioned `JsonEnvelope`
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(&JsonEnvelope::new(value))
+        .map_err(|e| format!("JSON error: {}", e))
+}
+
+/// `{"error", "code"}` for a memory failure; `code` is `MemoryError::code`
+#[cfg(feature = "memory")]
+fn memory_error_json(e: &MemoryError) -> String {
+    serde_json::json!({ "error": e.to_string(), "code": e.code() }).to_string()
+}
+
 /// Initialize the Rust core library
 /// JNI: AgentCore.init()
 #[no_mangle]
@@ -29,13 +59,110 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_AgentCore_init
 /// JNI: AgentCore.getVersion(): String
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_AgentCore_getVersion<'local>(
-    mut env: JNIEnv<'local>,
+    env: JNIEnv<'local>,
     _class: JClass<'local>,
 ) -> jstring {
     let version = env.new_string(crate::VERSION).expect("Failed to create string");
     version.into_raw()
 }
 
+/// Get build configuration (version, features, target, thread count) as JSON
+/// JNI: AgentCore.getBuildInfo(): String (JSON)
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_AgentCore_getBuildInfo<'local>(
+    env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) -> jstring {
+    match to_json(&crate::build_info()) {
+        Ok(json) => env.new_string(&json).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+    }
+}
+
+/// Latency and activity counters since the library was loaded
+fn session_stats() -> &'static Mutex<SessionStats> {
+    static STATS: OnceLock<Mutex<SessionStats>> = OnceLock::new();
+    STATS.get_or_init(|| Mutex::new(SessionStats::new()))
+}
+
+/// Set while the last `detectOverlays` frame had an overlay up (death, pause, ads...)
+/// `analyzeCombat` and `findPath` return no actions until an overlay-free frame clears it.
+static ACTIONS_PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Whether combat and movement are paused by an overlay
+/// JNI: AgentCore.isActionsPaused(): Boolean
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_AgentCore_isActionsPaused(
+    _env: JNIEnv,
+    _class: JClass,
+) -> jboolean {
+    if ACTIONS_PAUSED.load(Ordering::Relaxed) { JNI_TRUE } else { JNI_FALSE }
+}
+
+/// Run a detector, recording its latency in the session stats
+fn timed_detection<T>(detect: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = detect();
+    session_stats().lock().unwrap().record_detection(start.elapsed().as_secs_f32() * 1000.0);
+    result
+}
+
+/// Run a strategy decision, recording its latency in the session stats
+fn timed_decision<T>(decide: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = decide();
+    session_stats().lock().unwrap().record_decision(start.elapsed().as_secs_f32() * 1000.0);
+    result
+}
+
+/// Get session statistics (frames, average latencies, moves, kills, uptime) as JSON
+/// Detection latency covers the ImageEngineNative detectors (health bars, skill
+/// buttons, joystick, overlays, button state, eliminate board). Decision latency
+/// covers the StrategyEngineNative move search (sync and async), simulateMove,
+/// findPath and analyzeCombat. Memory scans are not counted.
+/// JNI: AgentCore.getSessionStats(): String (JSON)
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_AgentCore_getSessionStats<'local>(
+    env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) -> jstring {
+    let report = session_stats().lock().unwrap().report();
+    match to_json(&report) {
+        Ok(json) => env.new_string(&json).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+    }
+}
+
+/// Count a move the agent executed on screen
+/// JNI: AgentCore.recordMoveExecuted()
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_AgentCore_recordMoveExecuted(
+    _env: JNIEnv,
+    _class: JClass,
+) {
+    session_stats().lock().unwrap().record_move_executed();
+}
+
+/// Count a kill scored by the agent
+/// JNI: AgentCore.recordKill()
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_AgentCore_recordKill(
+    _env: JNIEnv,
+    _class: JClass,
+) {
+    session_stats().lock().unwrap().record_kill();
+}
+
+/// Get the schema version of JSON results (the `version` field of each envelope)
+/// JNI: AgentCore.getSchemaVersion(): Int
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_AgentCore_getSchemaVersion(
+    _env: JNIEnv,
+    _class: JClass,
+) -> jint {
+    crate::SCHEMA_VERSION as jint
+}
+
 // ============================================================================
 // Image Engine JNI Functions
 // ============================================================================
@@ -44,7 +171,7 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_AgentCore_getV
 /// JNI: ImageEngineNative.detectHealthBars(pixels: ByteArray, width: Int, height: Int): String (JSON)
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNative_detectHealthBars<'local>(
-    mut env: JNIEnv<'local>,
+    env: JNIEnv<'local>,
     _class: JClass<'local>,
     pixels: JByteArray<'local>,
     width: jint,
@@ -55,15 +182,44 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNat
             .map_err(|e| format!("Failed to convert byte array: {}", e))?;
         
         let image = ImageData::from_argb_bytes(&bytes, width as usize, height as usize);
-        let elements = ImageEngine::detect_health_bars(&image);
+        let elements = timed_detection(|| ImageEngine::detect_health_bars(&image));
         
-        serde_json::to_string(&elements)
-            .map_err(|e| format!("JSON error: {}", e))
+        to_json(&elements)
     })();
 
     match result {
         Ok(json) => env.new_string(&json).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+    }
+}
+
+/// Detect health bars that fill in the given direction
+/// `rightToLeft` is for bars anchored on the right, which empty from the left.
+/// JNI: ImageEngineNative.detectHealthBarsWithDirection(pixels: ByteArray, width: Int, height: Int,
+///                                                      rightToLeft: Boolean): String (JSON)
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNative_detectHealthBarsWithDirection<'local>(
+    env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    pixels: JByteArray<'local>,
+    width: jint,
+    height: jint,
+    right_to_left: jboolean,
+) -> jstring {
+    let result = (|| -> Result<String, String> {
+        let bytes = env.convert_byte_array(&pixels)
+            .map_err(|e| format!("Failed to convert byte array: {}", e))?;
+        
+        let image = ImageData::from_argb_bytes(&bytes, width as usize, height as usize);
+        let direction = if right_to_left == JNI_TRUE { FillDirection::RightToLeft } else { FillDirection::LeftToRight };
+        let elements = timed_detection(|| ImageEngine::detect_health_bars_with_direction(&image, direction));
+        
+        to_json(&elements)
+    })();
+
+    match result {
+        Ok(json) => env.new_string(&json).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
     }
 }
 
@@ -71,7 +227,7 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNat
 /// JNI: ImageEngineNative.detectSkillButtons(pixels: ByteArray, width: Int, height: Int): String (JSON)
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNative_detectSkillButtons<'local>(
-    mut env: JNIEnv<'local>,
+    env: JNIEnv<'local>,
     _class: JClass<'local>,
     pixels: JByteArray<'local>,
     width: jint,
@@ -82,15 +238,14 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNat
             .map_err(|e| format!("Failed to convert byte array: {}", e))?;
         
         let image = ImageData::from_argb_bytes(&bytes, width as usize, height as usize);
-        let elements = ImageEngine::detect_skill_buttons(&image);
+        let elements = timed_detection(|| ImageEngine::detect_skill_buttons(&image));
         
-        serde_json::to_string(&elements)
-            .map_err(|e| format!("JSON error: {}", e))
+        to_json(&elements)
     })();
 
     match result {
         Ok(json) => env.new_string(&json).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
     }
 }
 
@@ -98,7 +253,7 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNat
 /// JNI: ImageEngineNative.detectJoystick(pixels: ByteArray, width: Int, height: Int): String (JSON)
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNative_detectJoystick<'local>(
-    mut env: JNIEnv<'local>,
+    env: JNIEnv<'local>,
     _class: JClass<'local>,
     pixels: JByteArray<'local>,
     width: jint,
@@ -109,15 +264,55 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNat
             .map_err(|e| format!("Failed to convert byte array: {}", e))?;
         
         let image = ImageData::from_argb_bytes(&bytes, width as usize, height as usize);
-        let element = ImageEngine::detect_joystick(&image);
+        let element = timed_detection(|| ImageEngine::detect_joystick(&image));
         
-        serde_json::to_string(&element)
-            .map_err(|e| format!("JSON error: {}", e))
+        to_json(&element)
+    })();
+
+    match result {
+        Ok(json) => env.new_string(&json).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+    }
+}
+
+/// Detect full-screen overlays (death, pause, score, upgrade, ads) that appeared
+/// since `previousPixels`, the last in-game frame of the same size. An empty
+/// `previousPixels` (no in-game frame yet) checks the current frame alone.
+/// Pauses combat and movement while an overlay is up, see `isActionsPaused`.
+/// JNI: ImageEngineNative.detectOverlays(pixels: ByteArray, previousPixels: ByteArray,
+///                                       width: Int, height: Int): String (JSON array)
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNative_detectOverlays<'local>(
+    env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    pixels: JByteArray<'local>,
+    previous_pixels: JByteArray<'local>,
+    width: jint,
+    height: jint,
+) -> jstring {
+    let result = (|| -> Result<String, String> {
+        let bytes = env.convert_byte_array(&pixels)
+            .map_err(|e| format!("Failed to convert byte array: {}", e))?;
+        let previous_bytes = env.convert_byte_array(&previous_pixels)
+            .map_err(|e| format!("Failed to convert byte array: {}", e))?;
+
+        let image = ImageData::from_argb_bytes(&bytes, width as usize, height as usize);
+        let overlays = timed_detection(|| {
+            if previous_bytes.is_empty() {
+                ImageEngine::detect_overlays(&image)
+            } else {
+                let previous = ImageData::from_argb_bytes(&previous_bytes, width as usize, height as usize);
+                ImageEngine::detect_overlays_since(&previous, &image)
+            }
+        });
+        ACTIONS_PAUSED.store(!overlays.is_empty(), Ordering::Relaxed);
+
+        to_json(&overlays)
     })();
 
     match result {
         Ok(json) => env.new_string(&json).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
     }
 }
 
@@ -127,7 +322,7 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNat
 ///                                              rows: Int, cols: Int): String (JSON 2D array)
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNative_analyzeEliminateBoard<'local>(
-    mut env: JNIEnv<'local>,
+    env: JNIEnv<'local>,
     _class: JClass<'local>,
     pixels: JByteArray<'local>,
     width: jint,
@@ -145,15 +340,47 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNat
         
         let image = ImageData::from_argb_bytes(&bytes, width as usize, height as usize);
         let grid_bounds = Rect::new(grid_x, grid_y, grid_w, grid_h);
-        let board = ImageEngine::analyze_eliminate_board(&image, &grid_bounds, rows as usize, cols as usize);
+        let board = timed_detection(|| ImageEngine::analyze_eliminate_board(&image, &grid_bounds, rows as usize, cols as usize));
+        
+        to_json(&board)
+    })();
+
+    match result {
+        Ok(json) => env.new_string(&json).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+    }
+}
+
+/// Detect the visual state of a button
+/// JNI: ImageEngineNative.detectButtonState(pixels: ByteArray, width: Int, height: Int,
+///                                          buttonRectJson: String): String (JSON ButtonState)
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_ImageEngineNative_detectButtonState<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    pixels: JByteArray<'local>,
+    width: jint,
+    height: jint,
+    button_rect_json: JString<'local>,
+) -> jstring {
+    let result = (|| -> Result<String, String> {
+        let bytes = env.convert_byte_array(&pixels)
+            .map_err(|e| format!("Failed to convert byte array: {}", e))?;
+        let rect_str: String = env.get_string(&button_rect_json)
+            .map_err(|e| format!("Failed to get string: {}", e))?
+            .into();
+        let button_rect: Rect = serde_json::from_str(&rect_str)
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+        
+        let image = ImageData::from_argb_bytes(&bytes, width as usize, height as usize);
+        let state = timed_detection(|| ImageEngine::detect_button_state(&image, &button_rect));
         
-        serde_json::to_string(&board)
-            .map_err(|e| format!("JSON error: {}", e))
+        to_json(&state)
     })();
 
     match result {
         Ok(json) => env.new_string(&json).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
     }
 }
 
@@ -177,15 +404,14 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_StrategyEngine
         let board: Vec<Vec<u8>> = serde_json::from_str(&board_str)
             .map_err(|e| format!("JSON parse error: {}", e))?;
         
-        let best_move = EliminateEngine::find_best_move(&board);
+        let best_move = timed_decision(|| EliminateEngine::find_best_move(&board));
         
-        serde_json::to_string(&best_move)
-            .map_err(|e| format!("JSON error: {}", e))
+        to_json(&best_move)
     })();
 
     match result {
         Ok(json) => env.new_string(&json).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
     }
 }
 
@@ -206,19 +432,58 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_StrategyEngine
         let board: Vec<Vec<u8>> = serde_json::from_str(&board_str)
             .map_err(|e| format!("JSON parse error: {}", e))?;
         
-        let moves = EliminateEngine::find_best_moves(&board, n as usize);
+        let moves = timed_decision(|| EliminateEngine::find_best_moves(&board, n as usize));
         
-        serde_json::to_string(&moves)
-            .map_err(|e| format!("JSON error: {}", e))
+        to_json(&moves)
     })();
 
     match result {
         Ok(json) => env.new_string(&json).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+    }
+}
+
+/// Play a move and return the settled board after all cascades
+/// JNI: StrategyEngineNative.simulateMove(boardJson: String, moveJson: String): String (JSON CascadeResult)
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_StrategyEngineNative_simulateMove<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    board_json: JString<'local>,
+    move_json: JString<'local>,
+) -> jstring {
+    let result = (|| -> Result<String, String> {
+        let board_str: String = env.get_string(&board_json)
+            .map_err(|e| format!("Failed to get string: {}", e))?
+            .into();
+        let move_str: String = env.get_string(&move_json)
+            .map_err(|e| format!("Failed to get string: {}", e))?
+            .into();
+
+        let board: Vec<Vec<u8>> = serde_json::from_str(&board_str)
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+        let mv: EliminateMove = serde_json::from_str(&move_str)
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+
+        let cols = board.first().map_or(0, Vec::len);
+        if board.iter().any(|row| row.len() != cols)
+            || [mv.from_row, mv.to_row].iter().any(|&r| r >= board.len())
+            || [mv.from_col, mv.to_col].iter().any(|&c| c >= cols)
+        {
+            return Err("Move is outside the board".to_string());
+        }
+
+        to_json(&timed_decision(|| EliminateEngine::simulate_cascade(&board, &mv)))
+    })();
+
+    match result {
+        Ok(json) => env.new_string(&json).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
     }
 }
 
 /// Find path using A* algorithm
+/// Returns a not-found result while an overlay has actions paused.
 /// JNI: StrategyEngineNative.findPath(startX: Int, startY: Int, goalX: Int, goalY: Int,
 ///                                    obstaclesJson: String, gridWidth: Int, gridHeight: Int,
 ///                                    use8Dir: Boolean): String (JSON PathResult)
@@ -247,26 +512,32 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_StrategyEngine
             .map(|(x, y)| GridPos::new(x, y))
             .collect();
         
+        if ACTIONS_PAUSED.load(Ordering::Relaxed) {
+            return to_json(&PathResult { path: Vec::new(), total_cost: 0, found: false, nodes_expanded: 0 });
+        }
+
         let start = GridPos::new(start_x, start_y);
         let goal = GridPos::new(goal_x, goal_y);
         
-        let path_result = if use_8dir == JNI_TRUE {
-            PathfindingEngine::find_path_8dir(start, goal, &obstacles, grid_width, grid_height)
-        } else {
-            PathfindingEngine::find_path(start, goal, &obstacles, grid_width, grid_height)
-        };
+        let path_result = timed_decision(|| {
+            if use_8dir == JNI_TRUE {
+                PathfindingEngine::find_path_8dir(start, goal, &obstacles, grid_width, grid_height)
+            } else {
+                PathfindingEngine::find_path(start, goal, &obstacles, grid_width, grid_height)
+            }
+        });
         
-        serde_json::to_string(&path_result)
-            .map_err(|e| format!("JSON error: {}", e))
+        to_json(&path_result)
     })();
 
     match result {
         Ok(json) => env.new_string(&json).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
     }
 }
 
 /// Analyze combat situation
+/// Returns no decisions while an overlay has actions paused.
 /// JNI: StrategyEngineNative.analyzeCombat(selfX: Int, selfY: Int, selfHpPercent: Float,
 ///                                         enemiesJson: String, alliesJson: String,
 ///                                         skillReadyJson: String, inTowerRange: Boolean): String
@@ -307,24 +578,165 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_StrategyEngine
             .map(|(x, y)| GridPos::new(x, y))
             .collect();
         
+        if ACTIONS_PAUSED.load(Ordering::Relaxed) {
+            return to_json(&Vec::<CombatDecision>::new());
+        }
+
         let self_pos = GridPos::new(self_x, self_y);
         
-        let decisions = CombatEngine::analyze_combat(
-            self_pos,
-            self_hp_percent,
-            &enemies,
-            &allies,
-            &skill_ready,
-            in_tower_range == JNI_TRUE,
-        );
-        
-        serde_json::to_string(&decisions)
-            .map_err(|e| format!("JSON error: {}", e))
+        let decisions = timed_decision(|| {
+            CombatEngine::analyze_combat(
+                self_pos,
+                self_hp_percent,
+                &enemies,
+                &allies,
+                &skill_ready,
+                in_tower_range == JNI_TRUE,
+            )
+        });
+        
+        to_json(&decisions)
     })();
 
     match result {
         Ok(json) => env.new_string(&json).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+    }
+}
+
+/// Analyze combat situation, interrupting enemy casts
+/// Returns no decisions while an overlay has actions paused.
+/// `stunJson` is a `StunSkill` or `null`; `castsJson` lists `EnemyCast`s in progress.
+/// When the stun can stop one of them, an InterruptCast decision comes first.
+/// JNI: StrategyEngineNative.analyzeCombatWithCasts(selfX: Int, selfY: Int, selfHpPercent: Float,
+///                                                  enemiesJson: String, alliesJson: String,
+///                                                  skillReadyJson: String, inTowerRange: Boolean,
+///                                                  stunJson: String, castsJson: String): String
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_StrategyEngineNative_analyzeCombatWithCasts<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    self_x: jint,
+    self_y: jint,
+    self_hp_percent: jfloat,
+    enemies_json: JString<'local>,
+    allies_json: JString<'local>,
+    skill_ready_json: JString<'local>,
+    in_tower_range: jboolean,
+    stun_json: JString<'local>,
+    casts_json: JString<'local>,
+) -> jstring {
+    let result = (|| -> Result<String, String> {
+        let enemies_str: String = env.get_string(&enemies_json)
+            .map_err(|e| format!("Failed to get string: {}", e))?
+            .into();
+        let allies_str: String = env.get_string(&allies_json)
+            .map_err(|e| format!("Failed to get string: {}", e))?
+            .into();
+        let skill_str: String = env.get_string(&skill_ready_json)
+            .map_err(|e| format!("Failed to get string: {}", e))?
+            .into();
+        
+        let enemies_vec: Vec<(i32, i32, f32)> = serde_json::from_str(&enemies_str)
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+        let allies_vec: Vec<(i32, i32)> = serde_json::from_str(&allies_str)
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+        let skill_ready: Vec<bool> = serde_json::from_str(&skill_str)
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+        let stun_str: String = env.get_string(&stun_json)
+            .map_err(|e| format!("Failed to get string: {}", e))?
+            .into();
+        let casts_str: String = env.get_string(&casts_json)
+            .map_err(|e| format!("Failed to get string: {}", e))?
+            .into();
+        let stun: Option<StunSkill> = serde_json::from_str(&stun_str)
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+        let casts: Vec<EnemyCast> = serde_json::from_str(&casts_str)
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+        
+        let enemies: Vec<(GridPos, f32)> = enemies_vec.into_iter()
+            .map(|(x, y, hp)| (GridPos::new(x, y), hp))
+            .collect();
+        let allies: Vec<GridPos> = allies_vec.into_iter()
+            .map(|(x, y)| GridPos::new(x, y))
+            .collect();
+        
+        if ACTIONS_PAUSED.load(Ordering::Relaxed) {
+            return to_json(&Vec::<CombatDecision>::new());
+        }
+
+        let self_pos = GridPos::new(self_x, self_y);
+        
+        let decisions = timed_decision(|| {
+            CombatEngine::analyze_combat_with_casts(
+                self_pos,
+                self_hp_percent,
+                &enemies,
+                &allies,
+                &skill_ready,
+                in_tower_range == JNI_TRUE,
+                None,
+                stun.as_ref(),
+                &casts,
+            )
+        });
+        
+        to_json(&decisions)
+    })();
+
+    match result {
+        Ok(json) => env.new_string(&json).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+    }
+}
+
+/// Objective timers shared across JNI calls
+fn objective_timers() -> &'static Mutex<ObjectiveTimers> {
+    static TIMERS: OnceLock<Mutex<ObjectiveTimers>> = OnceLock::new();
+    TIMERS.get_or_init(|| Mutex::new(ObjectiveTimers::new()))
+}
+
+/// Record an objective kill
+/// JNI: StrategyEngineNative.recordObjectiveKill(objective: String, currentTime: Float, respawnDelay: Float)
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_StrategyEngineNative_recordObjectiveKill<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    objective: JString<'local>,
+    current_time: jfloat,
+    respawn_delay: jfloat,
+) {
+    match env.get_string(&objective) {
+        Ok(name) => {
+            let name: String = name.into();
+            objective_timers().lock().unwrap().record_kill(&name, current_time, respawn_delay);
+        }
+        Err(e) => log::error!("recordObjectiveKill: Failed to get string: {}", e),
+    }
+}
+
+/// Get the soonest upcoming objective respawn
+/// JNI: StrategyEngineNative.getNextRespawn(currentTime: Float): String (JSON {objective, seconds} or null)
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_StrategyEngineNative_getNextRespawn<'local>(
+    env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    current_time: jfloat,
+) -> jstring {
+    let next = {
+        let timers = objective_timers().lock().unwrap();
+        timers.list_upcoming_respawns(current_time, f32::INFINITY).first().map(|(objective, seconds)| {
+            serde_json::json!({
+                "objective": objective,
+                "seconds": seconds,
+            })
+        })
+    };
+
+    match to_json(&next) {
+        Ok(json) => env.new_string(&json).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
     }
 }
 
@@ -333,26 +745,28 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_StrategyEngine
 // ============================================================================
 
 /// Parse memory maps for a process
+/// Failures here and in the other memory endpoints come back as
+/// `{"error": message, "code": n}`, see `MemoryError::code`.
 /// JNI: MemoryEngineNative.parseMemoryMaps(pid: Int): String (JSON Array)
+#[cfg(feature = "memory")]
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_parseMemoryMaps<'local>(
-    mut env: JNIEnv<'local>,
+    env: JNIEnv<'local>,
     _class: JClass<'local>,
     pid: jint,
 ) -> jstring {
-    let result = MemoryEngine::parse_memory_maps(pid as u32);
-    
+    let result = MemoryEngine::parse_memory_maps(pid as u32)
+        .and_then(|regions| to_json(&regions).map_err(MemoryError::Parse));
+
     match result {
-        Ok(regions) => {
-            let json = serde_json::to_string(&regions).unwrap_or_else(|_| "[]".to_string());
-            env.new_string(&json).unwrap().into_raw()
-        }
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Ok(json) => env.new_string(&json).unwrap().into_raw(),
+        Err(e) => env.new_string(memory_error_json(&e)).unwrap().into_raw(),
     }
 }
 
 /// Search for int32 value in memory
 /// JNI: MemoryEngineNative.searchInt32(pid: Int, value: Int, regionsJson: String, limit: Int): String
+#[cfg(feature = "memory")]
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_searchInt32<'local>(
     mut env: JNIEnv<'local>,
@@ -362,29 +776,29 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNa
     regions_json: JString<'local>,
     limit: jint,
 ) -> jstring {
-    let result = (|| -> Result<String, String> {
+    let result = (|| -> Result<String, MemoryError> {
         let regions_str: String = env.get_string(&regions_json)
-            .map_err(|e| format!("Failed to get string: {}", e))?
+            .map_err(|e| MemoryError::InvalidArgument(format!("Failed to get string: {}", e)))?
             .into();
         
         let regions: Vec<MemoryRegion> = serde_json::from_str(&regions_str)
-            .map_err(|e| format!("JSON parse error: {}", e))?;
+            .map_err(|e| MemoryError::Parse(format!("JSON parse error: {}", e)))?;
         
-        let matches = MemoryEngine::search_int32(pid as u32, value, &regions, limit as usize)?;
+        let matches = MemoryEngine::search_int32(pid as u32, value, &regions, limit as usize, false)?;
         
-        serde_json::to_string(&matches)
-            .map_err(|e| format!("JSON error: {}", e))
+        to_json(&matches).map_err(MemoryError::Parse)
     })();
 
     match result {
         Ok(json) => env.new_string(&json).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(memory_error_json(&e)).unwrap().into_raw(),
     }
 }
 
 /// Search for float32 value in memory
 /// JNI: MemoryEngineNative.searchFloat32(pid: Int, value: Float, tolerance: Float, 
 ///                                        regionsJson: String, limit: Int): String
+#[cfg(feature = "memory")]
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_searchFloat32<'local>(
     mut env: JNIEnv<'local>,
@@ -395,28 +809,28 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNa
     regions_json: JString<'local>,
     limit: jint,
 ) -> jstring {
-    let result = (|| -> Result<String, String> {
+    let result = (|| -> Result<String, MemoryError> {
         let regions_str: String = env.get_string(&regions_json)
-            .map_err(|e| format!("Failed to get string: {}", e))?
+            .map_err(|e| MemoryError::InvalidArgument(format!("Failed to get string: {}", e)))?
             .into();
         
         let regions: Vec<MemoryRegion> = serde_json::from_str(&regions_str)
-            .map_err(|e| format!("JSON parse error: {}", e))?;
+            .map_err(|e| MemoryError::Parse(format!("JSON parse error: {}", e)))?;
         
-        let matches = MemoryEngine::search_float32(pid as u32, value, tolerance, &regions, limit as usize)?;
+        let matches = MemoryEngine::search_float32(pid as u32, value, tolerance, &regions, limit as usize, false)?;
         
-        serde_json::to_string(&matches)
-            .map_err(|e| format!("JSON error: {}", e))
+        to_json(&matches).map_err(MemoryError::Parse)
     })();
 
     match result {
         Ok(json) => env.new_string(&json).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(memory_error_json(&e)).unwrap().into_raw(),
     }
 }
 
 /// Read int32 at address
 /// JNI: MemoryEngineNative.readInt32(pid: Int, address: Long): Int
+#[cfg(feature = "memory")]
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_readInt32(
     _env: JNIEnv,
@@ -427,8 +841,41 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNa
     MemoryEngine::read_int32(pid as u32, address as u64).unwrap_or(-1)
 }
 
+/// Resolve a multi-level pointer chain; returns 0 on failure
+/// JNI: MemoryEngineNative.resolvePointerChain(pid: Int, baseAddr: Long, offsetsJson: String, pointerWidth: Int): Long
+#[cfg(feature = "memory")]
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_resolvePointerChain<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    pid: jint,
+    base_addr: jlong,
+    offsets_json: JString<'local>,
+    pointer_width: jint,
+) -> jlong {
+    let result = (|| -> Result<u64, String> {
+        let offsets_str: String = env.get_string(&offsets_json)
+            .map_err(|e| format!("Failed to get string: {}", e))?
+            .into();
+        let offsets: Vec<i64> = serde_json::from_str(&offsets_str)
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+
+        let chain = PointerChain::new(base_addr as u64, offsets);
+        Ok(chain.resolve(pid as u32, pointer_width as usize)?)
+    })();
+
+    match result {
+        Ok(address) => address as jlong,
+        Err(e) => {
+            log::error!("resolvePointerChain: {}", e);
+            0
+        }
+    }
+}
+
 /// Read float32 at address
 /// JNI: MemoryEngineNative.readFloat32(pid: Int, address: Long): Float
+#[cfg(feature = "memory")]
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_readFloat32(
     _env: JNIEnv,
@@ -441,9 +888,10 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNa
 
 /// Read string at address
 /// JNI: MemoryEngineNative.readString(pid: Int, address: Long, maxLen: Int): String
+#[cfg(feature = "memory")]
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_readString<'local>(
-    mut env: JNIEnv<'local>,
+    env: JNIEnv<'local>,
     _class: JClass<'local>,
     pid: jint,
     address: jlong,
@@ -451,15 +899,16 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNa
 ) -> jstring {
     match MemoryEngine::read_string(pid as u32, address as u64, max_len as usize) {
         Ok(s) => env.new_string(&s).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("ERROR: {}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("ERROR: {}", e)).unwrap().into_raw(),
     }
 }
 
 /// Parse Unity player stats from memory data
 /// JNI: MemoryEngineNative.parseUnityStats(data: ByteArray): String (JSON)
+#[cfg(feature = "memory")]
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_parseUnityStats<'local>(
-    mut env: JNIEnv<'local>,
+    env: JNIEnv<'local>,
     _class: JClass<'local>,
     data: JByteArray<'local>,
 ) -> jstring {
@@ -467,24 +916,24 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNa
         let bytes = env.convert_byte_array(&data)
             .map_err(|e| format!("Failed to convert byte array: {}", e))?;
         
-        if let Some((hp, max_hp, mp, max_mp)) = GameDataStructures::parse_unity_stats(&bytes) {
-            Ok(format!("{{\"hp\":{},\"maxHp\":{},\"mp\":{},\"maxMp\":{}}}", hp, max_hp, mp, max_mp))
-        } else {
-            Ok("null".to_string())
-        }
+        let stats = GameDataStructures::parse_unity_stats(&bytes).map(|(hp, max_hp, mp, max_mp)| {
+            serde_json::json!({ "hp": hp, "maxHp": max_hp, "mp": mp, "maxMp": max_mp })
+        });
+        to_json(&stats)
     })();
 
     match result {
         Ok(json) => env.new_string(&json).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
     }
 }
 
 /// Parse position from memory data
 /// JNI: MemoryEngineNative.parsePosition(data: ByteArray): String (JSON)
+#[cfg(feature = "memory")]
 #[no_mangle]
 pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_parsePosition<'local>(
-    mut env: JNIEnv<'local>,
+    env: JNIEnv<'local>,
     _class: JClass<'local>,
     data: JByteArray<'local>,
 ) -> jstring {
@@ -492,15 +941,300 @@ pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNa
         let bytes = env.convert_byte_array(&data)
             .map_err(|e| format!("Failed to convert byte array: {}", e))?;
         
-        if let Some((x, y, z)) = GameDataStructures::parse_position(&bytes) {
-            Ok(format!("{{\"x\":{},\"y\":{},\"z\":{}}}", x, y, z))
-        } else {
-            Ok("null".to_string())
-        }
+        let position = GameDataStructures::parse_position(&bytes)
+            .map(|(x, y, z)| serde_json::json!({ "x": x, "y": y, "z": z }));
+        to_json(&position)
     })();
 
     match result {
         Ok(json) => env.new_string(&json).unwrap().into_raw(),
-        Err(e) => env.new_string(&format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+        Err(e) => env.new_string(format!("{{\"error\":\"{}\"}}", e)).unwrap().into_raw(),
+    }
+}
+
+/// Locate a known game's HP/position structures in its running process
+/// The signature (patterns, masks and offsets) comes from the caller as JSON,
+/// captured against the game build being targeted
+/// JNI: MemoryEngineNative.verifySignature(packageName: String, signatureJson: String): String (JSON)
+#[cfg(feature = "memory")]
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_verifySignature<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    package_name: JString<'local>,
+    signature_json: JString<'local>,
+) -> jstring {
+    let result = (|| -> Result<String, MemoryError> {
+        let package: String = env.get_string(&package_name)
+            .map_err(|e| MemoryError::InvalidArgument(format!("Failed to get string: {}", e)))?
+            .into();
+        let signature_str: String = env.get_string(&signature_json)
+            .map_err(|e| MemoryError::InvalidArgument(format!("Failed to get string: {}", e)))?
+            .into();
+
+        let signature: GameSignature = serde_json::from_str(&signature_str)
+            .map_err(|e| MemoryError::Parse(format!("Invalid signature: {}", e)))?;
+        let pid = MemoryEngine::find_pid_by_package(&package)?;
+        let regions = MemoryEngine::filter_game_regions(&MemoryEngine::parse_memory_maps(pid)?);
+
+        to_json(&signature.verify(pid, &regions)).map_err(MemoryError::Parse)
+    })();
+
+    match result {
+        Ok(json) => env.new_string(&json).unwrap().into_raw(),
+        Err(e) => env.new_string(memory_error_json(&e)).unwrap().into_raw(),
+    }
+}
+
+// ============================================================================
+// Async JNI Functions
+// ============================================================================
+
+/// JavaVM used to attach worker threads for callbacks
+static JAVA_VM: OnceLock<JavaVM> = OnceLock::new();
+
+/// Cached callback object with onResult(int, String) and onError(int, String)
+static ASYNC_CALLBACK: Mutex<Option<GlobalRef>> = Mutex::new(None);
+
+/// Dedicated pool so long scans don't starve the rayon pool used by image analysis
+fn async_pool() -> &'static rayon::ThreadPool {
+    static POOL: OnceLock<rayon::ThreadPool> = OnceLock::new();
+    POOL.get_or_init(|| {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .thread_name(|i| format!("agent-async-{}", i))
+            .build()
+            .expect("Failed to build async thread pool")
+    })
+}
+
+/// Run `job` in the background and return its request ID immediately
+/// The result is delivered through the cached callback; a panicking job is
+/// reported through onError instead of taking down the pool.
+fn spawn_async<F>(job: F) -> jint
+where
+    F: FnOnce(&AtomicBool) -> Result<String, String> + Send + 'static,
+{
+    let (id, cancel) = JobRegistry::global().register();
+    async_pool().spawn(move || {
+        let guard = JobRegistry::global().guard(id);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| job(&cancel)))
+            .unwrap_or_else(|_| Err("Request panicked".to_string()));
+        drop(guard);
+        deliver_async_result(id, result);
+    });
+    id
+}
+
+/// Invoke onResult/onError on the cached callback from a worker thread
+fn deliver_async_result(id: i32, result: Result<String, String>) {
+    let callback = ASYNC_CALLBACK.lock().unwrap().clone();
+    let (Some(vm), Some(callback)) = (JAVA_VM.get(), callback) else {
+        log::warn!("Async request {} finished but no callback is registered", id);
+        return;
+    };
+
+    let mut env = match vm.attach_current_thread() {
+        Ok(env) => env,
+        Err(e) => {
+            log::error!("Failed to attach async thread: {}", e);
+            return;
+        }
+    };
+
+    let (method, payload) = match result {
+        Ok(json) => ("onResult", json),
+        Err(e) => ("onError", e),
+    };
+
+    let payload = match env.new_string(payload) {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to create string: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = env.call_method(
+        &callback,
+        method,
+        "(ILjava/lang/String;)V",
+        &[JValue::Int(id), JValue::Object(&payload)],
+    ) {
+        log::error!("Async callback {} failed: {}", method, e);
+    }
+}
+
+/// Register the callback that receives async results
+/// JNI: AsyncNative.setCallback(callback: AsyncCallback)
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_AsyncNative_setCallback<'local>(
+    env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    callback: JObject<'local>,
+) {
+    if let Ok(vm) = env.get_java_vm() {
+        let _ = JAVA_VM.set(vm);
+    }
+
+    match env.new_global_ref(&callback) {
+        Ok(global) => *ASYNC_CALLBACK.lock().unwrap() = Some(global),
+        Err(e) => log::error!("Failed to cache async callback: {}", e),
+    }
+}
+
+/// Cancel an async request
+/// JNI: AsyncNative.cancel(requestId: Int): Boolean
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_AsyncNative_cancel(
+    _env: JNIEnv,
+    _class: JClass,
+    request_id: jint,
+) -> jboolean {
+    if JobRegistry::global().cancel(request_id) {
+        JNI_TRUE
+    } else {
+        JNI_FALSE
+    }
+}
+
+/// Search for byte pattern in memory without blocking the caller
+/// Scan failures reach onError as `{"error", "code"}` JSON like the sync endpoints.
+/// JNI: MemoryEngineNative.searchPatternAsync(pid: Int, pattern: ByteArray, regionsJson: String,
+///                                            limit: Int): Int (request ID, -1 on bad input)
+#[cfg(feature = "memory")]
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_searchPatternAsync<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    pid: jint,
+    pattern: JByteArray<'local>,
+    regions_json: JString<'local>,
+    limit: jint,
+) -> jint {
+    let input = (|| -> Result<(Vec<u8>, Vec<MemoryRegion>), String> {
+        let pattern = env.convert_byte_array(&pattern)
+            .map_err(|e| format!("Failed to convert byte array: {}", e))?;
+        let regions_str: String = env.get_string(&regions_json)
+            .map_err(|e| format!("Failed to get string: {}", e))?
+            .into();
+        let regions: Vec<MemoryRegion> = serde_json::from_str(&regions_str)
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+        Ok((pattern, regions))
+    })();
+
+    match input {
+        Ok((pattern, regions)) => spawn_async(move |cancel| {
+            let matches = MemoryEngine::search_pattern_cancellable(
+                pid as u32, &pattern, &regions, limit as usize, cancel)
+                .map_err(|e| memory_error_json(&e))?;
+            to_json(&matches)
+        }),
+        Err(e) => {
+            log::error!("searchPatternAsync: {}", e);
+            -1
+        }
+    }
+}
+
+/// Watch a value and report through the async callback when it crosses a threshold
+/// valueType: 0 = Int32, 1 = Int64, 2 = Float32, 3 = Float64
+/// Fires onResult(id, value) when the value goes below (or above) the threshold;
+/// stop it with AsyncNative.cancel(id). Polling also ends when the process exits.
+/// JNI: MemoryEngineNative.startWatchpoint(pid: Int, address: Long, valueType: Int, threshold: Double,
+///                                         below: Boolean, keepWatching: Boolean, intervalMs: Int): Int
+#[cfg(feature = "memory")]
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_startWatchpoint(
+    _env: JNIEnv,
+    _class: JClass,
+    pid: jint,
+    address: jlong,
+    value_type: jint,
+    threshold: jdouble,
+    below: jboolean,
+    keep_watching: jboolean,
+    interval_ms: jint,
+) -> jint {
+    let ty = match value_type {
+        0 => GameValueType::Int32,
+        1 => GameValueType::Int64,
+        2 => GameValueType::Float32,
+        3 => GameValueType::Float64,
+        _ => {
+            log::error!("startWatchpoint: unknown value type {}", value_type);
+            return -1;
+        }
+    };
+
+    let below = below == JNI_TRUE;
+    let watchpoint = Watchpoint::new(pid as u32, address as u64, ty, move |value| {
+        value.as_f64().is_some_and(|v| if below { v < threshold } else { v > threshold })
+    });
+    let interval = Duration::from_millis(interval_ms.max(1) as u64);
+    let keep_watching = keep_watching == JNI_TRUE;
+
+    let (id, stop) = JobRegistry::global().register();
+    std::thread::spawn(move || {
+        let _guard = JobRegistry::global().guard(id);
+        watchpoint.run(interval, keep_watching, &stop, |value| {
+            deliver_async_result(id, to_json(value));
+        });
+    });
+    id
+}
+
+/// Watch a process for libraries being loaded or unloaded (e.g. injected anti-cheat)
+/// Fires onResult(id, LibraryChanges JSON) for every change after the first poll;
+/// stop it with AsyncNative.cancel(id). Polling also ends when the process exits.
+/// JNI: MemoryEngineNative.checkLibraryChanges(pid: Int, intervalMs: Int): Int (request ID)
+#[cfg(feature = "memory")]
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_MemoryEngineNative_checkLibraryChanges(
+    _env: JNIEnv,
+    _class: JClass,
+    pid: jint,
+    interval_ms: jint,
+) -> jint {
+    let interval = Duration::from_millis(interval_ms.max(1) as u64);
+
+    let (id, stop) = JobRegistry::global().register();
+    std::thread::spawn(move || {
+        let _guard = JobRegistry::global().guard(id);
+        LibraryWatcher::new().run(pid as u32, interval, &stop, |changes| {
+            deliver_async_result(id, to_json(changes));
+        });
+    });
+    id
+}
+
+/// Find top N best moves for eliminate game without blocking the caller
+/// JNI: StrategyEngineNative.findBestEliminateMovesAsync(boardJson: String, n: Int): Int (request ID)
+#[no_mangle]
+pub extern "system" fn Java_com_example_deepseekaiassistant_agent_StrategyEngineNative_findBestEliminateMovesAsync<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    board_json: JString<'local>,
+    n: jint,
+) -> jint {
+    let input = (|| -> Result<Vec<Vec<u8>>, String> {
+        let board_str: String = env.get_string(&board_json)
+            .map_err(|e| format!("Failed to get string: {}", e))?
+            .into();
+        serde_json::from_str(&board_str)
+            .map_err(|e| format!("JSON parse error: {}", e))
+    })();
+
+    match input {
+        Ok(board) => spawn_async(move |cancel| {
+            let moves = timed_decision(|| EliminateEngine::find_best_moves_cancellable(&board, n as usize, cancel))
+                .ok_or_else(|| "Request cancelled".to_string())?;
+            to_json(&moves)
+        }),
+        Err(e) => {
+            log::error!("findBestEliminateMovesAsync: {}", e);
+            -1
+        }
     }
 }
@@ -3,20 +3,28 @@
 //! This library provides high-performance implementations for:
 //! - Image processing and pattern matching
 //! - Game strategy calculation (eliminate games, pathfinding)
-//! - Memory parsing and pattern search
+//! - Memory parsing and pattern search (`memory` feature, on by default)
+//! - Async execution of long-running requests
 //! - JNI bridge for Android integration
 
 mod image_engine;
 mod strategy_engine;
+#[cfg(feature = "memory")]
 mod memory_engine;
+mod async_jobs;
 mod jni_bridge;
+#[cfg(test)]
+mod test_utils;
 
 pub use image_engine::*;
 pub use strategy_engine::*;
+#[cfg(feature = "memory")]
 pub use memory_engine::*;
+pub use async_jobs::*;
 
 use log::LevelFilter;
 use android_logger::Config;
+use serde::{Deserialize, Serialize};
 
 /// Initialize the Rust core library
 pub fn init_library() {
@@ -31,6 +39,135 @@ pub fn init_library() {
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Version of the JSON shapes returned over JNI; bump when result fields change
+/// (together with `AgentCore.SCHEMA_VERSION` on the Kotlin side)
+/// - 1: initial shapes
+/// - 2: `EliminateMove.special`, `DetectedElement.state`
+/// - 3: `PathResult.nodes_expanded`
+/// - 4: `DetectedElement.fill_percent`
+pub const SCHEMA_VERSION: u32 = 4;
+
+/// JNI result wrapper so callers can detect schema changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonEnvelope<T> {
+    pub version: u32,
+    pub data: T,
+}
+
+impl<T> JsonEnvelope<T> {
+    /// Wrap `data` with the current `SCHEMA_VERSION`
+    pub fn new(data: T) -> Self {
+        Self { version: SCHEMA_VERSION, data }
+    }
+}
+
+/// Build configuration reported by `build_info`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildInfo {
+    pub version: String,
+    pub schema_version: u32,
+    /// Enabled optional cargo features
+    pub features: Vec<String>,
+    pub target_arch: String,
+    pub target_os: String,
+    pub debug_assertions: bool,
+    pub rayon_threads: usize,
+}
+
+/// How this library was built, for bug reports
+pub fn build_info() -> BuildInfo {
+    let mut features = Vec::new();
+    if cfg!(feature = "memory") {
+        features.push("memory".to_string());
+    }
+
+    BuildInfo {
+        version: VERSION.to_string(),
+        schema_version: SCHEMA_VERSION,
+        features,
+        target_arch: std::env::consts::ARCH.to_string(),
+        target_os: std::env::consts::OS.to_string(),
+        debug_assertions: cfg!(debug_assertions),
+        rayon_threads: rayon::current_num_threads(),
+    }
+}
+
+/// Running latency and activity counters for the current session
+#[derive(Debug, Clone)]
+pub struct SessionStats {
+    started: std::time::Instant,
+    detections: u64,
+    detection_ms: f64,
+    decisions: u64,
+    decision_ms: f64,
+    moves_executed: u64,
+    kills: u64,
+}
+
+/// Snapshot of `SessionStats`; averages are 0 before anything is recorded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionReport {
+    /// Frames run through a detector
+    pub total_frames: u64,
+    pub avg_detection_ms: f32,
+    pub avg_decision_ms: f32,
+    pub moves_executed: u64,
+    pub kills: u64,
+    pub uptime_s: f32,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self {
+            started: std::time::Instant::now(),
+            detections: 0,
+            detection_ms: 0.0,
+            decisions: 0,
+            decision_ms: 0.0,
+            moves_executed: 0,
+            kills: 0,
+        }
+    }
+
+    /// Record one analyzed frame and how long detection took
+    pub fn record_detection(&mut self, elapsed_ms: f32) {
+        self.detections += 1;
+        self.detection_ms += elapsed_ms as f64;
+    }
+
+    /// Record one strategy decision (move search, path, combat) and its latency
+    pub fn record_decision(&mut self, elapsed_ms: f32) {
+        self.decisions += 1;
+        self.decision_ms += elapsed_ms as f64;
+    }
+
+    pub fn record_move_executed(&mut self) {
+        self.moves_executed += 1;
+    }
+
+    pub fn record_kill(&mut self) {
+        self.kills += 1;
+    }
+
+    pub fn report(&self) -> SessionReport {
+        let average = |total: f64, count: u64| if count == 0 { 0.0 } else { (total / count as f64) as f32 };
+        SessionReport {
+            total_frames: self.detections,
+            avg_detection_ms: average(self.detection_ms, self.detections),
+            avg_decision_ms: average(self.decision_ms, self.decisions),
+            moves_executed: self.moves_executed,
+            kills: self.kills,
+            uptime_s: self.started.elapsed().as_secs_f32(),
+        }
+    }
+}
+
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,4 +176,53 @@ mod tests {
     fn test_version() {
         assert_eq!(VERSION, "1.0.0");
     }
+
+    #[test]
+    fn test_json_envelope_round_trip() {
+        let json = serde_json::to_string(&JsonEnvelope::new(vec![1u8, 2])).unwrap();
+        assert_eq!(json, format!("{{\"version\":{},\"data\":[1,2]}}", SCHEMA_VERSION));
+
+        let parsed: JsonEnvelope<Vec<u8>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.version, SCHEMA_VERSION);
+        assert_eq!(parsed.data, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_build_info() {
+        let info = build_info();
+        assert_eq!(info.version, VERSION);
+        assert_eq!(info.schema_version, SCHEMA_VERSION);
+        assert_eq!(info.features.contains(&"memory".to_string()), cfg!(feature = "memory"));
+        assert_eq!(info.target_arch, std::env::consts::ARCH);
+        assert!(info.rayon_threads >= 1);
+    }
+
+    #[test]
+    fn test_session_stats_report() {
+        let mut stats = SessionStats::new();
+        assert_eq!(stats.report().avg_detection_ms, 0.0);
+
+        stats.record_detection(10.0);
+        stats.record_detection(20.0);
+        stats.record_decision(4.0);
+        stats.record_move_executed();
+        stats.record_kill();
+        stats.record_kill();
+
+        let report = stats.report();
+        assert_eq!(report.total_frames, 2);
+        assert_eq!(report.avg_detection_ms, 15.0);
+        assert_eq!(report.avg_decision_ms, 4.0);
+        assert_eq!((report.moves_executed, report.kills), (1, 2));
+        assert!(report.uptime_s >= 0.0);
+    }
+
+    /// Built by `cargo test --no-default-features`; only image and strategy code is linked
+    #[cfg(not(feature = "memory"))]
+    #[test]
+    fn test_builds_without_memory_engine() {
+        let image = ImageData { width: 4, height: 4, pixels: vec![Rgb::new(0, 0, 0); 16], alpha: None };
+        assert!(ImageEngine::detect_health_bars(&image).is_empty());
+        assert_eq!(GridPos::new(0, 0).disk(1).len(), 5);
+    }
 }
@@ -5,12 +5,102 @@
 //! - Pattern searching in memory regions
 //! - Game data structure parsing
 
-use memmap2::MmapOptions;
-use regex::bytes::Regex;
+use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
-use std::fs::File;
+use std::fmt;
+use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, Read};
-use rayon::prelude::*;
+use std::os::unix::fs::FileExt;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Bytes scanned between checks of a cancellation flag
+const CANCEL_CHECK_INTERVAL: usize = 1 << 20;
+
+/// Default window size for chunked region reads (4MB)
+pub const DEFAULT_SCAN_CHUNK_SIZE: usize = 4 << 20;
+
+/// Writes closer than this many bytes are merged into one pwrite
+pub const DEFAULT_WRITE_GAP: u64 = 64;
+
+/// Upper bound on the total payload of a single write batch
+pub const MAX_WRITE_BATCH_BYTES: usize = 65536;
+
+/// Addresses returned by `profile_write_hotspots`
+pub const MAX_HOT_ADDRESSES: usize = 200;
+
+/// `verify_stable_read` gives up after `retries` times this many reads
+const STABLE_READ_ATTEMPT_FACTOR: usize = 4;
+
+/// Errors from process memory access
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemoryError {
+    /// The pid or package that couldn't be found
+    ProcessNotFound(String),
+    PermissionDenied,
+    Io(String),
+    InvalidArgument(String),
+    Parse(String),
+    /// A cancellable scan saw its flag set
+    Cancelled,
+    /// The value kept changing across every read in `verify_stable_read`
+    Unstable { address: u64, attempts: usize },
+    /// An AOB pattern matched nowhere in the scanned regions
+    PatternNotFound,
+}
+
+impl MemoryError {
+    /// Stable numeric code per variant, reported to the JNI caller
+    pub fn code(&self) -> i32 {
+        match self {
+            MemoryError::ProcessNotFound(_) => 1,
+            MemoryError::PermissionDenied => 2,
+            MemoryError::Io(_) => 3,
+            MemoryError::InvalidArgument(_) => 4,
+            MemoryError::Parse(_) => 5,
+            MemoryError::Cancelled => 6,
+            MemoryError::Unstable { .. } => 7,
+            MemoryError::PatternNotFound => 8,
+        }
+    }
+}
+
+impl fmt::Display for MemoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryError::ProcessNotFound(target) => write!(f, "Process not found: {}", target),
+            MemoryError::PermissionDenied => write!(f, "Permission denied"),
+            MemoryError::Io(msg) | MemoryError::InvalidArgument(msg) | MemoryError::Parse(msg) => {
+                write!(f, "{}", msg)
+            }
+            MemoryError::Cancelled => write!(f, "Scan cancelled"),
+            MemoryError::Unstable { address, attempts } => {
+                write!(f, "Value at {:#x} kept changing over {} reads", address, attempts)
+            }
+            MemoryError::PatternNotFound => write!(f, "Pattern not found"),
+        }
+    }
+}
+
+impl std::error::Error for MemoryError {}
+
+impl From<std::io::Error> for MemoryError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => MemoryError::ProcessNotFound(e.to_string()),
+            std::io::ErrorKind::PermissionDenied => MemoryError::PermissionDenied,
+            _ => MemoryError::Io(e.to_string()),
+        }
+    }
+}
+
+impl From<MemoryError> for String {
+    fn from(e: MemoryError) -> Self {
+        e.to_string()
+    }
+}
 
 /// Memory region information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,6 +151,38 @@ impl MemoryRegion {
     }
 }
 
+/// Byte order of values in the target process (little-endian unless emulated/ported)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Endian {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endian {
+    pub fn u32_from_bytes(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            Endian::Little => u32::from_le_bytes(bytes),
+            Endian::Big => u32::from_be_bytes(bytes),
+        }
+    }
+
+    pub fn u32_to_bytes(self, value: u32) -> [u8; 4] {
+        match self {
+            Endian::Little => value.to_le_bytes(),
+            Endian::Big => value.to_be_bytes(),
+        }
+    }
+
+    pub fn i32_from_bytes(self, bytes: [u8; 4]) -> i32 {
+        self.u32_from_bytes(bytes) as i32
+    }
+
+    pub fn f32_from_bytes(self, bytes: [u8; 4]) -> f32 {
+        f32::from_bits(self.u32_from_bytes(bytes))
+    }
+}
+
 /// Pattern match result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatternMatch {
@@ -70,6 +192,102 @@ pub struct PatternMatch {
     pub matched_bytes: Vec<u8>,
 }
 
+/// Sorted set of unique match addresses for combining scans
+/// e.g. "value == 100" ∩ "value decreased" when narrowing down HP.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchSet {
+    addresses: Vec<u64>,
+}
+
+impl MatchSet {
+    /// Addresses of `matches`, deduplicated
+    pub fn from_matches(matches: Vec<PatternMatch>) -> Self {
+        matches.into_iter().map(|m| m.address).collect()
+    }
+
+    /// Addresses in both sets
+    pub fn intersect(&self, other: &MatchSet) -> MatchSet {
+        self.merge(other, false, true, false)
+    }
+
+    /// Addresses in either set
+    pub fn union(&self, other: &MatchSet) -> MatchSet {
+        self.merge(other, true, true, true)
+    }
+
+    /// Addresses in `self` but not in `other`
+    pub fn difference(&self, other: &MatchSet) -> MatchSet {
+        self.merge(other, true, false, false)
+    }
+
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+
+    /// Binary search, O(log N)
+    pub fn contains(&self, address: u64) -> bool {
+        self.addresses.binary_search(&address).is_ok()
+    }
+
+    /// Addresses in ascending order
+    pub fn addresses(&self) -> &[u64] {
+        &self.addresses
+    }
+
+    /// Sorted merge keeping addresses only in self, in both, or only in other
+    fn merge(&self, other: &MatchSet, only_self: bool, both: bool, only_other: bool) -> MatchSet {
+        let (a, b) = (&self.addresses, &other.addresses);
+        let (mut i, mut j) = (0, 0);
+        let mut addresses = Vec::new();
+
+        while i < a.len() || j < b.len() {
+            match (a.get(i), b.get(j)) {
+                (Some(&x), Some(&y)) if x == y => {
+                    if both {
+                        addresses.push(x);
+                    }
+                    i += 1;
+                    j += 1;
+                }
+                (Some(&x), Some(&y)) if x < y => {
+                    if only_self {
+                        addresses.push(x);
+                    }
+                    i += 1;
+                }
+                (Some(&x), None) => {
+                    if only_self {
+                        addresses.push(x);
+                    }
+                    i += 1;
+                }
+                (_, Some(&y)) => {
+                    if only_other {
+                        addresses.push(y);
+                    }
+                    j += 1;
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+
+        MatchSet { addresses }
+    }
+}
+
+impl FromIterator<u64> for MatchSet {
+    fn from_iter<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        let mut addresses: Vec<u64> = iter.into_iter().collect();
+        addresses.sort_unstable();
+        addresses.dedup();
+        MatchSet { addresses }
+    }
+}
+
 /// Game data value types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GameValue {
@@ -81,6 +299,61 @@ pub enum GameValue {
     Bytes(Vec<u8>),
 }
 
+impl GameValue {
+    /// Numeric value as f64 (None for strings and raw bytes)
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            GameValue::Int32(v) => Some(*v as f64),
+            GameValue::Int64(v) => Some(*v as f64),
+            GameValue::Float32(v) => Some(*v as f64),
+            GameValue::Float64(v) => Some(*v),
+            GameValue::String(_) | GameValue::Bytes(_) => None,
+        }
+    }
+}
+
+/// Primitive value types for typed scans
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameValueType {
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+}
+
+impl GameValueType {
+    /// Size of the value in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            GameValueType::Int32 | GameValueType::Float32 => 4,
+            GameValueType::Int64 | GameValueType::Float64 => 8,
+        }
+    }
+
+    /// Decode little-endian bytes into a typed value
+    pub fn decode(&self, bytes: &[u8]) -> Option<GameValue> {
+        let bytes = bytes.get(..self.size())?;
+        Some(match self {
+            GameValueType::Int32 => GameValue::Int32(i32::from_le_bytes(bytes.try_into().ok()?)),
+            GameValueType::Int64 => GameValue::Int64(i64::from_le_bytes(bytes.try_into().ok()?)),
+            GameValueType::Float32 => GameValue::Float32(f32::from_le_bytes(bytes.try_into().ok()?)),
+            GameValueType::Float64 => GameValue::Float64(f64::from_le_bytes(bytes.try_into().ok()?)),
+        })
+    }
+
+    /// Widen little-endian bytes to i64 for storage.
+    /// Integers are sign-extended; floats keep their raw bit pattern so that
+    /// later passes can compare them exactly.
+    pub fn widen(&self, bytes: &[u8]) -> Option<i64> {
+        match self {
+            GameValueType::Int32 => Some(i32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as i64),
+            GameValueType::Int64 => Some(i64::from_le_bytes(bytes.get(..8)?.try_into().ok()?)),
+            GameValueType::Float32 => Some(u32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as i64),
+            GameValueType::Float64 => Some(u64::from_le_bytes(bytes.get(..8)?.try_into().ok()?) as i64),
+        }
+    }
+}
+
 /// Parsed game data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameData {
@@ -93,17 +366,30 @@ pub struct GameData {
 pub struct MemoryEngine;
 
 impl MemoryEngine {
+    /// Open /proc/<pid>/<name>, telling a missing process apart from missing permissions
+    fn open_proc_file(pid: u32, name: &str) -> Result<File, MemoryError> {
+        Self::open_proc_file_with(pid, name, OpenOptions::new().read(true))
+    }
+
+    /// Same as `open_proc_file`, with explicit open options (e.g. for writes)
+    fn open_proc_file_with(pid: u32, name: &str, options: &OpenOptions) -> Result<File, MemoryError> {
+        let path = format!("/proc/{}/{}", pid, name);
+        options.open(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => MemoryError::ProcessNotFound(pid.to_string()),
+            std::io::ErrorKind::PermissionDenied => MemoryError::PermissionDenied,
+            _ => MemoryError::Io(format!("Failed to open {}: {}", path, e)),
+        })
+    }
+
     /// Parse /proc/pid/maps to get memory regions
-    pub fn parse_memory_maps(pid: u32) -> Result<Vec<MemoryRegion>, String> {
-        let maps_path = format!("/proc/{}/maps", pid);
-        let file = File::open(&maps_path)
-            .map_err(|e| format!("Failed to open {}: {}", maps_path, e))?;
+    pub fn parse_memory_maps(pid: u32) -> Result<Vec<MemoryRegion>, MemoryError> {
+        let file = Self::open_proc_file(pid, "maps")?;
 
         let reader = BufReader::new(file);
         let mut regions = Vec::new();
 
         for line in reader.lines() {
-            let line = line.map_err(|e| format!("Failed to read line: {}", e))?;
+            let line = line.map_err(|e| MemoryError::Io(format!("Failed to read line: {}", e)))?;
             if let Some(region) = Self::parse_maps_line(&line) {
                 regions.push(region);
             }
@@ -112,6 +398,32 @@ impl MemoryEngine {
         Ok(regions)
     }
 
+    /// Find the pid of a running app by its package name (/proc/<pid>/cmdline)
+    pub fn find_pid_by_package(package_name: &str) -> Result<u32, MemoryError> {
+        let entries = std::fs::read_dir("/proc")
+            .map_err(|e| MemoryError::Io(format!("Failed to read /proc: {}", e)))?;
+
+        for entry in entries.flatten() {
+            let pid = match entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) {
+                Some(pid) => pid,
+                None => continue,
+            };
+
+            let cmdline = match std::fs::read(entry.path().join("cmdline")) {
+                Ok(cmdline) => cmdline,
+                Err(_) => continue,
+            };
+
+            // cmdline is NUL-separated; the first argument is the process name
+            let name = cmdline.split(|&b| b == 0).next().unwrap_or(&[]);
+            if name == package_name.as_bytes() {
+                return Ok(pid);
+            }
+        }
+
+        Err(MemoryError::ProcessNotFound(package_name.to_string()))
+    }
+
     /// Parse a single line from /proc/pid/maps
     fn parse_maps_line(line: &str) -> Option<MemoryRegion> {
         let parts: Vec<&str> = line.split_whitespace().collect();
@@ -164,47 +476,207 @@ impl MemoryEngine {
         pattern: &[u8],
         regions: &[MemoryRegion],
         limit: usize,
-    ) -> Result<Vec<PatternMatch>, String> {
-        let mem_path = format!("/proc/{}/mem", pid);
-        let mut file = File::open(&mem_path)
-            .map_err(|e| format!("Failed to open {}: {}", mem_path, e))?;
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        Self::search_pattern_cancellable(pid, pattern, regions, limit, &AtomicBool::new(false))
+    }
+
+    /// Search for byte pattern in memory, aborting when `cancel` is set
+    /// The flag is checked before each chunk and every `CANCEL_CHECK_INTERVAL` bytes.
+    pub fn search_pattern_cancellable(
+        pid: u32,
+        pattern: &[u8],
+        regions: &[MemoryRegion],
+        limit: usize,
+        cancel: &AtomicBool,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        let file = Self::open_proc_file(pid, "mem")?;
+        Self::scan_chunked(&file, pattern, regions, limit, DEFAULT_SCAN_CHUNK_SIZE, false, cancel)
+    }
+
+    /// Search for byte pattern reading each region in `chunk_size` windows
+    /// Bounds peak memory to roughly one chunk instead of a whole region.
+    pub fn search_pattern_chunked(
+        pid: u32,
+        pattern: &[u8],
+        regions: &[MemoryRegion],
+        limit: usize,
+        chunk_size: usize,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        let file = Self::open_proc_file(pid, "mem")?;
+        Self::scan_chunked(&file, pattern, regions, limit, chunk_size, false, &AtomicBool::new(false))
+    }
+
+    /// With `one_per_region`, scanning moves to the next region after its first match.
+    fn scan_chunked(
+        file: &File,
+        pattern: &[u8],
+        regions: &[MemoryRegion],
+        limit: usize,
+        chunk_size: usize,
+        one_per_region: bool,
+        cancel: &AtomicBool,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        if pattern.is_empty() {
+            return Err(MemoryError::InvalidArgument("Empty pattern".to_string()));
+        }
+        if chunk_size == 0 {
+            return Err(MemoryError::InvalidArgument("Chunk size must be non-zero".to_string()));
+        }
 
+        Self::scan_chunked_with(pattern, regions, limit, chunk_size, one_per_region, cancel, |address, buffer| {
+            file.read_exact_at(buffer, address).is_ok()
+        })
+    }
+
+    /// `scan_chunked` over an arbitrary reader (fills the buffer, false on failure)
+    /// A chunk that can't be read (e.g. an unmapped guard page) is skipped and
+    /// the scan resumes with the next chunk of the same region.
+    fn scan_chunked_with(
+        pattern: &[u8],
+        regions: &[MemoryRegion],
+        limit: usize,
+        chunk_size: usize,
+        one_per_region: bool,
+        cancel: &AtomicBool,
+        mut read: impl FnMut(u64, &mut [u8]) -> bool,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
         let mut matches = Vec::new();
         let pattern_len = pattern.len();
+        // Consecutive chunks overlap so matches spanning a boundary are still seen
+        let overlap = pattern_len - 1;
+        let mut buffer = Vec::new();
 
-        for region in regions {
+        'regions: for region in regions {
             if !region.is_readable() || region.size() == 0 {
                 continue;
             }
 
-            // Read region data
-            let mut buffer = vec![0u8; region.size() as usize];
-            
-            // Seek to region start
-            use std::io::Seek;
-            if file.seek(std::io::SeekFrom::Start(region.start_addr)).is_err() {
-                continue;
+            let region_size = region.size() as usize;
+            let mut offset = 0usize;
+
+            while offset < region_size {
+                if cancel.load(AtomicOrdering::Relaxed) {
+                    return Err(MemoryError::Cancelled);
+                }
+
+                let read_len = (chunk_size + overlap).min(region_size - offset);
+                buffer.resize(read_len, 0);
+
+                let chunk_addr = region.start_addr + offset as u64;
+                if !read(chunk_addr, &mut buffer) {
+                    log::debug!(
+                        "Skipping unreadable range {:#x}-{:#x}",
+                        chunk_addr,
+                        chunk_addr + read_len as u64
+                    );
+                    offset += chunk_size;
+                    continue;
+                }
+
+                // Only windows starting inside this chunk; later ones belong to the next
+                for (i, window) in buffer.windows(pattern_len).take(chunk_size).enumerate() {
+                    if i % CANCEL_CHECK_INTERVAL == 0 && i > 0 && cancel.load(AtomicOrdering::Relaxed) {
+                        return Err(MemoryError::Cancelled);
+                    }
+
+                    if window == pattern {
+                        let offset_in_region = (offset + i) as u64;
+                        matches.push(PatternMatch {
+                            address: region.start_addr + offset_in_region,
+                            region_start: region.start_addr,
+                            offset_in_region,
+                            matched_bytes: window.to_vec(),
+                        });
+
+                        if matches.len() >= limit {
+                            return Ok(matches);
+                        }
+                        if one_per_region {
+                            continue 'regions;
+                        }
+                    }
+                }
+
+                offset += chunk_size;
             }
+        }
+
+        Ok(matches)
+    }
+
+    /// Search for several patterns in one pass (e.g. 100 as int32 or as float32)
+    /// Each match comes with the index of the pattern it matched; where several
+    /// patterns match at one address each is reported, lowest index first.
+    pub fn search_any(
+        pid: u32,
+        patterns: &[Vec<u8>],
+        regions: &[MemoryRegion],
+        limit: usize,
+    ) -> Result<Vec<(PatternMatch, usize)>, MemoryError> {
+        Self::search_any_in(&Self::open_proc_file(pid, "mem")?, patterns, regions, limit)
+    }
+
+    fn search_any_in(
+        file: &File,
+        patterns: &[Vec<u8>],
+        regions: &[MemoryRegion],
+        limit: usize,
+    ) -> Result<Vec<(PatternMatch, usize)>, MemoryError> {
+        if patterns.is_empty() || patterns.iter().any(Vec::is_empty) {
+            return Err(MemoryError::InvalidArgument("Empty pattern".to_string()));
+        }
+
+        // Candidate patterns by first byte, so most offsets are rejected with one lookup
+        let mut by_first_byte: Vec<Vec<usize>> = vec![Vec::new(); 256];
+        for (index, pattern) in patterns.iter().enumerate() {
+            by_first_byte[pattern[0] as usize].push(index);
+        }
+
+        let overlap = patterns.iter().map(Vec::len).max().unwrap_or(1) - 1;
+        let chunk_size = DEFAULT_SCAN_CHUNK_SIZE;
+        let mut matches = Vec::new();
+        let mut buffer = Vec::new();
 
-            // Read region
-            if file.read_exact(&mut buffer).is_err() {
+        for region in regions {
+            if !region.is_readable() || region.size() == 0 {
                 continue;
             }
 
-            // Search for pattern in buffer
-            for (i, window) in buffer.windows(pattern_len).enumerate() {
-                if window == pattern {
-                    matches.push(PatternMatch {
-                        address: region.start_addr + i as u64,
-                        region_start: region.start_addr,
-                        offset_in_region: i as u64,
-                        matched_bytes: window.to_vec(),
-                    });
+            let region_size = region.size() as usize;
+            let mut offset = 0usize;
 
-                    if matches.len() >= limit {
-                        return Ok(matches);
+            while offset < region_size {
+                let read_len = (chunk_size + overlap).min(region_size - offset);
+                buffer.resize(read_len, 0);
+                if file.read_exact_at(&mut buffer, region.start_addr + offset as u64).is_err() {
+                    offset += chunk_size;
+                    continue;
+                }
+
+                for i in 0..read_len.min(chunk_size) {
+                    for &index in &by_first_byte[buffer[i] as usize] {
+                        let pattern = &patterns[index];
+                        if !buffer[i..].starts_with(pattern) {
+                            continue;
+                        }
+
+                        let offset_in_region = (offset + i) as u64;
+                        matches.push((
+                            PatternMatch {
+                                address: region.start_addr + offset_in_region,
+                                region_start: region.start_addr,
+                                offset_in_region,
+                                matched_bytes: pattern.clone(),
+                            },
+                            index,
+                        ));
+                        if matches.len() >= limit {
+                            return Ok(matches);
+                        }
                     }
                 }
+
+                offset += chunk_size;
             }
         }
 
@@ -218,15 +690,21 @@ impl MemoryEngine {
         mask: &[bool], // true = must match, false = wildcard
         regions: &[MemoryRegion],
         limit: usize,
-    ) -> Result<Vec<PatternMatch>, String> {
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        Self::search_pattern_masked_in(&Self::open_proc_file(pid, "mem")?, pattern, mask, regions, limit)
+    }
+
+    fn search_pattern_masked_in(
+        file: &File,
+        pattern: &[u8],
+        mask: &[bool],
+        regions: &[MemoryRegion],
+        limit: usize,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
         if pattern.len() != mask.len() {
-            return Err("Pattern and mask length mismatch".to_string());
+            return Err(MemoryError::InvalidArgument("Pattern and mask length mismatch".to_string()));
         }
 
-        let mem_path = format!("/proc/{}/mem", pid);
-        let mut file = File::open(&mem_path)
-            .map_err(|e| format!("Failed to open {}: {}", mem_path, e))?;
-
         let mut matches = Vec::new();
         let pattern_len = pattern.len();
 
@@ -236,13 +714,7 @@ impl MemoryEngine {
             }
 
             let mut buffer = vec![0u8; region.size() as usize];
-            
-            use std::io::Seek;
-            if file.seek(std::io::SeekFrom::Start(region.start_addr)).is_err() {
-                continue;
-            }
-
-            if file.read_exact(&mut buffer).is_err() {
+            if file.read_exact_at(&mut buffer, region.start_addr).is_err() {
                 continue;
             }
 
@@ -271,27 +743,151 @@ impl MemoryEngine {
     }
 
     /// Search for 32-bit integer value
+    /// `one_per_region` keeps only the first match in each region, a quick
+    /// fingerprint of which regions hold the value before a full scan.
+    /// Opens /proc/<pid>/mem per call; `SafeMemoryReader::search_int32` keeps it open.
     pub fn search_int32(
         pid: u32,
         value: i32,
         regions: &[MemoryRegion],
         limit: usize,
-    ) -> Result<Vec<PatternMatch>, String> {
-        Self::search_pattern(pid, &value.to_le_bytes(), regions, limit)
+        one_per_region: bool,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        Self::search_int32_endian(pid, value, regions, limit, one_per_region, Endian::Little)
+    }
+
+    /// `search_int32` for a target with the given byte order
+    pub fn search_int32_endian(
+        pid: u32,
+        value: i32,
+        regions: &[MemoryRegion],
+        limit: usize,
+        one_per_region: bool,
+        endian: Endian,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        let file = Self::open_proc_file(pid, "mem")?;
+        Self::scan_chunked(&file, &endian.u32_to_bytes(value as u32), regions, limit, DEFAULT_SCAN_CHUNK_SIZE,
+            one_per_region, &AtomicBool::new(false))
+    }
+
+    /// Search for a 32-bit integer one page at a time
+    /// `cursor` is the address of the last match already seen (0 to start);
+    /// the returned cursor continues after this page, or is `None` when done.
+    pub fn search_int32_paged(
+        pid: u32,
+        value: i32,
+        regions: &[MemoryRegion],
+        page_size: usize,
+        cursor: u64,
+    ) -> Result<(Vec<PatternMatch>, Option<u64>), MemoryError> {
+        if page_size == 0 {
+            return Err(MemoryError::InvalidArgument("Page size must be non-zero".to_string()));
+        }
+
+        // Matches must come out in address order for the cursor to be meaningful
+        let mut sorted: Vec<&MemoryRegion> = regions.iter().collect();
+        sorted.sort_by_key(|r| r.start_addr);
+
+        let resume_at = if cursor == 0 { 0 } else { cursor + 1 };
+        let mut remaining = Vec::new();
+        let mut original_starts = FxHashMap::default();
+        for region in sorted {
+            if region.end_addr <= resume_at {
+                continue;
+            }
+            let start_addr = region.start_addr.max(resume_at);
+            original_starts.insert(start_addr, region.start_addr);
+            remaining.push(MemoryRegion { start_addr, ..region.clone() });
+        }
+
+        // One extra match tells us whether another page exists
+        let file = Self::open_proc_file(pid, "mem")?;
+        let mut matches = Self::scan_chunked(&file, &value.to_le_bytes(), &remaining, page_size + 1,
+            DEFAULT_SCAN_CHUNK_SIZE, false, &AtomicBool::new(false))?;
+
+        for m in &mut matches {
+            m.region_start = original_starts[&m.region_start];
+            m.offset_in_region = m.address - m.region_start;
+        }
+
+        let next_cursor = if matches.len() > page_size {
+            matches.truncate(page_size);
+            matches.last().map(|m| m.address)
+        } else {
+            None
+        };
+
+        Ok((matches, next_cursor))
     }
 
     /// Search for 32-bit float value (with tolerance)
+    /// `one_per_region` keeps only the first match in each region.
     pub fn search_float32(
         pid: u32,
         value: f32,
         tolerance: f32,
         regions: &[MemoryRegion],
         limit: usize,
-    ) -> Result<Vec<PatternMatch>, String> {
-        let mem_path = format!("/proc/{}/mem", pid);
-        let mut file = File::open(&mem_path)
-            .map_err(|e| format!("Failed to open {}: {}", mem_path, e))?;
+        one_per_region: bool,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        Self::search_float32_endian(pid, value, tolerance, regions, limit, one_per_region, Endian::Little)
+    }
+
+    /// `search_float32` for a target with the given byte order
+    pub fn search_float32_endian(
+        pid: u32,
+        value: f32,
+        tolerance: f32,
+        regions: &[MemoryRegion],
+        limit: usize,
+        one_per_region: bool,
+        endian: Endian,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        Self::scan_floats(&Self::open_proc_file(pid, "mem")?, regions, limit, one_per_region, endian, |found| {
+            (found - value).abs() <= tolerance
+        })
+    }
+
+    /// Search for 32-bit floats within `max_ulps` representable values of `value`
+    /// Unlike an absolute tolerance this scales with magnitude: 4 ULPs is ~3e-5
+    /// around 100.0 and ~4e-3 around 10000.0. +0.0 and -0.0 are 0 ULPs apart and
+    /// denormals count like any other step, so near zero the window is tiny
+    /// (1 ULP from 0.0 is ~1.4e-45); use `search_float32` for values close to zero.
+    pub fn search_float32_ulps(
+        pid: u32,
+        value: f32,
+        max_ulps: u32,
+        regions: &[MemoryRegion],
+        limit: usize,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        Self::scan_floats(&Self::open_proc_file(pid, "mem")?, regions, limit, false, Endian::Little, |found| {
+            Self::ulps_distance(found, value) <= max_ulps as u64
+        })
+    }
+
+    /// Number of representable f32 values between `a` and `b`
+    fn ulps_distance(a: f32, b: f32) -> u64 {
+        // Map the sign-magnitude bit patterns onto one monotonic integer line
+        let ordered = |f: f32| {
+            let bits = f.to_bits();
+            if bits & 0x8000_0000 != 0 {
+                -((bits & 0x7FFF_FFFF) as i64)
+            } else {
+                bits as i64
+            }
+        };
+        ordered(a).abs_diff(ordered(b))
+    }
 
+    /// Aligned 4-byte float scan keeping finite values accepted by `accept`
+    fn scan_floats(
+        file: &File,
+        regions: &[MemoryRegion],
+        limit: usize,
+        one_per_region: bool,
+        endian: Endian,
+        accept: impl Fn(f32) -> bool,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
         let mut matches = Vec::new();
 
         for region in regions {
@@ -300,22 +896,16 @@ impl MemoryEngine {
             }
 
             let mut buffer = vec![0u8; region.size() as usize];
-            
-            use std::io::Seek;
-            if file.seek(std::io::SeekFrom::Start(region.start_addr)).is_err() {
-                continue;
-            }
-
-            if file.read_exact(&mut buffer).is_err() {
+            if file.read_exact_at(&mut buffer, region.start_addr).is_err() {
                 continue;
             }
 
             // Search for float values
             for i in (0..buffer.len() - 3).step_by(4) {
                 let bytes: [u8; 4] = buffer[i..i + 4].try_into().unwrap();
-                let found_value = f32::from_le_bytes(bytes);
+                let found_value = endian.f32_from_bytes(bytes);
 
-                if (found_value - value).abs() <= tolerance && found_value.is_finite() {
+                if found_value.is_finite() && accept(found_value) {
                     matches.push(PatternMatch {
                         address: region.start_addr + i as u64,
                         region_start: region.start_addr,
@@ -326,6 +916,9 @@ impl MemoryEngine {
                     if matches.len() >= limit {
                         return Ok(matches);
                     }
+                    if one_per_region {
+                        break;
+                    }
                 }
             }
         }
@@ -333,49 +926,560 @@ impl MemoryEngine {
         Ok(matches)
     }
 
-    /// Read value at specific address
-    pub fn read_value(pid: u32, address: u64, size: usize) -> Result<Vec<u8>, String> {
-        let mem_path = format!("/proc/{}/mem", pid);
-        let mut file = File::open(&mem_path)
-            .map_err(|e| format!("Failed to open {}: {}", mem_path, e))?;
+    /// Refine float matches whose value drifts a little each frame (positions)
+    /// Keeps a previous match if its value moved by less than `tolerance`; otherwise
+    /// looks for the old value within +/-12 bytes in case the field shifted in its struct.
+    pub fn next_scan_float_near(
+        pid: u32,
+        previous: &[PatternMatch],
+        tolerance: f32,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        let file = Self::open_proc_file(pid, "mem")?;
 
-        use std::io::Seek;
-        file.seek(std::io::SeekFrom::Start(address))
-            .map_err(|e| format!("Failed to seek: {}", e))?;
+        Ok(Self::next_scan_float_near_with(previous, tolerance, |address, buffer| {
+            file.read_exact_at(buffer, address).is_ok()
+        }))
+    }
 
-        let mut buffer = vec![0u8; size];
-        file.read_exact(&mut buffer)
-            .map_err(|e| format!("Failed to read: {}", e))?;
+    /// `next_scan_float_near` over an arbitrary reader (fills the buffer, false on failure)
+    fn next_scan_float_near_with(
+        previous: &[PatternMatch],
+        tolerance: f32,
+        mut read: impl FnMut(u64, &mut [u8]) -> bool,
+    ) -> Vec<PatternMatch> {
+        const WINDOW: u64 = 12;
+        let mut results = Vec::new();
+
+        for prev in previous {
+            let old_value = match prev.matched_bytes.get(..4).and_then(|b| b.try_into().ok()) {
+                Some(bytes) => f32::from_le_bytes(bytes),
+                None => continue,
+            };
+
+            let is_near = |bytes: [u8; 4]| {
+                let value = f32::from_le_bytes(bytes);
+                value.is_finite() && (value - old_value).abs() < tolerance
+            };
+
+            let mut bytes = [0u8; 4];
+            if read(prev.address, &mut bytes) && is_near(bytes) {
+                results.push(PatternMatch { matched_bytes: bytes.to_vec(), ..prev.clone() });
+                continue;
+            }
 
-        Ok(buffer)
-    }
+            // Field may have moved within its struct; check aligned neighbours
+            let window_start = prev.address.saturating_sub(WINDOW);
+            let mut window = vec![0u8; (prev.address - window_start + WINDOW) as usize + 4];
+            if !read(window_start, &mut window) {
+                continue;
+            }
 
-    /// Read 32-bit integer at address
-    pub fn read_int32(pid: u32, address: u64) -> Result<i32, String> {
-        let bytes = Self::read_value(pid, address, 4)?;
-        let arr: [u8; 4] = bytes.try_into().map_err(|_| "Invalid byte count")?;
-        Ok(i32::from_le_bytes(arr))
-    }
+            let shifted = (0..window.len() - 3).step_by(4)
+                .map(|i| (window_start + i as u64, [window[i], window[i + 1], window[i + 2], window[i + 3]]))
+                .filter(|&(address, _)| address != prev.address)
+                .find(|&(_, bytes)| is_near(bytes));
+
+            if let Some((address, bytes)) = shifted {
+                results.push(PatternMatch {
+                    address,
+                    region_start: prev.region_start,
+                    offset_in_region: address.wrapping_sub(prev.region_start),
+                    matched_bytes: bytes.to_vec(),
+                });
+            }
+        }
 
-    /// Read 32-bit float at address
-    pub fn read_float32(pid: u32, address: u64) -> Result<f32, String> {
-        let bytes = Self::read_value(pid, address, 4)?;
-        let arr: [u8; 4] = bytes.try_into().map_err(|_| "Invalid byte count")?;
-        Ok(f32::from_le_bytes(arr))
+        results
     }
 
-    /// Read null-terminated string at address
-    pub fn read_string(pid: u32, address: u64, max_len: usize) -> Result<String, String> {
-        let bytes = Self::read_value(pid, address, max_len)?;
-        let null_pos = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
-        String::from_utf8(bytes[..null_pos].to_vec())
-            .map_err(|e| format!("Invalid UTF-8: {}", e))
+    /// First pass of an unknown-value scan: record every aligned value
+    /// in the readable regions so later passes can compare against it.
+    /// Values are widened with `GameValueType::widen`; at most
+    /// `max_results` entries are returned.
+    pub fn initial_unknown_scan(
+        pid: u32,
+        ty: GameValueType,
+        regions: &[MemoryRegion],
+        alignment: usize,
+        max_results: usize,
+    ) -> Result<Vec<(u64, i64)>, MemoryError> {
+        if alignment == 0 {
+            return Err(MemoryError::InvalidArgument("Alignment must be non-zero".to_string()));
+        }
+
+        let file = Self::open_proc_file(pid, "mem")?;
+        Ok(Self::initial_unknown_scan_in(&file, ty, regions, alignment, max_results, DEFAULT_SCAN_CHUNK_SIZE))
     }
 
-    /// Filter regions by common game memory patterns
-    pub fn filter_game_regions(regions: &[MemoryRegion]) -> Vec<MemoryRegion> {
-        regions.iter()
-            .filter(|r| {
+    /// Reads each region `chunk_size` bytes at a time, like `scan_chunked_with`;
+    /// a chunk that can't be read is skipped.
+    fn initial_unknown_scan_in(
+        file: &File,
+        ty: GameValueType,
+        regions: &[MemoryRegion],
+        alignment: usize,
+        max_results: usize,
+        chunk_size: usize,
+    ) -> Vec<(u64, i64)> {
+        let mut values = Vec::new();
+        if max_results == 0 {
+            return values;
+        }
+        let value_size = ty.size();
+        // Consecutive chunks overlap so values spanning a boundary are still read
+        let overlap = value_size - 1;
+        let mut buffer = Vec::new();
+
+        for region in regions {
+            if !region.is_readable() || region.size() < value_size as u64 {
+                continue;
+            }
+
+            let region_size = region.size() as usize;
+            let mut offset = 0usize;
+
+            while offset + value_size <= region_size {
+                let read_len = (chunk_size + overlap).min(region_size - offset);
+                buffer.resize(read_len, 0);
+
+                let chunk_addr = region.start_addr + offset as u64;
+                if file.read_exact_at(&mut buffer, chunk_addr).is_err() {
+                    offset += chunk_size;
+                    continue;
+                }
+
+                // Align to absolute addresses, not to the chunk start; only values
+                // starting inside this chunk, later ones belong to the next
+                let first = (alignment - (chunk_addr % alignment as u64) as usize) % alignment;
+                let end = chunk_size.min(read_len + 1 - value_size);
+                for i in (first..end).step_by(alignment) {
+                    if let Some(value) = ty.widen(&buffer[i..i + value_size]) {
+                        values.push((chunk_addr + i as u64, value));
+
+                        if values.len() >= max_results {
+                            return values;
+                        }
+                    }
+                }
+
+                offset += chunk_size;
+            }
+        }
+
+        values
+    }
+
+    /// Copy every readable region for a later `RegionSnapshot::changed_bytes_since`
+    /// Regions that can't be read are left out of the snapshot.
+    pub fn snapshot_regions_full(pid: u32, regions: &[MemoryRegion]) -> Result<RegionSnapshot, MemoryError> {
+        let file = Self::open_proc_file(pid, "mem")?;
+        let mut blocks = FxHashMap::default();
+
+        for region in regions {
+            if !region.is_readable() || region.size() == 0 {
+                continue;
+            }
+
+            let mut buffer = vec![0u8; region.size() as usize];
+            if file.read_exact_at(&mut buffer, region.start_addr).is_ok() {
+                blocks.insert(region.start_addr, buffer);
+            }
+        }
+
+        Ok(RegionSnapshot { blocks })
+    }
+
+    /// Aligned 4-byte words in `regions` that change most often over time
+    /// Takes `total_samples` full snapshots `sample_interval_ms` apart and counts,
+    /// per word, how many consecutive pairs differ. Returns up to
+    /// `MAX_HOT_ADDRESSES`, most active first; `value_range` is over the i32
+    /// values seen while changing. Good starting candidates for HP, position, etc.
+    pub fn profile_write_hotspots(
+        pid: u32,
+        regions: &[MemoryRegion],
+        sample_interval_ms: u64,
+        total_samples: usize,
+    ) -> Result<Vec<HotAddress>, MemoryError> {
+        if total_samples < 2 {
+            return Err(MemoryError::InvalidArgument("Need at least 2 samples".to_string()));
+        }
+
+        let mut hits: FxHashMap<u64, HotAddress> = FxHashMap::default();
+        let mut previous = Self::snapshot_regions_full(pid, regions)?;
+        for _ in 1..total_samples {
+            std::thread::sleep(Duration::from_millis(sample_interval_ms));
+            let current = Self::snapshot_regions_full(pid, regions)?;
+
+            for (&start, new_block) in &current.blocks {
+                let Some(old_block) = previous.blocks.get(&start) else {
+                    continue;
+                };
+
+                let words = old_block.chunks_exact(4).zip(new_block.chunks_exact(4));
+                for (i, (old, new)) in words.enumerate().filter(|(_, (old, new))| old != new) {
+                    let old = i32::from_le_bytes(old.try_into().unwrap()) as i64;
+                    let new = i32::from_le_bytes(new.try_into().unwrap()) as i64;
+                    let address = start + (i * 4) as u64;
+                    let hot = hits.entry(address).or_insert(HotAddress {
+                        address,
+                        change_count: 0,
+                        value_range: (old, old),
+                    });
+                    hot.change_count += 1;
+                    hot.value_range = (hot.value_range.0.min(old).min(new), hot.value_range.1.max(old).max(new));
+                }
+            }
+            previous = current;
+        }
+
+        let mut hot: Vec<HotAddress> = hits.into_values().collect();
+        hot.sort_by(|a, b| b.change_count.cmp(&a.change_count).then(a.address.cmp(&b.address)));
+        hot.truncate(MAX_HOT_ADDRESSES);
+        Ok(hot)
+    }
+
+    /// Read value at specific address
+    pub fn read_value(pid: u32, address: u64, size: usize) -> Result<Vec<u8>, MemoryError> {
+        let mut file = Self::open_proc_file(pid, "mem")?;
+
+        use std::io::Seek;
+        file.seek(std::io::SeekFrom::Start(address))
+            .map_err(|e| MemoryError::Io(format!("Failed to seek: {}", e)))?;
+
+        let mut buffer = vec![0u8; size];
+        file.read_exact(&mut buffer)
+            .map_err(|e| MemoryError::Io(format!("Failed to read: {}", e)))?;
+
+        Ok(buffer)
+    }
+
+    /// Read `size` bytes along with their CRC32
+    pub fn read_with_crc32(pid: u32, address: u64, size: usize) -> Result<(Vec<u8>, u32), MemoryError> {
+        let data = Self::read_value(pid, address, size)?;
+        let crc = crc32fast::hash(&data);
+        Ok((data, crc))
+    }
+
+    /// Keep reading until `retries` consecutive reads (at least two) agree, and return that value
+    /// A game thread writing while we read can hand back a torn value, so a
+    /// mismatch restarts the streak. After `retries * STABLE_READ_ATTEMPT_FACTOR`
+    /// reads without a full streak the value is reported as `Unstable`.
+    pub fn verify_stable_read(pid: u32, address: u64, size: usize, retries: usize) -> Result<Vec<u8>, MemoryError> {
+        let file = Self::open_proc_file(pid, "mem")?;
+        Self::read_until_stable(address, size, retries, |buffer| Ok(file.read_exact_at(buffer, address)?))
+    }
+
+    /// The retry loop behind `verify_stable_read`, with the read itself supplied by `read`
+    fn read_until_stable(
+        address: u64,
+        size: usize,
+        retries: usize,
+        mut read: impl FnMut(&mut [u8]) -> Result<(), MemoryError>,
+    ) -> Result<Vec<u8>, MemoryError> {
+        let needed = retries.max(2);
+        let budget = needed.saturating_mul(STABLE_READ_ATTEMPT_FACTOR);
+        let mut data = vec![0u8; size];
+        read(&mut data)?;
+
+        let mut streak = 1;
+        let mut next = vec![0u8; size];
+        for _ in 1..budget {
+            read(&mut next)?;
+            if next == data {
+                streak += 1;
+                if streak == needed {
+                    return Ok(data);
+                }
+            } else {
+                std::mem::swap(&mut data, &mut next);
+                streak = 1;
+            }
+        }
+        Err(MemoryError::Unstable { address, attempts: budget })
+    }
+
+    /// Write several values with as few syscalls as possible
+    /// See `write_batch_with_gap`; merges writes less than `DEFAULT_WRITE_GAP` apart.
+    pub fn write_batch(pid: u32, writes: &[(u64, &[u8])]) -> Result<Vec<Result<(), MemoryError>>, MemoryError> {
+        Self::write_batch_with_gap(pid, writes, DEFAULT_WRITE_GAP)
+    }
+
+    /// Write several values, coalescing nearby ones into single pwrite calls
+    /// Writes are sorted by address and merged when the gap between them is at
+    /// most `max_gap`; gap bytes are read first so they're written back unchanged.
+    /// Returns one result per input write, in input order.
+    pub fn write_batch_with_gap(
+        pid: u32,
+        writes: &[(u64, &[u8])],
+        max_gap: u64,
+    ) -> Result<Vec<Result<(), MemoryError>>, MemoryError> {
+        let total: usize = writes.iter().map(|(_, data)| data.len()).sum();
+        if total > MAX_WRITE_BATCH_BYTES {
+            return Err(MemoryError::InvalidArgument(format!(
+                "Batch of {} bytes exceeds {} byte limit", total, MAX_WRITE_BATCH_BYTES)));
+        }
+        if let Some((address, data)) = writes.iter().find(|(address, data)| address.checked_add(data.len() as u64).is_none()) {
+            return Err(MemoryError::InvalidArgument(format!(
+                "Write of {} bytes at {:#x} overflows the address space", data.len(), address)));
+        }
+
+        let file = Self::open_proc_file_with(pid, "mem", OpenOptions::new().read(true).write(true))?;
+
+        let mut order: Vec<usize> = (0..writes.len()).filter(|&i| !writes[i].1.is_empty()).collect();
+        order.sort_by_key(|&i| writes[i].0);
+
+        let mut results: Vec<Result<(), MemoryError>> = vec![Ok(()); writes.len()];
+        let mut group_start = 0;
+
+        while group_start < order.len() {
+            let span_start = writes[order[group_start]].0;
+            let mut span_end = span_start + writes[order[group_start]].1.len() as u64;
+            let mut group_end = group_start + 1;
+
+            while group_end < order.len() {
+                let (addr, data) = writes[order[group_end]];
+                if addr > span_end.saturating_add(max_gap) {
+                    break;
+                }
+                span_end = span_end.max(addr + data.len() as u64);
+                group_end += 1;
+            }
+
+            let group = &order[group_start..group_end];
+            let outcome = Self::write_span(&file, span_start, span_end, group, writes);
+            for &i in group {
+                results[i] = outcome.clone();
+            }
+
+            group_start = group_end;
+        }
+
+        Ok(results)
+    }
+
+    /// Read-modify-write one merged span; overlapping writes apply in input order
+    fn write_span(
+        file: &File,
+        span_start: u64,
+        span_end: u64,
+        group: &[usize],
+        writes: &[(u64, &[u8])],
+    ) -> Result<(), MemoryError> {
+        let mut buffer = vec![0u8; (span_end - span_start) as usize];
+        let covered: usize = group.iter().map(|&i| writes[i].1.len()).sum();
+        if group.len() > 1 || covered < buffer.len() {
+            file.read_exact_at(&mut buffer, span_start)?;
+        }
+
+        let mut in_order = group.to_vec();
+        in_order.sort_unstable();
+        for i in in_order {
+            let (addr, data) = writes[i];
+            let offset = (addr - span_start) as usize;
+            buffer[offset..offset + data.len()].copy_from_slice(data);
+        }
+
+        file.write_all_at(&buffer, span_start)?;
+        Ok(())
+    }
+
+    /// Read a typed value at address
+    pub fn read_typed(pid: u32, address: u64, ty: GameValueType) -> Result<GameValue, MemoryError> {
+        let file = Self::open_proc_file(pid, "mem")?;
+        let mut buffer = vec![0u8; ty.size()];
+        file.read_exact_at(&mut buffer, address)?;
+        ty.decode(&buffer)
+            .ok_or_else(|| MemoryError::Parse(format!("Failed to decode {:?}", ty)))
+    }
+
+    /// Read any plain-old-data value (including `#[repr(C)]` structs) at address
+    pub fn read_pod<T: bytemuck::Pod>(pid: u32, address: u64) -> Result<T, MemoryError> {
+        let file = Self::open_proc_file(pid, "mem")?;
+        let mut buffer = vec![0u8; std::mem::size_of::<T>()];
+        file.read_exact_at(&mut buffer, address)?;
+        // The Vec carries no alignment guarantee for T, so copy out unaligned
+        bytemuck::try_pod_read_unaligned(&buffer)
+            .map_err(|e| MemoryError::Parse(format!("{:?}", e)))
+    }
+
+    /// Write any plain-old-data value at address
+    pub fn write_pod<T: bytemuck::Pod>(pid: u32, address: u64, value: &T) -> Result<(), MemoryError> {
+        let file = Self::open_proc_file_with(pid, "mem", OpenOptions::new().write(true))?;
+        file.write_all_at(bytemuck::bytes_of(value), address)?;
+        Ok(())
+    }
+
+    /// Read 32-bit integer at address
+    pub fn read_int32(pid: u32, address: u64) -> Result<i32, MemoryError> {
+        Self::read_int32_endian(pid, address, Endian::Little)
+    }
+
+    /// `read_int32` for a target with the given byte order
+    pub fn read_int32_endian(pid: u32, address: u64, endian: Endian) -> Result<i32, MemoryError> {
+        Self::read_pod(pid, address).map(|bytes| endian.i32_from_bytes(bytes))
+    }
+
+    /// Read 32-bit float at address
+    pub fn read_float32(pid: u32, address: u64) -> Result<f32, MemoryError> {
+        Self::read_float32_endian(pid, address, Endian::Little)
+    }
+
+    /// `read_float32` for a target with the given byte order
+    pub fn read_float32_endian(pid: u32, address: u64, endian: Endian) -> Result<f32, MemoryError> {
+        Self::read_pod(pid, address).map(|bytes| endian.f32_from_bytes(bytes))
+    }
+
+    /// Read null-terminated string at address
+    pub fn read_string(pid: u32, address: u64, max_len: usize) -> Result<String, MemoryError> {
+        let bytes = Self::read_value(pid, address, max_len)?;
+        let null_pos = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        String::from_utf8(bytes[..null_pos].to_vec())
+            .map_err(|e| MemoryError::Parse(format!("Invalid UTF-8: {}", e)))
+    }
+
+    /// Byte offset of `expected_field_bytes` within `search_radius` bytes from `base_address`
+    /// Used to discover struct layouts from a known base pointer and a known
+    /// current field value (e.g. HP = 95.0f32 as bytes). Returns the first hit.
+    pub fn find_field_offset(
+        pid: u32,
+        base_address: u64,
+        expected_field_bytes: &[u8],
+        search_radius: usize,
+    ) -> Result<Option<u64>, MemoryError> {
+        Ok(Self::find_field_offsets_multi(pid, base_address, &[expected_field_bytes], search_radius)?[0])
+    }
+
+    /// `find_field_offset` for several fields with a single read of the struct
+    pub fn find_field_offsets_multi(
+        pid: u32,
+        base: u64,
+        expected_fields: &[&[u8]],
+        search_radius: usize,
+    ) -> Result<Vec<Option<u64>>, MemoryError> {
+        if expected_fields.iter().any(|field| field.is_empty()) {
+            return Err(MemoryError::InvalidArgument("Empty field pattern".to_string()));
+        }
+
+        let file = Self::open_proc_file(pid, "mem")?;
+        let mut buffer = vec![0u8; search_radius];
+        file.read_exact_at(&mut buffer, base)?;
+
+        Ok(expected_fields
+            .iter()
+            .map(|field| {
+                buffer
+                    .windows(field.len())
+                    .position(|window| window == *field)
+                    .map(|offset| offset as u64)
+            })
+            .collect())
+    }
+
+    /// C++ class name of the object at `object_address` via its RTTI (Itanium ABI)
+    /// Follows object -> vtable -> typeinfo (at vtable - 8) -> name (typeinfo + 8).
+    /// Every pointer must land in a readable region and, if `lib_base` is non-zero,
+    /// the vtable must lie at or above it; otherwise there's no RTTI to read (None).
+    /// The returned name is still mangled, see `demangle_type_name`.
+    pub fn find_rtti_type_name(
+        pid: u32,
+        object_address: u64,
+        lib_base: u64,
+        regions: &[MemoryRegion],
+    ) -> Result<Option<String>, MemoryError> {
+        let readable = |address: u64| {
+            regions.iter().any(|r| r.is_readable() && r.start_addr <= address && address < r.end_addr)
+        };
+
+        let vtable: u64 = Self::read_pod(pid, object_address)?;
+        if vtable < 8 || vtable < lib_base || !readable(vtable - 8) {
+            return Ok(None);
+        }
+
+        let typeinfo: u64 = Self::read_pod(pid, vtable - 8)?;
+        if !readable(typeinfo) {
+            return Ok(None);
+        }
+
+        let name_ptr: u64 = Self::read_pod(pid, typeinfo + 8)?;
+        if !readable(name_ptr) {
+            return Ok(None);
+        }
+
+        let name = Self::read_string(pid, name_ptr, 256)?;
+        Ok((!name.is_empty()).then_some(name))
+    }
+
+    /// Read the first `method_count` function pointers of an object's vtable
+    /// Each entry must point into the library at `[lib_base, lib_base + lib_size)`;
+    /// an entry outside it means the object or count is wrong, reported as Parse.
+    pub fn read_vtable(
+        pid: u32,
+        object_address: u64,
+        method_count: usize,
+        lib_base: u64,
+        lib_size: u64,
+    ) -> Result<Vec<u64>, MemoryError> {
+        let vtable: u64 = Self::read_pod(pid, object_address)?;
+        if vtable == 0 {
+            return Err(MemoryError::Parse(format!("Null vtable pointer at {:#x}", object_address)));
+        }
+
+        let len = method_count.checked_mul(8).ok_or_else(|| {
+            MemoryError::InvalidArgument(format!("VTable of {} methods is too large", method_count))
+        })?;
+        let lib_end = lib_base.saturating_add(lib_size);
+        let bytes = Self::read_value(pid, vtable, len)?;
+        bytes
+            .chunks_exact(8)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let method = u64::from_le_bytes(chunk.try_into().unwrap());
+                if (lib_base..lib_end).contains(&method) {
+                    Ok(method)
+                } else {
+                    Err(MemoryError::Parse(format!("VTable entry {} ({:#x}) lies outside the library", i, method)))
+                }
+            })
+            .collect()
+    }
+
+    /// Replace one vtable entry with `hook_address`, returning the original pointer
+    /// Writes go through `/proc/<pid>/mem`, which ignores page protections, so
+    /// vtables in read-only relro segments can be patched too.
+    pub fn patch_vtable_entry(
+        pid: u32,
+        vtable_address: u64,
+        method_index: usize,
+        hook_address: u64,
+    ) -> Result<u64, MemoryError> {
+        let entry = (method_index as u64)
+            .checked_mul(8)
+            .and_then(|offset| vtable_address.checked_add(offset))
+            .ok_or_else(|| {
+                MemoryError::InvalidArgument(format!(
+                    "VTable entry {} at {:#x} overflows the address space", method_index, vtable_address))
+            })?;
+        let original: u64 = Self::read_pod(pid, entry)?;
+        Self::write_pod(pid, entry, &hook_address)?;
+        Ok(original)
+    }
+
+    /// Demangle an RTTI type name (e.g. `N4Game6PlayerE` -> `Game::Player`)
+    /// Returns the input unchanged if it isn't a valid mangled type.
+    pub fn demangle_type_name(mangled: &str) -> String {
+        const PREFIX: &str = "typeinfo name for ";
+        // Type names lack the `_Z` prefix; parse them as the typeinfo-name symbol
+        cpp_demangle::Symbol::new(format!("_ZTS{}", mangled))
+            .ok()
+            .and_then(|symbol| symbol.demangle(&Default::default()).ok())
+            .and_then(|demangled| demangled.strip_prefix(PREFIX).map(str::to_string))
+            .unwrap_or_else(|| mangled.to_string())
+    }
+
+    /// Filter regions by common game memory patterns
+    pub fn filter_game_regions(regions: &[MemoryRegion]) -> Vec<MemoryRegion> {
+        regions.iter()
+            .filter(|r| {
                 // Keep readable, writable anonymous regions (heap-like)
                 r.is_readable() && r.is_writable() && 
                 (r.is_anonymous() || r.is_heap()) &&
@@ -385,6 +1489,16 @@ impl MemoryEngine {
             .collect()
     }
 
+    /// Readable + executable regions (library `r-xp` text) for code patching,
+    /// optionally limited to paths containing `lib_name`
+    pub fn filter_code_regions(regions: &[MemoryRegion], lib_name: Option<&str>) -> Vec<MemoryRegion> {
+        regions.iter()
+            .filter(|r| r.is_readable() && r.is_executable())
+            .filter(|r| lib_name.is_none_or(|name| r.pathname.contains(name)))
+            .cloned()
+            .collect()
+    }
+
     /// Find regions belonging to a specific library
     pub fn find_library_regions(regions: &[MemoryRegion], lib_name: &str) -> Vec<MemoryRegion> {
         regions.iter()
@@ -398,26 +1512,161 @@ impl MemoryEngine {
         pid: u32,
         base_address: u64,
         offsets: &[u64],
-    ) -> Result<u64, String> {
-        let mut address = base_address;
+    ) -> Result<u64, MemoryError> {
+        let chain = PointerChain::new(base_address, offsets.iter().map(|&o| o as i64).collect());
+        chain.resolve(pid, 8)
+    }
+
+    /// Export match addresses as GDB watch commands
+    /// `value_type` is one of int/int32, long/int64, float/float32, double/float64;
+    /// anything else watches a single byte.
+    pub fn export_as_gdb_commands(matches: &[PatternMatch], value_type: &str) -> String {
+        let (c_type, _, _) = Self::debugger_type(value_type);
+        matches.iter()
+            .map(|m| format!("watch *({}*)0x{:x}\n", c_type, m.address))
+            .collect()
+    }
 
-        for (i, &offset) in offsets.iter().enumerate() {
-            // Read pointer at current address
-            let bytes = Self::read_value(pid, address, 8)?;
-            let arr: [u8; 8] = bytes.try_into().map_err(|_| "Invalid byte count")?;
-            let ptr = u64::from_le_bytes(arr);
+    /// Export match addresses as LLDB watchpoint commands
+    pub fn export_as_lldb_commands(matches: &[PatternMatch], value_type: &str) -> String {
+        let (_, size, _) = Self::debugger_type(value_type);
+        matches.iter()
+            .map(|m| format!("watchpoint set expression -w write -s {} -- 0x{:x}\n", size, m.address))
+            .collect()
+    }
 
-            if ptr == 0 {
-                return Err(format!("Null pointer at offset index {}", i));
+    /// Export a Frida script logging every match address each time `hook_function_name` is called
+    /// Values are read as 32-bit ints; edit the reader calls for other types.
+    pub fn export_as_frida_script(matches: &[PatternMatch], hook_function_name: &str) -> String {
+        let (_, _, reader) = Self::debugger_type("int");
+        let addresses: Vec<String> = matches.iter()
+            .map(|m| format!("    ptr('0x{:x}'),\n", m.address))
+            .collect();
+        let hook_function_name = serde_json::to_string(hook_function_name).unwrap_or_else(|_| "\"\"".to_string());
+
+        format!(
+            "const addresses = [\n{}];\n\n\
+             Interceptor.attach(Module.getExportByName(null, {}), {{\n\
+             \x20   onEnter() {{\n\
+             \x20       addresses.forEach(addr => console.log(addr + ' = ' + addr.{}()));\n\
+             \x20   }}\n\
+             }});\n",
+            addresses.concat(), hook_function_name, reader,
+        )
+    }
+
+    /// Export regions as GDB `mem` commands, for use with `source`
+    /// Regions that are neither readable nor writable are left out, so GDB refuses them.
+    pub fn export_regions_gdb(regions: &[MemoryRegion]) -> String {
+        regions.iter()
+            .filter_map(|r| {
+                let mode = match (r.is_readable(), r.is_writable()) {
+                    (true, true) => "rw",
+                    (true, false) => "ro",
+                    (false, true) => "wo",
+                    (false, false) => return None,
+                };
+                Some(format!("mem 0x{:x} 0x{:x} {}\n", r.start_addr, r.end_addr, mode))
+            })
+            .collect()
+    }
+
+    /// Export regions as a Frida `const REGIONS = [...]` array
+    /// Entries mirror `Process.enumerateRanges()`: base, size, protection and path.
+    pub fn export_regions_frida(regions: &[MemoryRegion]) -> String {
+        let entries: Vec<String> = regions.iter()
+            .map(|r| {
+                let protection = r.permissions.get(..3).unwrap_or("---");
+                // A JSON string literal is also a valid JS one
+                let path = serde_json::to_string(&r.pathname).unwrap_or_else(|_| "\"\"".to_string());
+                format!(
+                    "    {{ base: ptr('0x{:x}'), size: 0x{:x}, protection: '{}', path: {} }},\n",
+                    r.start_addr, r.size(), protection, path,
+                )
+            })
+            .collect();
+
+        format!("const REGIONS = [\n{}];\n", entries.concat())
+    }
+
+    /// (C type, size in bytes, Frida NativePointer reader) for a value type name
+    fn debugger_type(value_type: &str) -> (&'static str, usize, &'static str) {
+        match value_type.to_ascii_lowercase().as_str() {
+            "int" | "int32" | "i32" => ("int", 4, "readS32"),
+            "long" | "int64" | "i64" => ("long long", 8, "readS64"),
+            "float" | "float32" | "f32" => ("float", 4, "readFloat"),
+            "double" | "float64" | "f64" => ("double", 8, "readDouble"),
+            _ => ("char", 1, "readU8"),
+        }
+    }
+}
+
+/// Byte that differs from a `RegionSnapshot`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangedByte {
+    pub address: u64,
+    pub old: u8,
+    pub new: u8,
+}
+
+/// Word found by `profile_write_hotspots`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotAddress {
+    pub address: u64,
+    /// Consecutive sample pairs in which the word differed
+    pub change_count: usize,
+    /// Smallest and largest i32 value observed
+    pub value_range: (i64, i64),
+}
+
+/// Full copy of region contents, keyed by region start address
+#[derive(Debug, Clone, Default)]
+pub struct RegionSnapshot {
+    blocks: FxHashMap<u64, Vec<u8>>,
+}
+
+impl RegionSnapshot {
+    /// Bytes captured across all regions
+    pub fn total_bytes(&self) -> usize {
+        self.blocks.values().map(Vec::len).sum()
+    }
+
+    /// Every byte in `regions` that changed since the snapshot, by address
+    /// Only regions present in the snapshot (same start) are compared, over the
+    /// shorter of the old and current sizes; unreadable regions are skipped.
+    /// Addresses that change by the hundreds per frame are usually live game state.
+    pub fn changed_bytes_since(&self, pid: u32, regions: &[MemoryRegion]) -> Result<Vec<ChangedByte>, MemoryError> {
+        let file = MemoryEngine::open_proc_file(pid, "mem")?;
+        let mut changes = Vec::new();
+
+        for region in regions {
+            let Some(old) = self.blocks.get(&region.start_addr) else {
+                continue;
+            };
+
+            let mut current = vec![0u8; old.len().min(region.size() as usize)];
+            if file.read_exact_at(&mut current, region.start_addr).is_err() {
+                continue;
             }
 
-            address = ptr + offset;
+            changes.extend(old.iter().zip(&current).enumerate()
+                .filter(|(_, (old, new))| old != new)
+                .map(|(i, (&old, &new))| ChangedByte { address: region.start_addr + i as u64, old, new }));
         }
 
-        Ok(address)
+        changes.sort_by_key(|c| c.address);
+        Ok(changes)
     }
 }
 
+/// Inventory slot contents
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ItemSlot {
+    pub item_id: u32,
+    pub count: u32,
+    pub durability: f32,
+}
+
 /// Common game data structures
 pub struct GameDataStructures;
 
@@ -425,14 +1674,19 @@ impl GameDataStructures {
     /// Parse Unity player stats structure
     /// Typical layout: HP (float), MaxHP (float), MP (float), MaxMP (float)
     pub fn parse_unity_stats(data: &[u8]) -> Option<(f32, f32, f32, f32)> {
+        Self::parse_unity_stats_endian(data, Endian::Little)
+    }
+
+    /// `parse_unity_stats` for a target with the given byte order
+    pub fn parse_unity_stats_endian(data: &[u8], endian: Endian) -> Option<(f32, f32, f32, f32)> {
         if data.len() < 16 {
             return None;
         }
 
-        let hp = f32::from_le_bytes(data[0..4].try_into().ok()?);
-        let max_hp = f32::from_le_bytes(data[4..8].try_into().ok()?);
-        let mp = f32::from_le_bytes(data[8..12].try_into().ok()?);
-        let max_mp = f32::from_le_bytes(data[12..16].try_into().ok()?);
+        let hp = endian.f32_from_bytes(data[0..4].try_into().ok()?);
+        let max_hp = endian.f32_from_bytes(data[4..8].try_into().ok()?);
+        let mp = endian.f32_from_bytes(data[8..12].try_into().ok()?);
+        let max_mp = endian.f32_from_bytes(data[12..16].try_into().ok()?);
 
         // Sanity check
         if hp >= 0.0 && hp <= max_hp && max_hp > 0.0 && max_hp < 100000.0 {
@@ -444,13 +1698,18 @@ impl GameDataStructures {
 
     /// Parse position structure (x, y, z as floats)
     pub fn parse_position(data: &[u8]) -> Option<(f32, f32, f32)> {
+        Self::parse_position_endian(data, Endian::Little)
+    }
+
+    /// `parse_position` for a target with the given byte order
+    pub fn parse_position_endian(data: &[u8], endian: Endian) -> Option<(f32, f32, f32)> {
         if data.len() < 12 {
             return None;
         }
 
-        let x = f32::from_le_bytes(data[0..4].try_into().ok()?);
-        let y = f32::from_le_bytes(data[4..8].try_into().ok()?);
-        let z = f32::from_le_bytes(data[8..12].try_into().ok()?);
+        let x = endian.f32_from_bytes(data[0..4].try_into().ok()?);
+        let y = endian.f32_from_bytes(data[4..8].try_into().ok()?);
+        let z = endian.f32_from_bytes(data[8..12].try_into().ok()?);
 
         // Sanity check - reasonable world coordinates
         if x.is_finite() && y.is_finite() && z.is_finite() &&
@@ -463,6 +1722,11 @@ impl GameDataStructures {
 
     /// Parse skill cooldown structure
     pub fn parse_skill_cooldowns(data: &[u8], skill_count: usize) -> Vec<f32> {
+        Self::parse_skill_cooldowns_endian(data, skill_count, Endian::Little)
+    }
+
+    /// `parse_skill_cooldowns` for a target with the given byte order
+    pub fn parse_skill_cooldowns_endian(data: &[u8], skill_count: usize, endian: Endian) -> Vec<f32> {
         let mut cooldowns = Vec::with_capacity(skill_count);
         
         for i in 0..skill_count {
@@ -472,8 +1736,8 @@ impl GameDataStructures {
             }
             
             if let Ok(arr) = data[offset..offset + 4].try_into() {
-                let cd: f32 = f32::from_le_bytes(arr);
-                if cd.is_finite() && cd >= 0.0 && cd < 1000.0 {
+                let cd: f32 = endian.f32_from_bytes(arr);
+                if cd.is_finite() && (0.0..1000.0).contains(&cd) {
                     cooldowns.push(cd);
                 }
             }
@@ -481,12 +1745,56 @@ impl GameDataStructures {
 
         cooldowns
     }
+
+    /// Parse inventory slots laid out every `slot_stride` bytes
+    /// Layout per slot: item_id (u32), count (u32), durability (f32).
+    /// Empty slots (item_id 0) and slots failing sanity checks are `None`.
+    pub fn parse_item_slots(data: &[u8], slot_count: usize, slot_stride: usize) -> Vec<Option<ItemSlot>> {
+        Self::parse_item_slots_endian(data, slot_count, slot_stride, Endian::Little)
+    }
+
+    /// `parse_item_slots` for a target with the given byte order
+    pub fn parse_item_slots_endian(
+        data: &[u8],
+        slot_count: usize,
+        slot_stride: usize,
+        endian: Endian,
+    ) -> Vec<Option<ItemSlot>> {
+        if slot_stride < 12 {
+            return Vec::new();
+        }
+
+        let mut slots = Vec::with_capacity(slot_count);
+
+        for i in 0..slot_count {
+            let offset = i * slot_stride;
+            if offset + 12 > data.len() {
+                break;
+            }
+
+            let slot = &data[offset..offset + 12];
+            let item_id = endian.u32_from_bytes(slot[0..4].try_into().unwrap());
+            let count = endian.u32_from_bytes(slot[4..8].try_into().unwrap());
+            let durability = endian.f32_from_bytes(slot[8..12].try_into().unwrap());
+
+            // Sanity check
+            if item_id != 0 && item_id < 100_000 && count < 10_000 && (0.0..=1.0).contains(&durability) {
+                slots.push(Some(ItemSlot { item_id, count, durability }));
+            } else {
+                slots.push(None);
+            }
+        }
+
+        slots
+    }
 }
 
 /// Memory signature for common games
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct GameSignature {
+    #[serde(default)]
     pub game_name: String,
+    #[serde(default)]
     pub package_name: String,
     pub hp_pattern: Vec<u8>,
     pub hp_mask: Vec<bool>,
@@ -496,6 +1804,14 @@ pub struct GameSignature {
     pub position_offset: i64,
 }
 
+/// Result of locating a signature's fields in a live process
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureVerifyResult {
+    pub hp_address: Option<u64>,
+    pub position_address: Option<u64>,
+    pub confidence: f32,
+}
+
 impl GameSignature {
     /// Create signature for a generic Unity game
     pub fn generic_unity() -> Self {
@@ -511,6 +1827,446 @@ impl GameSignature {
             position_offset: 0,
         }
     }
+
+    /// Locate the HP and position structures in `pid`
+    /// Each field contributes half the confidence: a quarter for a unique pattern
+    /// match and a quarter when the data at the resolved address parses sanely.
+    /// Fully wildcarded patterns are skipped since they match everywhere.
+    pub fn verify(&self, pid: u32, regions: &[MemoryRegion]) -> SignatureVerifyResult {
+        let (hp_address, hp_score) = Self::locate(pid, regions, &self.hp_pattern, &self.hp_mask, self.hp_offset, 16,
+            |data| GameDataStructures::parse_unity_stats(data).is_some());
+        let (position_address, position_score) = Self::locate(pid, regions, &self.position_pattern,
+            &self.position_mask, self.position_offset, 12,
+            |data| GameDataStructures::parse_position(data).is_some());
+
+        SignatureVerifyResult {
+            hp_address,
+            position_address,
+            confidence: hp_score + position_score,
+        }
+    }
+
+    fn locate(
+        pid: u32,
+        regions: &[MemoryRegion],
+        pattern: &[u8],
+        mask: &[bool],
+        offset: i64,
+        value_size: usize,
+        is_sane: impl Fn(&[u8]) -> bool,
+    ) -> (Option<u64>, f32) {
+        if !mask.iter().any(|&m| m) {
+            return (None, 0.0);
+        }
+
+        let matches = match MemoryEngine::search_pattern_masked(pid, pattern, mask, regions, 2) {
+            Ok(matches) if !matches.is_empty() => matches,
+            _ => return (None, 0.0),
+        };
+
+        let address = matches[0].address.wrapping_add_signed(offset);
+        let mut score = if matches.len() == 1 { 0.25 } else { 0.0 };
+        if MemoryEngine::read_value(pid, address, value_size).is_ok_and(|data| is_sane(&data)) {
+            score += 0.25;
+        }
+
+        (Some(address), score)
+    }
+}
+
+/// Reader that keeps `/proc/<pid>/mem` open and caches the region list
+/// Avoids an open/close per read or per search when polling many values; the
+/// searches match their `MemoryEngine` counterparts. `regions` is usually
+/// `self.regions()` or a filtered copy of it.
+#[derive(Debug)]
+pub struct SafeMemoryReader {
+    pid: u32,
+    file: File,
+    regions: Vec<MemoryRegion>,
+}
+
+impl SafeMemoryReader {
+    /// Open the process memory and read its maps once
+    pub fn new(pid: u32) -> Result<Self, MemoryError> {
+        let file = MemoryEngine::open_proc_file(pid, "mem")?;
+        let regions = MemoryEngine::parse_memory_maps(pid)?;
+        Ok(Self { pid, file, regions })
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Regions cached at construction or by the last `refresh_regions`
+    pub fn regions(&self) -> &[MemoryRegion] {
+        &self.regions
+    }
+
+    /// Read `size` bytes at `address` through the cached handle
+    pub fn read_value(&mut self, address: u64, size: usize) -> Result<Vec<u8>, MemoryError> {
+        let mut buffer = vec![0u8; size];
+        self.file.read_exact_at(&mut buffer, address)?;
+        Ok(buffer)
+    }
+
+    /// See `MemoryEngine::search_pattern`
+    pub fn search_pattern(&self, pattern: &[u8], regions: &[MemoryRegion], limit: usize) -> Result<Vec<PatternMatch>, MemoryError> {
+        MemoryEngine::scan_chunked(&self.file, pattern, regions, limit, DEFAULT_SCAN_CHUNK_SIZE, false, &AtomicBool::new(false))
+    }
+
+    /// See `MemoryEngine::search_pattern_masked`
+    pub fn search_pattern_masked(
+        &self,
+        pattern: &[u8],
+        mask: &[bool],
+        regions: &[MemoryRegion],
+        limit: usize,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        MemoryEngine::search_pattern_masked_in(&self.file, pattern, mask, regions, limit)
+    }
+
+    /// See `MemoryEngine::search_any`
+    pub fn search_any(
+        &self,
+        patterns: &[Vec<u8>],
+        regions: &[MemoryRegion],
+        limit: usize,
+    ) -> Result<Vec<(PatternMatch, usize)>, MemoryError> {
+        MemoryEngine::search_any_in(&self.file, patterns, regions, limit)
+    }
+
+    /// See `MemoryEngine::search_int32`
+    pub fn search_int32(
+        &self,
+        value: i32,
+        regions: &[MemoryRegion],
+        limit: usize,
+        one_per_region: bool,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        MemoryEngine::scan_chunked(&self.file, &value.to_le_bytes(), regions, limit, DEFAULT_SCAN_CHUNK_SIZE,
+            one_per_region, &AtomicBool::new(false))
+    }
+
+    /// See `MemoryEngine::search_float32`
+    pub fn search_float32(
+        &self,
+        value: f32,
+        tolerance: f32,
+        regions: &[MemoryRegion],
+        limit: usize,
+        one_per_region: bool,
+    ) -> Result<Vec<PatternMatch>, MemoryError> {
+        MemoryEngine::scan_floats(&self.file, regions, limit, one_per_region, Endian::Little, |found| {
+            (found - value).abs() <= tolerance
+        })
+    }
+
+    /// See `MemoryEngine::next_scan_float_near`
+    pub fn next_scan_float_near(&self, previous: &[PatternMatch], tolerance: f32) -> Vec<PatternMatch> {
+        MemoryEngine::next_scan_float_near_with(previous, tolerance, |address, buffer| {
+            self.file.read_exact_at(buffer, address).is_ok()
+        })
+    }
+
+    /// See `MemoryEngine::initial_unknown_scan`
+    pub fn initial_unknown_scan(
+        &self,
+        ty: GameValueType,
+        regions: &[MemoryRegion],
+        alignment: usize,
+        max_results: usize,
+    ) -> Result<Vec<(u64, i64)>, MemoryError> {
+        if alignment == 0 {
+            return Err(MemoryError::InvalidArgument("Alignment must be non-zero".to_string()));
+        }
+        Ok(MemoryEngine::initial_unknown_scan_in(&self.file, ty, regions, alignment, max_results, DEFAULT_SCAN_CHUNK_SIZE))
+    }
+
+    /// Re-read /proc/<pid>/maps after the target maps or unmaps memory
+    pub fn refresh_regions(&mut self) -> Result<(), MemoryError> {
+        self.regions = MemoryEngine::parse_memory_maps(self.pid)?;
+        Ok(())
+    }
+}
+
+/// Address located by an AOB pattern, re-found after ASLR or a game update
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddressTracker {
+    pub pattern: Vec<u8>,
+    /// true = must match, false = wildcard
+    pub mask: Vec<bool>,
+    /// Tracked address relative to the pattern start
+    pub struct_offset: i64,
+    pub last_known_address: u64,
+}
+
+impl AddressTracker {
+    pub fn new(pattern: Vec<u8>, mask: Vec<bool>, struct_offset: i64, last_known_address: u64) -> Self {
+        Self { pattern, mask, struct_offset, last_known_address }
+    }
+
+    /// Current address, re-scanning only when the pattern moved
+    /// If the pattern is still at `last_known_address - struct_offset` that address
+    /// is returned as-is. Otherwise `search_pattern_masked` runs over `regions`
+    /// (narrowed to the old address's library when it still falls inside one)
+    /// and `last_known_address` is updated to the first match + `struct_offset`.
+    /// Fails with `PatternNotFound` (leaving `last_known_address` alone) when the pattern is gone.
+    pub fn relocate(&mut self, pid: u32, regions: &[MemoryRegion]) -> Result<u64, MemoryError> {
+        if self.pattern.len() != self.mask.len() {
+            return Err(MemoryError::InvalidArgument("Pattern and mask length mismatch".to_string()));
+        }
+
+        let pattern_addr = self.last_known_address.wrapping_add_signed(-self.struct_offset);
+        if let Ok(bytes) = MemoryEngine::read_value(pid, pattern_addr, self.pattern.len()) {
+            let still_there = bytes.iter()
+                .zip(&self.pattern)
+                .zip(&self.mask)
+                .all(|((b, p), &must)| !must || b == p);
+            if still_there {
+                return Ok(self.last_known_address);
+            }
+        }
+
+        let library = regions.iter()
+            .find(|r| r.start_addr <= pattern_addr && pattern_addr < r.end_addr)
+            .map(|r| r.pathname.as_str());
+        let candidates: Vec<MemoryRegion> = match library {
+            Some(name) => regions.iter().filter(|r| r.pathname == name).cloned().collect(),
+            None => regions.to_vec(),
+        };
+
+        let found = MemoryEngine::search_pattern_masked(pid, &self.pattern, &self.mask, &candidates, 1)?;
+        let hit = found.first().ok_or(MemoryError::PatternNotFound)?;
+        self.last_known_address = hit.address.wrapping_add_signed(self.struct_offset);
+        Ok(self.last_known_address)
+    }
+}
+
+/// Multi-level pointer: `[[base] + o1] + o2 ...`, editable one offset at a time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PointerChain {
+    pub base: u64,
+    pub offsets: Vec<i64>,
+}
+
+impl PointerChain {
+    pub fn new(base: u64, offsets: Vec<i64>) -> Self {
+        Self { base, offsets }
+    }
+
+    /// Follow one more level of indirection
+    pub fn push_offset(&mut self, offset: i64) {
+        self.offsets.push(offset);
+    }
+
+    pub fn pop_offset(&mut self) -> Option<i64> {
+        self.offsets.pop()
+    }
+
+    /// Final address; for each offset the pointer at the current address is
+    /// read (`pointer_width` 4 or 8 bytes, little-endian) and the offset added
+    pub fn resolve(&self, pid: u32, pointer_width: usize) -> Result<u64, MemoryError> {
+        if pointer_width != 4 && pointer_width != 8 {
+            return Err(MemoryError::InvalidArgument(format!("Unsupported pointer width {}", pointer_width)));
+        }
+
+        let mut address = self.base;
+        for (i, &offset) in self.offsets.iter().enumerate() {
+            let bytes = MemoryEngine::read_value(pid, address, pointer_width)?;
+            let mut word = [0u8; 8];
+            word[..pointer_width].copy_from_slice(&bytes);
+            let ptr = u64::from_le_bytes(word);
+
+            if ptr == 0 {
+                return Err(MemoryError::Parse(format!("Null pointer at offset index {}", i)));
+            }
+            address = ptr.wrapping_add_signed(offset);
+        }
+        Ok(address)
+    }
+
+    /// Resolve with 64-bit pointers and read a value at the final address
+    pub fn resolve_and_read<T: bytemuck::Pod>(&self, pid: u32) -> Result<T, MemoryError> {
+        MemoryEngine::read_pod(pid, self.resolve(pid, 8)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Condition evaluated against each polled value
+pub type WatchCondition = Box<dyn Fn(&GameValue) -> bool + Send>;
+
+/// Polls a value in another process and reports when a condition becomes true
+pub struct Watchpoint {
+    pub pid: u32,
+    pub address: u64,
+    pub ty: GameValueType,
+    pub condition: WatchCondition,
+}
+
+impl Watchpoint {
+    pub fn new(
+        pid: u32,
+        address: u64,
+        ty: GameValueType,
+        condition: impl Fn(&GameValue) -> bool + Send + 'static,
+    ) -> Self {
+        Self { pid, address, ty, condition: Box::new(condition) }
+    }
+
+    /// Evaluate the condition against a value
+    pub fn check(&self, value: &GameValue) -> bool {
+        (self.condition)(value)
+    }
+
+    /// Poll every `interval` until `stop` is set or the process exits
+    /// `on_fire` runs when the condition turns true; with `keep_watching` it
+    /// runs again on every later false -> true transition, otherwise polling ends.
+    pub fn run(
+        &self,
+        interval: Duration,
+        keep_watching: bool,
+        stop: &AtomicBool,
+        mut on_fire: impl FnMut(&GameValue),
+    ) {
+        let mut was_satisfied = false;
+
+        while !stop.load(AtomicOrdering::Relaxed) {
+            match MemoryEngine::read_typed(self.pid, self.address, self.ty) {
+                Ok(value) => {
+                    let satisfied = self.check(&value);
+                    if satisfied && !was_satisfied {
+                        on_fire(&value);
+                        if !keep_watching {
+                            return;
+                        }
+                    }
+                    was_satisfied = satisfied;
+                }
+                Err(MemoryError::ProcessNotFound(_)) => return,
+                Err(_) => {}
+            }
+
+            std::thread::sleep(interval);
+        }
+    }
+
+    /// Run the poll loop on a background thread
+    pub fn start(
+        self,
+        interval: Duration,
+        keep_watching: bool,
+        on_fire: impl FnMut(&GameValue) + Send + 'static,
+    ) -> WatchpointHandle {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let thread = std::thread::spawn(move || {
+            self.run(interval, keep_watching, &thread_stop, on_fire);
+        });
+
+        WatchpointHandle { stop, thread: Some(thread) }
+    }
+}
+
+/// Handle to a running watchpoint thread
+/// Dropping the handle detaches the thread; call `stop` to end it.
+pub struct WatchpointHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WatchpointHandle {
+    /// Stop polling and wait for the thread to exit
+    pub fn stop(mut self) {
+        self.stop.store(true, AtomicOrdering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+
+    /// Whether the poll loop has ended (fired, stopped or process exited)
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(|t| t.is_finished())
+    }
+}
+
+/// File-backed mappings that appeared or disappeared since the last check
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryChanges {
+    pub loaded: Vec<String>,
+    pub unloaded: Vec<String>,
+}
+
+impl LibraryChanges {
+    pub fn is_empty(&self) -> bool {
+        self.loaded.is_empty() && self.unloaded.is_empty()
+    }
+}
+
+/// Tracks which libraries a process has mapped, to spot injected modules
+#[derive(Debug, Clone, Default)]
+pub struct LibraryWatcher {
+    pub known_libraries: FxHashSet<String>,
+}
+
+impl LibraryWatcher {
+    /// Watcher with nothing known; the first check reports every library as loaded
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Re-read /proc/<pid>/maps and report library changes since the last call
+    pub fn check_for_new_or_removed(&mut self, pid: u32) -> Result<LibraryChanges, MemoryError> {
+        let regions = MemoryEngine::parse_memory_maps(pid)?;
+        Ok(self.update_from_regions(&regions))
+    }
+
+    /// `check_for_new_or_removed` over an already parsed region list
+    /// Only absolute pathnames count; [heap], [stack] and anonymous maps are ignored.
+    pub fn update_from_regions(&mut self, regions: &[MemoryRegion]) -> LibraryChanges {
+        let current: FxHashSet<String> = regions.iter()
+            .filter(|r| r.pathname.starts_with('/'))
+            .map(|r| r.pathname.clone())
+            .collect();
+
+        let mut loaded: Vec<String> = current.difference(&self.known_libraries).cloned().collect();
+        let mut unloaded: Vec<String> = self.known_libraries.difference(&current).cloned().collect();
+        loaded.sort();
+        unloaded.sort();
+
+        self.known_libraries = current;
+        LibraryChanges { loaded, unloaded }
+    }
+
+    /// Poll every `interval` until `stop` is set or the process exits
+    /// The first poll only records the baseline; `on_change` runs for each later
+    /// non-empty set of changes.
+    pub fn run(
+        &mut self,
+        pid: u32,
+        interval: Duration,
+        stop: &AtomicBool,
+        mut on_change: impl FnMut(&LibraryChanges),
+    ) {
+        let mut baseline_taken = false;
+
+        while !stop.load(AtomicOrdering::Relaxed) {
+            match self.check_for_new_or_removed(pid) {
+                Ok(changes) => {
+                    if baseline_taken && !changes.is_empty() {
+                        on_change(&changes);
+                    }
+                    baseline_taken = true;
+                }
+                Err(MemoryError::ProcessNotFound(_)) => return,
+                Err(_) => {}
+            }
+
+            std::thread::sleep(interval);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -561,6 +2317,369 @@ mod tests {
         assert!((pos.2 - 30.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_parse_item_slots() {
+        // 16-byte stride: sword x1 @ 0.5, empty, garbage id
+        let mut data = Vec::new();
+        for (id, count, durability) in [(1201u32, 1u32, 0.5f32), (0, 0, 0.0), (999_999, 1, 1.0)] {
+            data.extend_from_slice(&id.to_le_bytes());
+            data.extend_from_slice(&count.to_le_bytes());
+            data.extend_from_slice(&durability.to_le_bytes());
+            data.extend_from_slice(&[0; 4]);
+        }
+
+        let slots = GameDataStructures::parse_item_slots(&data, 4, 16);
+        assert_eq!(slots.len(), 3);
+        assert_eq!(slots[0], Some(ItemSlot { item_id: 1201, count: 1, durability: 0.5 }));
+        assert_eq!(slots[1], None);
+        assert_eq!(slots[2], None);
+    }
+
+    #[test]
+    fn test_signature_verify() {
+        // Marker followed by HP=100, MaxHP=100, MP=50, MaxMP=100
+        let mut buffer = b"HPSTRUCT".to_vec();
+        for v in [100.0f32, 100.0, 50.0, 100.0] {
+            buffer.extend_from_slice(&v.to_le_bytes());
+        }
+        let regions = vec![region_for(&buffer)];
+        let pid = std::process::id();
+
+        // Shape the JNI caller sends: names are optional, an empty pattern is skipped
+        let signature: GameSignature = serde_json::from_str(&format!(
+            r#"{{"hp_pattern":{:?},"hp_mask":[true,true,true,true,true,true,true,true],"hp_offset":8,
+                "position_pattern":[],"position_mask":[],"position_offset":0}}"#,
+            b"HPSTRUCT".to_vec()
+        )).unwrap();
+        let result = signature.verify(pid, &regions);
+        assert_eq!(result.hp_address, Some(buffer.as_ptr() as u64 + 8));
+        assert_eq!(result.position_address, None);
+        assert!(result.confidence > 0.0);
+        assert!((result.confidence - 0.5).abs() < 1e-6);
+
+        let generic = GameSignature::generic_unity().verify(pid, &regions);
+        assert_eq!(generic.confidence, 0.0);
+    }
+
+    /// Build a region that covers a buffer in this test process
+    fn region_for<T>(buffer: &[T]) -> MemoryRegion {
+        let start_addr = buffer.as_ptr() as u64;
+        MemoryRegion {
+            start_addr,
+            end_addr: start_addr + std::mem::size_of_val(buffer) as u64,
+            permissions: "rw-p".to_string(),
+            offset: 0,
+            device: "00:00".to_string(),
+            inode: 0,
+            pathname: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_initial_unknown_scan() {
+        let values: Vec<i32> = vec![100, -5, 7, 42];
+        let regions = vec![region_for(&values)];
+        let pid = std::process::id();
+
+        let all = MemoryEngine::initial_unknown_scan(pid, GameValueType::Int32, &regions, 4, 100).unwrap();
+        let base = values.as_ptr() as u64;
+        assert_eq!(all, vec![(base, 100), (base + 4, -5), (base + 8, 7), (base + 12, 42)]);
+
+        let capped = MemoryEngine::initial_unknown_scan(pid, GameValueType::Int32, &regions, 1, 2).unwrap();
+        assert_eq!(capped.len(), 2);
+
+        assert!(MemoryEngine::initial_unknown_scan(pid, GameValueType::Int32, &regions, 0, 2).is_err());
+        assert!(MemoryEngine::initial_unknown_scan(pid, GameValueType::Int32, &regions, 4, 0).unwrap().is_empty());
+
+        // 6-byte chunks: aligned values still line up and the one at 4..8 spans a boundary
+        let file = MemoryEngine::open_proc_file(pid, "mem").unwrap();
+        let chunked = MemoryEngine::initial_unknown_scan_in(&file, GameValueType::Int32, &regions, 4, 100, 6);
+        assert_eq!(chunked, all);
+    }
+
+    #[test]
+    fn test_search_pattern_cancellable() {
+        let buffer = b"xxMAGICxx".to_vec();
+        let regions = vec![region_for(&buffer)];
+        let pid = std::process::id();
+
+        let cancel = AtomicBool::new(false);
+        let matches = MemoryEngine::search_pattern_cancellable(pid, b"MAGIC", &regions, 10, &cancel).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].address, buffer.as_ptr() as u64 + 2);
+
+        cancel.store(true, AtomicOrdering::Relaxed);
+        let result = MemoryEngine::search_pattern_cancellable(pid, b"MAGIC", &regions, 10, &cancel);
+        let err = result.unwrap_err();
+        assert_eq!(err, MemoryError::Cancelled);
+        assert_eq!(err.to_string(), "Scan cancelled");
+    }
+
+    #[test]
+    fn test_search_pattern_chunked_boundary() {
+        // Chunks of 16 bytes; MAGIC occupies offsets 14..19, straddling the first boundary
+        let mut buffer = vec![b'.'; 40];
+        buffer[14..19].copy_from_slice(b"MAGIC");
+        buffer[32..37].copy_from_slice(b"MAGIC");
+        let regions = vec![region_for(&buffer)];
+        let pid = std::process::id();
+
+        let matches = MemoryEngine::search_pattern_chunked(pid, b"MAGIC", &regions, 10, 16).unwrap();
+        let offsets: Vec<u64> = matches.iter().map(|m| m.offset_in_region).collect();
+        assert_eq!(offsets, vec![14, 32]);
+        assert_eq!(matches[0].address, buffer.as_ptr() as u64 + 14);
+
+        assert!(MemoryEngine::search_pattern_chunked(pid, b"MAGIC", &regions, 10, 0).is_err());
+    }
+
+    #[test]
+    fn test_write_batch() {
+        let buffer = vec![0u32; 64];
+        let base = buffer.as_ptr() as u64;
+        let pid = std::process::id();
+
+        let a = 7u32.to_le_bytes();
+        let b = 9u32.to_le_bytes();
+        let c = 11u32.to_le_bytes();
+        // Out of order; a/b are 4 bytes apart and merge, c is 200 bytes away
+        let writes: Vec<(u64, &[u8])> = vec![(base + 16, &b), (base, &a), (base + 216, &c)];
+        let results = MemoryEngine::write_batch(pid, &writes).unwrap();
+
+        assert!(results.iter().all(|r| r.is_ok()));
+        let values: Vec<u32> = buffer.iter().map(|v| unsafe { std::ptr::read_volatile(v) }).collect();
+        assert_eq!(values[0], 7);
+        assert_eq!(values[4], 9);
+        assert_eq!(values[54], 11);
+        assert!(values.iter().enumerate().all(|(i, &v)| v == 0 || [0, 4, 54].contains(&i)));
+
+        let big = vec![0u8; MAX_WRITE_BATCH_BYTES + 1];
+        let result = MemoryEngine::write_batch(pid, &[(base, &big)]);
+        assert!(matches!(result, Err(MemoryError::InvalidArgument(_))));
+
+        let result = MemoryEngine::write_batch(pid, &[(u64::MAX - 1, &[1, 2, 3])]);
+        assert!(matches!(result, Err(MemoryError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_watchpoint_condition() {
+        // HP below 30% of 1000
+        let watchpoint = Watchpoint::new(0, 0, GameValueType::Float32,
+            |v| v.as_f64().is_some_and(|hp| hp < 300.0));
+
+        assert!(watchpoint.check(&GameValue::Float32(250.0)));
+        assert!(!watchpoint.check(&GameValue::Float32(800.0)));
+        assert!(watchpoint.check(&GameValue::Int32(10)));
+        assert!(!watchpoint.check(&GameValue::String("250".to_string())));
+    }
+
+    #[test]
+    fn test_watchpoint_fires_once() {
+        let hp = Box::new(250.0f32);
+        let address = &*hp as *const f32 as u64;
+        let watchpoint = Watchpoint::new(std::process::id(), address, GameValueType::Float32,
+            |v| v.as_f64().is_some_and(|hp| hp < 300.0));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let handle = watchpoint.start(Duration::from_millis(1), false, move |v| {
+            let _ = tx.send(v.as_f64());
+        });
+
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), Some(250.0));
+        handle.stop();
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_export_debugger_commands() {
+        let matches: Vec<PatternMatch> = [0x7f12345678u64, 0x7f12345680].iter()
+            .map(|&address| PatternMatch {
+                address,
+                region_start: 0x7f12340000,
+                offset_in_region: address - 0x7f12340000,
+                matched_bytes: vec![0; 4],
+            })
+            .collect();
+
+        assert_eq!(
+            MemoryEngine::export_as_gdb_commands(&matches, "int"),
+            "watch *(int*)0x7f12345678\nwatch *(int*)0x7f12345680\n"
+        );
+        assert_eq!(
+            MemoryEngine::export_as_lldb_commands(&matches[..1], "double"),
+            "watchpoint set expression -w write -s 8 -- 0x7f12345678\n"
+        );
+
+        let script = MemoryEngine::export_as_frida_script(&matches, "Player_Update");
+        assert!(script.contains("ptr('0x7f12345680')"));
+        assert!(script.contains("Module.getExportByName(null, \"Player_Update\")"));
+        assert!(script.contains("addr.readS32()"));
+
+        let script = MemoryEngine::export_as_frida_script(&matches, "a'); evil('");
+        assert!(script.contains("getExportByName(null, \"a'); evil('\")"));
+    }
+
+    #[test]
+    fn test_export_regions() {
+        let regions: Vec<MemoryRegion> = [
+            "7f12340000-7f12380000 r-xp 00000000 fd:01 1234 /data/app/lib/libgame.so",
+            "7f12380000-7f12390000 rw-p 00000000 00:00 0",
+            "7f12390000-7f123a0000 ---p 00000000 00:00 0",
+        ]
+        .iter()
+        .filter_map(|line| MemoryEngine::parse_maps_line(line))
+        .collect();
+        assert_eq!(regions.len(), 3);
+
+        assert_eq!(
+            MemoryEngine::export_regions_gdb(&regions),
+            "mem 0x7f12340000 0x7f12380000 ro\nmem 0x7f12380000 0x7f12390000 rw\n"
+        );
+
+        let script = MemoryEngine::export_regions_frida(&regions);
+        assert!(script.starts_with("const REGIONS = [\n"));
+        assert!(script.contains(
+            "{ base: ptr('0x7f12340000'), size: 0x40000, protection: 'r-x', path: \"/data/app/lib/libgame.so\" }"
+        ));
+        assert_eq!(script.matches("base: ptr(").count(), 3);
+    }
+
+    #[test]
+    fn test_next_scan_float_near() {
+        // Mocked memory at 0x1000: x drifted 10.0 -> 10.2, y moved 8 bytes, z jumped
+        let mut memory = [0u8; 64];
+        memory[0..4].copy_from_slice(&10.2f32.to_le_bytes());
+        memory[24..28].copy_from_slice(&20.1f32.to_le_bytes());
+        memory[32..36].copy_from_slice(&500.0f32.to_le_bytes());
+        let read = |address: u64, buf: &mut [u8]| {
+            let start = address as usize - 0x1000;
+            match memory.get(start..start + buf.len()) {
+                Some(src) => { buf.copy_from_slice(src); true }
+                None => false,
+            }
+        };
+
+        let prev = |offset: u64, value: f32| PatternMatch {
+            address: 0x1000 + offset,
+            region_start: 0x1000,
+            offset_in_region: offset,
+            matched_bytes: value.to_le_bytes().to_vec(),
+        };
+        let previous = vec![prev(0, 10.0), prev(16, 20.0), prev(32, 30.0)];
+
+        let results = MemoryEngine::next_scan_float_near_with(&previous, 0.5, read);
+        let addresses: Vec<u64> = results.iter().map(|m| m.address).collect();
+        assert_eq!(addresses, vec![0x1000, 0x1018]);
+        assert_eq!(results[1].offset_in_region, 24);
+        assert_eq!(results[0].matched_bytes, 10.2f32.to_le_bytes().to_vec());
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+    struct PlayerStats {
+        hp: f32,
+        max_hp: f32,
+        level: u32,
+    }
+
+    #[test]
+    fn test_read_with_crc32() {
+        let pid = std::process::id();
+        let buffer = b"123456789".to_vec();
+        let address = buffer.as_ptr() as u64;
+
+        let (data, crc) = MemoryEngine::read_with_crc32(pid, address, buffer.len()).unwrap();
+        assert_eq!(data, buffer);
+        assert_eq!(crc, 0xCBF4_3926); // Standard CRC-32 check value
+        assert_eq!(MemoryEngine::verify_stable_read(pid, address, buffer.len(), 5).unwrap(), buffer);
+
+        // Torn reads restart the streak; the value settles on 7 after three changes
+        let mut reads = [1u8, 2, 3, 7, 7, 7].into_iter().chain(std::iter::repeat(9));
+        let settled = MemoryEngine::read_until_stable(0x1000, 1, 3, |buffer| {
+            buffer[0] = reads.next().unwrap();
+            Ok(())
+        });
+        assert_eq!(settled.unwrap(), vec![7]);
+
+        // A value that never settles uses up the attempt budget
+        let mut counter = 0u8;
+        let err = MemoryEngine::read_until_stable(0x1000, 1, 3, |buffer| {
+            counter += 1;
+            buffer[0] = counter;
+            Ok(())
+        })
+        .unwrap_err();
+        assert_eq!(err, MemoryError::Unstable { address: 0x1000, attempts: 12 });
+        assert_eq!(counter, 12);
+        assert_eq!(err.code(), 7);
+    }
+
+    #[test]
+    fn test_read_write_pod() {
+        let pid = std::process::id();
+        let stats = Box::new(PlayerStats { hp: 75.0, max_hp: 100.0, level: 12 });
+        let address = &*stats as *const PlayerStats as u64;
+
+        assert_eq!(MemoryEngine::read_pod::<PlayerStats>(pid, address).unwrap(), *stats);
+        assert_eq!(MemoryEngine::read_pod::<u32>(pid, address + 8).unwrap(), 12);
+
+        let target = Box::new(PlayerStats { hp: 0.0, max_hp: 0.0, level: 0 });
+        let target_address = &*target as *const PlayerStats as u64;
+        MemoryEngine::write_pod(pid, target_address, &*stats).unwrap();
+        assert_eq!(unsafe { std::ptr::read_volatile(&*target) }, *stats);
+    }
+
+    #[test]
+    fn test_search_int32_paged() {
+        let mut buffer = vec![0i32; 32];
+        for i in [1, 4, 5, 20, 31] {
+            buffer[i] = 100;
+        }
+        let regions = vec![region_for(&buffer)];
+        let pid = std::process::id();
+        let base = buffer.as_ptr() as u64;
+
+        let mut found = Vec::new();
+        let mut cursor = 0;
+        let mut pages = 0;
+        loop {
+            let (page, next) = MemoryEngine::search_int32_paged(pid, 100, &regions, 2, cursor).unwrap();
+            assert!(page.len() <= 2);
+            found.extend(page.iter().map(|m| (m.address - base) / 4));
+            assert!(page.iter().all(|m| m.region_start == base));
+            pages += 1;
+            match next {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+
+        assert_eq!(found, vec![1, 4, 5, 20, 31]);
+        assert_eq!(pages, 3);
+    }
+
+    #[test]
+    fn test_search_one_per_region() {
+        let mut first = vec![0i32; 16];
+        let second = vec![0i32; 16];
+        let mut third = vec![0f32; 16];
+        first[2] = 4242;
+        first[9] = 4242;
+        third[3] = 42.5;
+        third[7] = 42.5;
+        let regions = vec![region_for(&first), region_for(&second), region_for(&third)];
+        let pid = std::process::id();
+
+        let all = MemoryEngine::search_int32(pid, 4242, &regions, 100, false).unwrap();
+        assert_eq!(all.len(), 2);
+        let probe = MemoryEngine::search_int32(pid, 4242, &regions, 100, true).unwrap();
+        assert_eq!(probe.len(), 1);
+        assert_eq!(probe[0].address, first.as_ptr() as u64 + 8);
+
+        let probe = MemoryEngine::search_float32(pid, 42.5, 0.01, &regions, 100, true).unwrap();
+        assert_eq!(probe.len(), 1);
+        assert_eq!(probe[0].region_start, third.as_ptr() as u64);
+    }
+
     #[test]
     fn test_region_filters() {
         let region = MemoryRegion {
@@ -579,4 +2698,420 @@ mod tests {
         assert!(region.is_heap());
         assert!(!region.is_stack());
     }
+
+    #[test]
+    fn test_filter_code_regions() {
+        let maps = [
+            "70000000-70010000 r--p 00000000 08:01 100 /data/app/lib/arm64/libil2cpp.so",
+            "70010000-70800000 r-xp 00010000 08:01 100 /data/app/lib/arm64/libil2cpp.so",
+            "70800000-70900000 rw-p 00800000 08:01 100 /data/app/lib/arm64/libil2cpp.so",
+            "71000000-71100000 r-xp 00000000 08:01 200 /system/lib64/libc.so",
+            "72000000-72100000 rw-p 00000000 00:00 0 [heap]",
+            "73000000-73001000 --xp 00000000 00:00 0",
+        ];
+        let regions: Vec<MemoryRegion> = maps.iter()
+            .filter_map(|line| MemoryEngine::parse_maps_line(line))
+            .collect();
+
+        let code = MemoryEngine::filter_code_regions(&regions, None);
+        assert_eq!(code.len(), 2);
+        assert!(code.iter().all(|r| r.permissions == "r-xp"));
+
+        let il2cpp = MemoryEngine::filter_code_regions(&regions, Some("libil2cpp"));
+        assert_eq!(il2cpp.len(), 1);
+        assert_eq!(il2cpp[0].start_addr, 0x70010000);
+    }
+
+    #[test]
+    fn test_scan_skips_unreadable_chunk() {
+        // 64-byte region read in 16-byte chunks; the first chunk is a "guard page"
+        let mut memory = [0u8; 64];
+        memory[5..9].copy_from_slice(&[0xAA, 0xBB, 0xCC, 0xDD]);
+        memory[40..44].copy_from_slice(&[0xAA, 0xBB, 0xCC, 0xDD]);
+        let region = MemoryRegion {
+            start_addr: 0x1000,
+            end_addr: 0x1040,
+            permissions: "rw-p".to_string(),
+            offset: 0,
+            device: "00:00".to_string(),
+            inode: 0,
+            pathname: String::new(),
+        };
+
+        let matches = MemoryEngine::scan_chunked_with(
+            &[0xAA, 0xBB, 0xCC, 0xDD], &[region], 10, 16, false, &AtomicBool::new(false),
+            |address, buffer| {
+                let start = (address - 0x1000) as usize;
+                if start < 16 {
+                    return false;
+                }
+                buffer.copy_from_slice(&memory[start..start + buffer.len()]);
+                true
+            },
+        ).unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].address, 0x1000 + 40);
+    }
+
+    #[test]
+    fn test_find_field_offset() {
+        let pid = std::process::id();
+        let mut data = [0u8; 64];
+        data[12..16].copy_from_slice(&95.0f32.to_le_bytes());
+        data[40..44].copy_from_slice(&1234i32.to_le_bytes());
+        let base = data.as_ptr() as u64;
+
+        let hp = MemoryEngine::find_field_offset(pid, base, &95.0f32.to_le_bytes(), data.len()).unwrap();
+        assert_eq!(hp, Some(12));
+
+        let offsets = MemoryEngine::find_field_offsets_multi(
+            pid,
+            base,
+            &[&95.0f32.to_le_bytes(), &1234i32.to_le_bytes(), &777i32.to_le_bytes()],
+            data.len(),
+        ).unwrap();
+        assert_eq!(offsets, vec![Some(12), Some(40), None]);
+
+        assert!(matches!(
+            MemoryEngine::find_field_offset(pid, base, &[], data.len()),
+            Err(MemoryError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_endian_decoding() {
+        let bytes = [0x3F, 0x80, 0x00, 0x00];
+        assert_eq!(Endian::Big.f32_from_bytes(bytes), 1.0);
+        assert_eq!(Endian::Little.f32_from_bytes(bytes), f32::from_bits(0x0000_803F));
+        assert_eq!(Endian::Big.i32_from_bytes([0, 0, 1, 0]), 256);
+        assert_eq!(Endian::Little.i32_from_bytes([0, 0, 1, 0]), 65536);
+        assert_eq!(Endian::default(), Endian::Little);
+
+        let mut position = Vec::new();
+        for v in [1.5f32, -2.0, 300.25] {
+            position.extend_from_slice(&v.to_be_bytes());
+        }
+        assert_eq!(GameDataStructures::parse_position_endian(&position, Endian::Big), Some((1.5, -2.0, 300.25)));
+        assert_ne!(GameDataStructures::parse_position(&position), Some((1.5, -2.0, 300.25)));
+
+        // Same memory read and searched both ways
+        let pid = std::process::id();
+        let values: Vec<u32> = vec![0x0102_0304, 0, 77u32.to_be(), 0];
+        let address = values.as_ptr() as u64;
+        assert_eq!(MemoryEngine::read_int32(pid, address).unwrap(), 0x0102_0304);
+        assert_eq!(MemoryEngine::read_int32_endian(pid, address, Endian::Big).unwrap(), 0x0403_0201);
+
+        let matches = MemoryEngine::search_int32_endian(pid, 77, &[region_for(&values)], 10, false, Endian::Big).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].address, address + 8);
+    }
+
+    #[test]
+    fn test_safe_memory_reader() {
+        let values: Vec<u32> = vec![0xDEAD_BEEF, 42];
+        let address = values.as_ptr() as u64;
+
+        let mut reader = SafeMemoryReader::new(std::process::id()).unwrap();
+        assert!(reader.regions().iter().any(|r| r.start_addr <= address && address < r.end_addr));
+        for _ in 0..3 {
+            assert_eq!(reader.read_value(address, 4).unwrap(), 0xDEAD_BEEFu32.to_le_bytes().to_vec());
+        }
+        assert_eq!(reader.read_value(address + 4, 4).unwrap(), 42u32.to_le_bytes().to_vec());
+        reader.refresh_regions().unwrap();
+        assert!(!reader.regions().is_empty());
+
+        // Searches reuse the open handle
+        let regions = vec![region_for(&values)];
+        let found = reader.search_int32(42, &regions, 10, false).unwrap();
+        assert_eq!(found.iter().map(|m| m.address).collect::<Vec<_>>(), vec![address + 4]);
+        assert_eq!(reader.initial_unknown_scan(GameValueType::Int32, &regions, 4, 10).unwrap().len(), 2);
+
+        assert!(SafeMemoryReader::new(u32::MAX).is_err());
+    }
+
+    #[test]
+    fn test_search_float32_ulps() {
+        let step = |f: f32, n: u32| f32::from_bits(f.to_bits() + n);
+        assert_eq!(MemoryEngine::ulps_distance(100.0, step(100.0, 3)), 3);
+        assert_eq!(MemoryEngine::ulps_distance(0.0, -0.0), 0);
+        assert_eq!(MemoryEngine::ulps_distance(-f32::from_bits(1), f32::from_bits(1)), 2);
+
+        // Same ULP window works at both magnitudes; an absolute 1e-3 only fits one
+        let values: Vec<f32> = vec![step(100.0, 2), step(10000.0, 2), step(10000.0, 50), 100.5];
+        let regions = [region_for(&values)];
+        let pid = std::process::id();
+
+        let near_hp = MemoryEngine::search_float32_ulps(pid, 100.0, 4, &regions, 10).unwrap();
+        assert_eq!(near_hp.len(), 1);
+        assert_eq!(near_hp[0].offset_in_region, 0);
+
+        let near_coord = MemoryEngine::search_float32_ulps(pid, 10000.0, 4, &regions, 10).unwrap();
+        assert_eq!(near_coord.len(), 1);
+        assert_eq!(near_coord[0].offset_in_region, 4);
+
+        let absolute = MemoryEngine::search_float32(pid, 10000.0, 1e-3, &regions, 10, false).unwrap();
+        assert!(absolute.is_empty());
+    }
+
+    #[test]
+    fn test_find_rtti_type_name() {
+        let pid = std::process::id();
+        let name = b"N4Game6PlayerE\0".to_vec();
+        let typeinfo: Vec<u64> = vec![0, name.as_ptr() as u64];
+        let vtable: Vec<u64> = vec![typeinfo.as_ptr() as u64, 0xdead_beef];
+        let object: Vec<u64> = vec![vtable.as_ptr() as u64 + 8, 42];
+        let regions = MemoryEngine::parse_memory_maps(pid).unwrap();
+
+        let found = MemoryEngine::find_rtti_type_name(pid, object.as_ptr() as u64, 0, &regions).unwrap();
+        assert_eq!(found.as_deref(), Some("N4Game6PlayerE"));
+        assert_eq!(MemoryEngine::demangle_type_name("N4Game6PlayerE"), "Game::Player");
+        assert_eq!(MemoryEngine::demangle_type_name("not a type"), "not a type");
+
+        // vtable below the library base, or no mapped regions: not an object we can identify
+        let above = MemoryEngine::find_rtti_type_name(pid, object.as_ptr() as u64, u64::MAX, &regions).unwrap();
+        assert_eq!(above, None);
+        assert_eq!(MemoryEngine::find_rtti_type_name(pid, object.as_ptr() as u64, 0, &[]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_and_patch_vtable() {
+        let pid = std::process::id();
+        let lib_base = 0x7000_0000u64;
+        // Patched through /proc/self/mem below, so the table must be mutable
+        let mut vtable: Vec<u64> = vec![lib_base + 0x100, lib_base + 0x200, lib_base + 0x300];
+        let vtable_address = vtable.as_mut_ptr() as u64;
+        let object: Vec<u64> = vec![vtable_address, 42];
+        let object_address = object.as_ptr() as u64;
+
+        let methods = MemoryEngine::read_vtable(pid, object_address, 3, lib_base, 0x1000).unwrap();
+        assert_eq!(methods, vtable);
+        // A library too small to contain the last entry rejects the table
+        assert!(matches!(
+            MemoryEngine::read_vtable(pid, object_address, 3, lib_base, 0x300),
+            Err(MemoryError::Parse(_))
+        ));
+
+        assert!(matches!(
+            MemoryEngine::read_vtable(pid, object_address, usize::MAX, lib_base, 0x1000),
+            Err(MemoryError::InvalidArgument(_))
+        ));
+
+        let original = MemoryEngine::patch_vtable_entry(pid, vtable_address, 1, 0xdead_beef).unwrap();
+        assert_eq!(original, lib_base + 0x200);
+        assert_eq!(unsafe { std::ptr::read_volatile(vtable.as_ptr().add(1)) }, 0xdead_beef);
+        assert!(matches!(
+            MemoryEngine::patch_vtable_entry(pid, u64::MAX - 4, 1, 0),
+            Err(MemoryError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_missing_process_errors() {
+        // Above the kernel's pid_max, so never a live process
+        let pid = u32::MAX;
+        let not_found = MemoryError::ProcessNotFound(pid.to_string());
+        assert_eq!(MemoryEngine::parse_memory_maps(pid).unwrap_err(), not_found);
+        assert_eq!(MemoryEngine::read_value(pid, 0x1000, 4).unwrap_err(), not_found);
+        assert_eq!(MemoryEngine::read_int32(pid, 0x1000).unwrap_err(), not_found);
+        assert_eq!(MemoryEngine::search_int32(pid, 1, &[], 10, false).unwrap_err(), not_found);
+        assert_eq!(not_found.to_string(), "Process not found: 4294967295");
+
+        let package = MemoryEngine::find_pid_by_package("com.example.not.running").unwrap_err();
+        assert_eq!(package.to_string(), "Process not found: com.example.not.running");
+        assert_eq!(package.code(), 1);
+
+        let empty = MemoryEngine::search_pattern(std::process::id(), &[], &[], 10).unwrap_err();
+        assert_eq!(empty, MemoryError::InvalidArgument("Empty pattern".to_string()));
+        assert_eq!(empty.to_string(), "Empty pattern");
+    }
+
+    #[test]
+    fn test_region_snapshot_changes() {
+        let pid = std::process::id();
+        let mut memory = vec![0u8; 32];
+        let regions = [region_for(&memory)];
+
+        let snapshot = MemoryEngine::snapshot_regions_full(pid, &regions).unwrap();
+        assert_eq!(snapshot.total_bytes(), 32);
+        assert!(snapshot.changed_bytes_since(pid, &regions).unwrap().is_empty());
+
+        memory[3] = 7;
+        memory[20] = 0xFF;
+        std::hint::black_box(&memory);
+
+        let base = regions[0].start_addr;
+        let changes = snapshot.changed_bytes_since(pid, &regions).unwrap();
+        assert_eq!(changes, vec![
+            ChangedByte { address: base + 3, old: 0, new: 7 },
+            ChangedByte { address: base + 20, old: 0, new: 0xFF },
+        ]);
+    }
+
+    #[test]
+    fn test_library_watcher() {
+        let maps = |lines: &[&str]| -> Vec<MemoryRegion> {
+            lines.iter().filter_map(|line| MemoryEngine::parse_maps_line(line)).collect()
+        };
+        let game = "70000000-70800000 r-xp 00000000 08:01 100 /data/app/lib/arm64/libgame.so";
+        let libc = "71000000-71100000 r-xp 00000000 08:01 200 /system/lib64/libc.so";
+        let heap = "72000000-72100000 rw-p 00000000 00:00 0 [heap]";
+        let injected = "73000000-73010000 r-xp 00000000 08:01 300 /data/local/tmp/libac.so";
+
+        let mut watcher = LibraryWatcher::new();
+        let first = watcher.update_from_regions(&maps(&[game, libc, heap]));
+        assert_eq!(first.loaded, vec!["/data/app/lib/arm64/libgame.so", "/system/lib64/libc.so"]);
+        assert!(watcher.update_from_regions(&maps(&[game, libc, heap])).is_empty());
+
+        let changes = watcher.update_from_regions(&maps(&[game, injected, heap]));
+        assert_eq!(changes.loaded, vec!["/data/local/tmp/libac.so"]);
+        assert_eq!(changes.unloaded, vec!["/system/lib64/libc.so"]);
+
+        // Against this process: everything is new once, then stable
+        let pid = std::process::id();
+        let mut live = LibraryWatcher::new();
+        assert!(!live.check_for_new_or_removed(pid).unwrap().loaded.is_empty());
+        assert!(live.check_for_new_or_removed(pid).unwrap().unloaded.is_empty());
+    }
+
+    #[test]
+    fn test_search_any() {
+        let pid = std::process::id();
+        let as_int = 100i32.to_le_bytes().to_vec();
+        let as_float = 100.0f32.to_le_bytes().to_vec();
+
+        let mut buffer = vec![0u8; 64];
+        buffer[4..8].copy_from_slice(&as_float);
+        buffer[20..24].copy_from_slice(&as_int);
+        buffer[40..44].copy_from_slice(&as_float);
+        let regions = [region_for(&buffer)];
+        let base = regions[0].start_addr;
+
+        let found = MemoryEngine::search_any(pid, &[as_int.clone(), as_float.clone()], &regions, 10).unwrap();
+        let hits: Vec<(u64, usize)> = found.iter().map(|(m, index)| (m.address - base, *index)).collect();
+        assert_eq!(hits, vec![(4, 1), (20, 0), (40, 1)]);
+        assert_eq!(found[1].0.matched_bytes, as_int);
+
+        assert_eq!(MemoryEngine::search_any(pid, &[as_int, as_float], &regions, 2).unwrap().len(), 2);
+        assert!(matches!(
+            MemoryEngine::search_any(pid, &[Vec::new()], &regions, 10),
+            Err(MemoryError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_match_set_operations() {
+        let pattern_match = |address: u64| PatternMatch {
+            address,
+            region_start: address & !0xFFF,
+            offset_in_region: address & 0xFFF,
+            matched_bytes: vec![1],
+        };
+
+        // Same address matched by two patterns appears once
+        let hp_value = MatchSet::from_matches([0x30, 0x10, 0x20, 0x10].map(pattern_match).to_vec());
+        assert_eq!(hp_value.addresses(), &[0x10, 0x20, 0x30]);
+        assert_eq!(hp_value.len(), 3);
+        assert!(hp_value.contains(0x20));
+        assert!(!hp_value.contains(0x25));
+
+        let hp_decreased: MatchSet = [0x20, 0x30, 0x40].into_iter().collect();
+        assert_eq!(hp_value.intersect(&hp_decreased).addresses(), &[0x20, 0x30]);
+        assert_eq!(hp_value.union(&hp_decreased).addresses(), &[0x10, 0x20, 0x30, 0x40]);
+        assert_eq!(hp_value.difference(&hp_decreased).addresses(), &[0x10]);
+        assert_eq!(hp_decreased.difference(&hp_value).addresses(), &[0x40]);
+
+        let empty = MatchSet::default();
+        assert!(hp_value.intersect(&empty).is_empty());
+        assert_eq!(hp_value.union(&empty), hp_value);
+    }
+
+    #[test]
+    fn test_pointer_chain() {
+        let pid = std::process::id();
+        // [[base] + 8] + 8: base -> level1, level1[1] -> stats, hp at stats + 8
+        let stats: Vec<u32> = vec![0, 0, 640, 0];
+        let level1: Vec<u64> = vec![0, stats.as_ptr() as u64];
+        let base = Box::new(level1.as_ptr() as u64);
+
+        let mut chain = PointerChain::new(&*base as *const u64 as u64, vec![8]);
+        chain.push_offset(8);
+        chain.push_offset(8);
+        assert_eq!(chain.pop_offset(), Some(8));
+        assert_eq!(chain.resolve(pid, 8).unwrap(), stats.as_ptr() as u64 + 8);
+        assert_eq!(chain.resolve_and_read::<u32>(pid).unwrap(), 640);
+        assert!(matches!(chain.resolve(pid, 2), Err(MemoryError::InvalidArgument(_))));
+
+        let parsed: PointerChain = serde_json::from_str(&chain.to_json()).unwrap();
+        assert_eq!(parsed, chain);
+        assert_eq!(
+            MemoryEngine::resolve_pointer_chain(pid, chain.base, &[8, 8]).unwrap(),
+            stats.as_ptr() as u64 + 8
+        );
+    }
+
+    #[test]
+    fn test_address_tracker_relocate() {
+        let pid = std::process::id();
+        let signature = [0xDE, 0xAD, 0x00, 0xBE, 0xEF];
+        let mut memory = vec![0u8; 64];
+        memory[8..13].copy_from_slice(&signature);
+        let regions = [region_for(&memory)];
+        let base = regions[0].start_addr;
+
+        let mut mask = vec![true; 5];
+        mask[2] = false;
+        let mut tracker = AddressTracker::new(signature.to_vec(), mask, 16, 0);
+        assert_eq!(tracker.relocate(pid, &regions).unwrap(), base + 24);
+        assert_eq!(tracker.last_known_address, base + 24);
+
+        // Wildcard byte changing doesn't count as a move
+        memory[10] = 0x77;
+        std::hint::black_box(&memory);
+        assert_eq!(tracker.relocate(pid, &regions).unwrap(), base + 24);
+
+        // "Next session": the pattern now lives elsewhere
+        memory[8..13].fill(0);
+        memory[30..35].copy_from_slice(&signature);
+        std::hint::black_box(&memory);
+        assert_eq!(tracker.relocate(pid, &regions).unwrap(), base + 46);
+
+        memory[30..35].fill(0);
+        std::hint::black_box(&memory);
+        let err = tracker.relocate(pid, &regions).unwrap_err();
+        assert!(matches!(err, MemoryError::PatternNotFound));
+        assert_eq!(err.code(), 8);
+        assert_eq!(tracker.last_known_address, base + 46);
+    }
+
+    #[test]
+    fn test_profile_write_hotspots() {
+        use std::sync::atomic::AtomicI32;
+
+        let pid = std::process::id();
+        let words: Arc<Vec<AtomicI32>> = Arc::new((0..16).map(|_| AtomicI32::new(0)).collect());
+        let regions = [region_for(&words[..])];
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let writer = {
+            let (words, stop) = (Arc::clone(&words), Arc::clone(&stop));
+            std::thread::spawn(move || {
+                while !stop.load(AtomicOrdering::Relaxed) {
+                    words[2].fetch_add(1, AtomicOrdering::Relaxed);
+                    std::thread::sleep(Duration::from_micros(200));
+                }
+            })
+        };
+
+        let hot = MemoryEngine::profile_write_hotspots(pid, &regions, 5, 5).unwrap();
+        stop.store(true, AtomicOrdering::Relaxed);
+        writer.join().unwrap();
+
+        assert_eq!(hot.len(), 1);
+        assert_eq!(hot[0].address, regions[0].start_addr + 8);
+        assert!((2..=4).contains(&hot[0].change_count));
+        assert!(hot[0].value_range.0 < hot[0].value_range.1);
+
+        assert!(MemoryEngine::profile_write_hotspots(pid, &regions, 0, 1).is_err());
+    }
 }
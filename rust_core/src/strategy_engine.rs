@@ -7,10 +7,42 @@
 
 use crate::image_engine::Rect;
 use priority_queue::PriorityQueue;
+use rand::Rng;
 use rayon::prelude::*;
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
 use std::cmp::{Ordering, Reverse};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+
+/// Widest passage `build_threat_heatmap` treats as a corridor
+const CORRIDOR_MAX_WIDTH: usize = 2;
+
+/// Extra threat on corridor cells, where enemies can cut off escape
+const CORRIDOR_THREAT: f32 = 0.5;
+
+/// Wall distance below which `find_path_with_clearance` charges extra per step
+const PREFERRED_CLEARANCE: i32 = 3;
+
+/// Enemy distance (cells) at which `position_score` treats a spot as fully safe
+const SAFE_RANGE: f32 = 6.0;
+
+/// Allies within this many cells count as support in `position_score`
+const ALLY_SUPPORT_RADIUS: i32 = 5;
+
+/// Decision priority of `CombatAction::InterruptCast`, above even a critical-HP retreat
+const INTERRUPT_PRIORITY: i32 = 110;
+
+/// Value of interrupting a cast whose name contains the key (lowercase); first match wins
+const INTERRUPT_TABLE: &[(&str, u8)] = &[
+    ("ultimate", 100),
+    ("revive", 95),
+    ("resurrect", 95),
+    ("heal", 90),
+    ("channel", 70),
+    ("teleport", 60),
+    ("recall", 50),
+];
 
 /// Move operation for eliminate games
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -21,7 +53,46 @@ pub struct EliminateMove {
     pub to_col: usize,
     pub score: i32,
     pub eliminates: usize, // Number of pieces eliminated
-    pub creates_special: bool, // Creates special piece (4+ match)
+    pub creates_special: bool, // Creates special piece (4+ match or T/L shape)
+    #[serde(default)]
+    pub special: Option<SpecialType>, // Which special piece the move creates
+}
+
+/// Special piece created by a match shape (ordered by value)
+/// Sizes are for the default `min_match` of 3; lines scale with `EliminateRules`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SpecialType {
+    /// Straight line of 4 (`min_match + 1`)
+    Line4,
+    /// 5-cell T or L shape (3 + 3 sharing the pivot)
+    TShape,
+    /// Straight line of 5 or more (`min_match + 2`)
+    Line5,
+}
+
+/// Game-specific matching rules for `EliminateEngine`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EliminateRules {
+    /// Shortest straight run that clears (3 for classic match-3)
+    pub min_match: usize,
+}
+
+impl Default for EliminateRules {
+    fn default() -> Self {
+        Self { min_match: 3 }
+    }
+}
+
+/// Board after a move has fully settled, see `EliminateEngine::simulate_cascade`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CascadeResult {
+    pub board: Vec<Vec<u8>>,
+    /// Pieces cleared across all cascade steps
+    pub total_cleared: usize,
+    /// Clear-and-drop rounds (1 = no chain reaction)
+    pub cascades: usize,
+    /// Cleared pieces per color id (index 0 is unused, ids >= 8 aren't counted)
+    pub cleared_by_color: [usize; 8],
 }
 
 impl EliminateMove {
@@ -34,16 +105,73 @@ impl EliminateMove {
             score: 0,
             eliminates: 0,
             creates_special: false,
+            special: None,
+        }
+    }
+
+    /// The same move on the transposed board (rows and columns swapped)
+    pub fn transposed(&self) -> Self {
+        Self {
+            from_row: self.from_col,
+            from_col: self.from_row,
+            to_row: self.to_col,
+            to_col: self.to_row,
+            ..*self
+        }
+    }
+
+    /// The same move on a board of `cols` columns mirrored left-right
+    /// An empty board (`cols == 0`) has nothing to mirror; the move is returned unchanged.
+    pub fn flipped_horizontal(&self, cols: usize) -> Self {
+        if cols == 0 {
+            return *self;
+        }
+        Self {
+            from_col: cols - 1 - self.from_col,
+            to_col: cols - 1 - self.to_col,
+            ..*self
+        }
+    }
+
+    /// The same move on a board of `rows` rows mirrored top-bottom
+    /// An empty board (`rows == 0`) has nothing to mirror; the move is returned unchanged.
+    pub fn flipped_vertical(&self, rows: usize) -> Self {
+        if rows == 0 {
+            return *self;
+        }
+        Self {
+            from_row: rows - 1 - self.from_row,
+            to_row: rows - 1 - self.to_row,
+            ..*self
+        }
+    }
+
+    /// The swap that puts the two pieces back (from and to exchanged)
+    pub fn inverse_move(&self) -> Self {
+        Self {
+            from_row: self.to_row,
+            from_col: self.to_col,
+            to_row: self.from_row,
+            to_col: self.from_col,
+            ..*self
         }
     }
 }
 
 impl Ord for EliminateMove {
     fn cmp(&self, other: &Self) -> Ordering {
-        // Prefer moves that create specials, then by score, then by eliminates
+        // Prefer moves that create specials, then by score, then by eliminates.
+        // Remaining ties go to the more valuable special, then to the move
+        // closest to the top-left (from_row, from_col, to_row, to_col), so
+        // `max()` picks the same move regardless of generation order.
         self.creates_special.cmp(&other.creates_special)
             .then_with(|| self.score.cmp(&other.score))
             .then_with(|| self.eliminates.cmp(&other.eliminates))
+            .then_with(|| self.special.cmp(&other.special))
+            .then_with(|| {
+                (other.from_row, other.from_col, other.to_row, other.to_col)
+                    .cmp(&(self.from_row, self.from_col, self.to_row, self.to_col))
+            })
     }
 }
 
@@ -53,28 +181,97 @@ impl PartialOrd for EliminateMove {
     }
 }
 
+/// Cells a move changed, as `(row, col, value before the change)` in the order
+/// they were written; a cell can appear more than once
+type BoardDelta = Vec<(usize, usize, u8)>;
+
+/// Current board plus the moves that led to it, so a lookahead line can be undone
+/// move by move. Each move keeps only the cells it wrote (the swap, the cleared
+/// pieces and the ones that fell), not whole boards.
+#[derive(Debug, Clone)]
+pub struct BoardHistory {
+    current: Vec<Vec<u8>>,
+    previous: Vec<(BoardDelta, EliminateMove)>,
+}
+
+impl BoardHistory {
+    pub fn new(board: Vec<Vec<u8>>) -> Self {
+        Self { current: board, previous: Vec::new() }
+    }
+
+    /// Play `move_made` on the current board, as `EliminateEngine::simulate_move` would
+    pub fn push(&mut self, move_made: EliminateMove) {
+        let mut delta = BoardDelta::new();
+        EliminateEngine::play_move_logged(&mut self.current, &move_made, &mut |row, col, before| {
+            delta.push((row, col, before))
+        });
+        self.previous.push((delta, move_made));
+    }
+
+    /// Take back the last move, touching only the cells it wrote
+    /// Returns the restored board and the move that was undone.
+    pub fn undo(&mut self) -> Option<(&[Vec<u8>], EliminateMove)> {
+        let (delta, move_made) = self.previous.pop()?;
+        for &(row, col, value) in delta.iter().rev() {
+            self.current[row][col] = value;
+        }
+        Some((&self.current, move_made))
+    }
+
+    pub fn current(&self) -> &[Vec<u8>] {
+        &self.current
+    }
+
+    /// Moves that can be undone
+    pub fn len(&self) -> usize {
+        self.previous.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.previous.is_empty()
+    }
+}
+
 /// Eliminate game strategy engine
+/// Boards are indexed `[row][col]`. Rows may differ in length (irregular levels);
+/// cells past the end of a short row are holes: nothing is swapped into them,
+/// pieces don't fall through them and runs don't match across them.
 pub struct EliminateEngine;
 
 impl EliminateEngine {
     /// Find all valid moves on the board
     pub fn find_all_moves(board: &[Vec<u8>]) -> Vec<EliminateMove> {
-        let rows = board.len();
-        if rows == 0 {
-            return Vec::new();
-        }
-        let cols = board[0].len();
+        Self::find_all_moves_with_rules(board, &EliminateRules::default())
+    }
+
+    /// Find all valid moves for a game with non-standard matching rules
+    pub fn find_all_moves_with_rules(board: &[Vec<u8>], rules: &EliminateRules) -> Vec<EliminateMove> {
+        Self::find_all_moves_with_priority(board, &[], rules, &AtomicBool::new(false)).unwrap_or_default()
+    }
 
+    /// Find all valid moves, weighting eliminated pieces by `priority_colors`
+    /// (color_id, score_multiplier); unlisted colors use 1.0.
+    /// `cancel` is checked once per row; None if it was set.
+    fn find_all_moves_with_priority(
+        board: &[Vec<u8>],
+        priority_colors: &[(u8, f32)],
+        rules: &EliminateRules,
+        cancel: &AtomicBool,
+    ) -> Option<Vec<EliminateMove>> {
+        let rows = board.len();
         let mut moves = Vec::new();
 
         // Check horizontal swaps
         for row in 0..rows {
-            for col in 0..cols - 1 {
+            if cancel.load(AtomicOrdering::Relaxed) {
+                return None;
+            }
+            for col in 0..board[row].len().saturating_sub(1) {
                 if board[row][col] != board[row][col + 1] && board[row][col] != 0 && board[row][col + 1] != 0 {
                     let mut test_board = board.to_vec();
                     test_board[row].swap(col, col + 1);
                     
-                    if let Some(mut mv) = Self::evaluate_move(&test_board, row, col, row, col + 1) {
+                    if let Some(mut mv) = Self::evaluate_move(&test_board, row, col, row, col + 1, priority_colors, rules) {
                         mv.from_row = row;
                         mv.from_col = col;
                         mv.to_row = row;
@@ -86,15 +283,18 @@ impl EliminateEngine {
         }
 
         // Check vertical swaps
-        for row in 0..rows - 1 {
-            for col in 0..cols {
+        for row in 0..rows.saturating_sub(1) {
+            if cancel.load(AtomicOrdering::Relaxed) {
+                return None;
+            }
+            for col in 0..board[row].len().min(board[row + 1].len()) {
                 if board[row][col] != board[row + 1][col] && board[row][col] != 0 && board[row + 1][col] != 0 {
                     let mut test_board = board.to_vec();
                     let temp = test_board[row][col];
                     test_board[row][col] = test_board[row + 1][col];
                     test_board[row + 1][col] = temp;
                     
-                    if let Some(mut mv) = Self::evaluate_move(&test_board, row, col, row + 1, col) {
+                    if let Some(mut mv) = Self::evaluate_move(&test_board, row, col, row + 1, col, priority_colors, rules) {
                         mv.from_row = row;
                         mv.from_col = col;
                         mv.to_row = row + 1;
@@ -105,16 +305,27 @@ impl EliminateEngine {
             }
         }
 
-        moves
+        Some(moves)
     }
 
     /// Evaluate a move and return its score
-    fn evaluate_move(board: &[Vec<u8>], r1: usize, c1: usize, r2: usize, c2: usize) -> Option<EliminateMove> {
-        let rows = board.len();
-        let cols = board[0].len();
+    /// The match at each swapped cell is the union of its horizontal and
+    /// vertical runs (runs shorter than `min_match` don't count), with the pivot counted once.
+    /// Each eliminated piece contributes its color's priority multiplier to the score.
+    fn evaluate_move(
+        board: &[Vec<u8>],
+        r1: usize,
+        c1: usize,
+        r2: usize,
+        c2: usize,
+        priority_colors: &[(u8, f32)],
+        rules: &EliminateRules,
+    ) -> Option<EliminateMove> {
+        let min_match = rules.min_match;
         
         let mut total_eliminates = 0;
-        let mut creates_special = false;
+        let mut weighted_eliminates = 0.0f32;
+        let mut special: Option<SpecialType> = None;
 
         // Check matches at both swap positions
         for (row, col) in [(r1, c1), (r2, c2)] {
@@ -131,7 +342,7 @@ impl EliminateEngine {
                 h_count += 1;
             }
             let mut right = col;
-            while right < cols - 1 && board[row][right + 1] == color {
+            while right + 1 < board[row].len() && board[row][right + 1] == color {
                 right += 1;
                 h_count += 1;
             }
@@ -139,45 +350,56 @@ impl EliminateEngine {
             // Check vertical match
             let mut v_count = 1;
             let mut top = row;
-            while top > 0 && board[top - 1][col] == color {
+            while top > 0 && Self::cell(board, top - 1, col) == Some(color) {
                 top -= 1;
                 v_count += 1;
             }
             let mut bottom = row;
-            while bottom < rows - 1 && board[bottom + 1][col] == color {
+            while Self::cell(board, bottom + 1, col) == Some(color) {
                 bottom += 1;
                 v_count += 1;
             }
 
-            // Calculate eliminates
-            if h_count >= 3 {
-                total_eliminates += h_count;
-                if h_count >= 4 {
-                    creates_special = true;
-                }
-            }
-            if v_count >= 3 {
-                total_eliminates += v_count;
-                if v_count >= 4 {
-                    creates_special = true;
-                }
-            }
-
-            // Cross pattern bonus
-            if h_count >= 3 && v_count >= 3 {
-                creates_special = true;
-            }
+            // Union of the qualifying runs, pivot counted once
+            let h_match = h_count >= min_match;
+            let v_match = v_count >= min_match;
+            let shape_size = match (h_match, v_match) {
+                (true, true) => h_count + v_count - 1,
+                (true, false) => h_count,
+                (false, true) => v_count,
+                (false, false) => 0,
+            };
+            total_eliminates += shape_size;
+
+            let multiplier = priority_colors.iter()
+                .find(|(c, _)| *c == color)
+                .map(|(_, m)| *m)
+                .unwrap_or(1.0);
+            weighted_eliminates += shape_size as f32 * multiplier;
+
+            let shape_special = if h_count >= min_match + 2 || v_count >= min_match + 2 {
+                Some(SpecialType::Line5)
+            } else if h_match && v_match {
+                Some(SpecialType::TShape)
+            } else if h_count > min_match || v_count > min_match {
+                Some(SpecialType::Line4)
+            } else {
+                None
+            };
+            special = special.max(shape_special);
         }
 
-        if total_eliminates >= 3 {
+        if total_eliminates > 0 {
+            let creates_special = special.is_some();
             Some(EliminateMove {
                 from_row: 0,
                 from_col: 0,
                 to_row: 0,
                 to_col: 0,
-                score: total_eliminates as i32 * 10 + if creates_special { 50 } else { 0 },
+                score: (weighted_eliminates * 10.0).round() as i32 + if creates_special { 50 } else { 0 },
                 eliminates: total_eliminates,
                 creates_special,
+                special,
             })
         } else {
             None
@@ -190,37 +412,196 @@ impl EliminateEngine {
         moves.into_iter().max()
     }
 
+    /// Find the best move when some colors matter more (level objectives)
+    /// e.g. `&[(1, 3.0)]` values red pieces three times as much as others.
+    pub fn find_best_move_with_priority(board: &[Vec<u8>], priority_colors: &[(u8, f32)]) -> Option<EliminateMove> {
+        Self::find_all_moves_with_priority(board, priority_colors, &EliminateRules::default(), &AtomicBool::new(false))
+            .unwrap_or_default()
+            .into_iter()
+            .max()
+    }
+
+    /// Find the move that clears the most pieces of `target_color`, cascades included
+    /// Ties (including boards where no move clears the color) fall back to the usual move ordering.
+    pub fn find_best_move_for_color(board: &[Vec<u8>], target_color: u8) -> Option<EliminateMove> {
+        Self::find_all_moves(board)
+            .into_iter()
+            .map(|mv| {
+                let cleared = Self::simulate_cascade(board, &mv).cleared_by_color;
+                (cleared.get(target_color as usize).copied().unwrap_or(0), mv)
+            })
+            .max()
+            .map(|(_, mv)| mv)
+    }
+
     /// Find top N best moves
     pub fn find_best_moves(board: &[Vec<u8>], n: usize) -> Vec<EliminateMove> {
-        let mut moves = Self::find_all_moves(board);
+        Self::find_best_moves_cancellable(board, n, &AtomicBool::new(false)).unwrap_or_default()
+    }
+
+    /// Find top N best moves, giving up (None) once `cancel` is set
+    pub fn find_best_moves_cancellable(board: &[Vec<u8>], n: usize, cancel: &AtomicBool) -> Option<Vec<EliminateMove>> {
+        let mut moves = Self::find_all_moves_with_priority(board, &[], &EliminateRules::default(), cancel)?;
         moves.sort_by(|a, b| b.cmp(a)); // Sort descending
         moves.truncate(n);
-        moves
+        Some(moves)
+    }
+
+    /// Delay before executing `best_move`, varied the way a human player's would be
+    /// Busy boards (many moves) and obvious specials shorten the delay; the
+    /// result gets +/-30% jitter and is clamped to `delay_range_ms`.
+    pub fn human_like_delay_ms(
+        board: &[Vec<u8>],
+        best_move: &EliminateMove,
+        base_ms: u64,
+        delay_range_ms: (u64, u64),
+        rng: &mut impl Rng,
+    ) -> u64 {
+        let move_count = Self::find_all_moves(board).len().min(10);
+        let complexity_factor = 1.0 - 0.05 * move_count as f64;
+
+        let obviousness_factor = match best_move.special {
+            Some(SpecialType::Line5) => 0.6,
+            Some(_) => 0.8,
+            None => 1.0,
+        };
+
+        let jitter = rng.gen_range(0.7..=1.3);
+        let delay = (base_ms as f64 * complexity_factor * obviousness_factor * jitter).round() as u64;
+
+        let (min_ms, max_ms) = delay_range_ms;
+        delay.clamp(min_ms.min(max_ms), max_ms.max(min_ms))
     }
 
     /// Simulate board after a move (for lookahead)
     pub fn simulate_move(board: &[Vec<u8>], mv: &EliminateMove) -> Vec<Vec<u8>> {
         let mut new_board = board.to_vec();
-        
+        Self::play_move_logged(&mut new_board, mv, &mut |_, _, _| {});
+        new_board
+    }
+
+    /// `simulate_move` in place, calling `log(row, col, before)` for every cell written
+    fn play_move_logged(board: &mut [Vec<u8>], mv: &EliminateMove, log: &mut impl FnMut(usize, usize, u8)) {
         // Swap pieces
-        let temp = new_board[mv.from_row][mv.from_col];
-        new_board[mv.from_row][mv.from_col] = new_board[mv.to_row][mv.to_col];
-        new_board[mv.to_row][mv.to_col] = temp;
+        let temp = board[mv.from_row][mv.from_col];
+        log(mv.from_row, mv.from_col, temp);
+        log(mv.to_row, mv.to_col, board[mv.to_row][mv.to_col]);
+        board[mv.from_row][mv.from_col] = board[mv.to_row][mv.to_col];
+        board[mv.to_row][mv.to_col] = temp;
 
         // Remove matches and apply gravity (simplified)
-        Self::remove_matches(&mut new_board);
-        Self::apply_gravity(&mut new_board);
+        Self::remove_matches_logged(board, EliminateRules::default().min_match, log);
+        Self::apply_gravity_logged(board, log);
+    }
 
-        new_board
+    /// Play a move and keep clearing matches and dropping pieces until the board settles
+    /// No new pieces are spawned, so emptied cells stay 0.
+    pub fn simulate_cascade(board: &[Vec<u8>], mv: &EliminateMove) -> CascadeResult {
+        Self::simulate_cascade_with_rules(board, mv, &EliminateRules::default())
+    }
+
+    /// `simulate_cascade` for a game with non-standard matching rules
+    pub fn simulate_cascade_with_rules(board: &[Vec<u8>], mv: &EliminateMove, rules: &EliminateRules) -> CascadeResult {
+        let mut result = CascadeResult {
+            board: board.to_vec(),
+            total_cleared: 0,
+            cascades: 0,
+            cleared_by_color: [0; 8],
+        };
+        let first = result.board[mv.from_row][mv.from_col];
+        result.board[mv.from_row][mv.from_col] = result.board[mv.to_row][mv.to_col];
+        result.board[mv.to_row][mv.to_col] = first;
+
+        loop {
+            let cleared = Self::remove_matches(&mut result.board, rules.min_match);
+            let count: usize = cleared.iter().sum();
+            if count == 0 {
+                break;
+            }
+
+            result.total_cleared += count;
+            result.cascades += 1;
+            for (total, n) in result.cleared_by_color.iter_mut().zip(cleared) {
+                *total += n;
+            }
+            Self::apply_gravity(&mut result.board);
+        }
+
+        result
+    }
+
+    /// The cell at `(row, col)`, or None for a hole past the end of a short row
+    fn cell(board: &[Vec<u8>], row: usize, col: usize) -> Option<u8> {
+        board.get(row)?.get(col).copied()
+    }
+
+    /// Swap rows and columns (board[r][c] -> result[c][r])
+    /// The result has one row per column of the longest row; holes past the end
+    /// of a short row become 0 in the transposed board.
+    pub fn transpose(board: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        let cols = board.iter().map(Vec::len).max().unwrap_or(0);
+        (0..cols)
+            .map(|c| (0..board.len()).map(|r| Self::cell(board, r, c).unwrap_or(0)).collect())
+            .collect()
+    }
+
+    /// Mirror left-right; undo with another flip or map moves with `EliminateMove::flipped_horizontal`
+    pub fn flip_horizontal(board: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        board.iter().map(|row| row.iter().rev().copied().collect()).collect()
+    }
+
+    /// Mirror top-bottom; map moves back with `EliminateMove::flipped_vertical`
+    pub fn flip_vertical(board: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        board.iter().rev().cloned().collect()
+    }
+
+    /// Relabel colors in order of first appearance (row-major) as 1, 2, 3...
+    /// Boards that differ only by a color permutation get the same canonical
+    /// form, for transposition-table dedup. Returns the board and
+    /// `mapping[original] = canonical`; empty (0) stays 0, as do ids not on the board.
+    pub fn canonicalize(board: &[Vec<u8>]) -> (Vec<Vec<u8>>, Vec<u8>) {
+        let max_color = board.iter().flatten().copied().max().unwrap_or(0);
+        let mut mapping = vec![0u8; max_color as usize + 1];
+        // At most 255 non-zero colors, so labels fit in u8; the counter itself
+        // reaches 256 after the last one.
+        let mut next: u16 = 1;
+
+        let canonical = board
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&color| {
+                        if color != 0 && mapping[color as usize] == 0 {
+                            mapping[color as usize] = next as u8;
+                            next += 1;
+                        }
+                        mapping[color as usize]
+                    })
+                    .collect()
+            })
+            .collect();
+
+        (canonical, mapping)
+    }
+
+    /// Clear every run of `min_match`+ and return how many pieces of each color went
+    fn remove_matches(board: &mut [Vec<u8>], min_match: usize) -> [usize; 8] {
+        Self::remove_matches_logged(board, min_match, &mut |_, _, _| {})
     }
 
-    fn remove_matches(board: &mut [Vec<u8>]) {
+    /// `remove_matches`, calling `log(row, col, before)` for every cleared cell
+    fn remove_matches_logged(
+        board: &mut [Vec<u8>],
+        min_match: usize,
+        log: &mut impl FnMut(usize, usize, u8),
+    ) -> [usize; 8] {
         let rows = board.len();
-        let cols = board[0].len();
-        let mut to_remove = vec![vec![false; cols]; rows];
+        let cols = board.iter().map(Vec::len).max().unwrap_or(0);
+        let mut to_remove: Vec<Vec<bool>> = board.iter().map(|row| vec![false; row.len()]).collect();
 
         // Find horizontal matches
         for row in 0..rows {
+            let cols = board[row].len();
             let mut start = 0;
             while start < cols {
                 let color = board[row][start];
@@ -234,10 +615,8 @@ impl EliminateEngine {
                     end += 1;
                 }
 
-                if end - start >= 3 {
-                    for col in start..end {
-                        to_remove[row][col] = true;
-                    }
+                if end - start >= min_match {
+                    to_remove[row][start..end].fill(true);
                 }
                 start = end;
             }
@@ -247,20 +626,22 @@ impl EliminateEngine {
         for col in 0..cols {
             let mut start = 0;
             while start < rows {
-                let color = board[start][col];
-                if color == 0 {
-                    start += 1;
-                    continue;
-                }
+                let color = match Self::cell(board, start, col) {
+                    Some(color) if color != 0 => color,
+                    _ => {
+                        start += 1;
+                        continue;
+                    }
+                };
 
                 let mut end = start;
-                while end < rows && board[end][col] == color {
+                while Self::cell(board, end, col) == Some(color) {
                     end += 1;
                 }
 
-                if end - start >= 3 {
-                    for row in start..end {
-                        to_remove[row][col] = true;
+                if end - start >= min_match {
+                    for marks in &mut to_remove[start..end] {
+                        marks[col] = true;
                     }
                 }
                 start = end;
@@ -268,25 +649,42 @@ impl EliminateEngine {
         }
 
         // Remove marked pieces
-        for row in 0..rows {
-            for col in 0..cols {
-                if to_remove[row][col] {
-                    board[row][col] = 0;
+        let mut cleared = [0; 8];
+        for (row, (board_row, marks)) in board.iter_mut().zip(&to_remove).enumerate() {
+            for (col, (cell, &remove)) in board_row.iter_mut().zip(marks).enumerate() {
+                if remove {
+                    if let Some(count) = cleared.get_mut(*cell as usize) {
+                        *count += 1;
+                    }
+                    log(row, col, *cell);
+                    *cell = 0;
                 }
             }
         }
+        cleared
     }
 
     fn apply_gravity(board: &mut [Vec<u8>]) {
+        Self::apply_gravity_logged(board, &mut |_, _, _| {});
+    }
+
+    /// `apply_gravity`, calling `log(row, col, before)` for every cell written
+    #[allow(clippy::needless_range_loop)] // column-major walk over a row-major board
+    fn apply_gravity_logged(board: &mut [Vec<u8>], log: &mut impl FnMut(usize, usize, u8)) {
         let rows = board.len();
-        let cols = board[0].len();
+        let cols = board.iter().map(Vec::len).max().unwrap_or(0);
 
         for col in 0..cols {
             let mut write_row = rows;
             for read_row in (0..rows).rev() {
-                if board[read_row][col] != 0 {
+                if col >= board[read_row].len() {
+                    // Hole: pieces above settle on top of it
+                    write_row = read_row;
+                } else if board[read_row][col] != 0 {
                     write_row -= 1;
                     if write_row != read_row {
+                        log(write_row, col, board[write_row][col]);
+                        log(read_row, col, board[read_row][col]);
                         board[write_row][col] = board[read_row][col];
                         board[read_row][col] = 0;
                     }
@@ -319,6 +717,45 @@ impl GridPos {
         let dy = self.y - other.y;
         dx * dx + dy * dy
     }
+
+    /// Moves needed when diagonal steps cost the same as straight ones
+    #[inline]
+    pub fn chebyshev_distance(&self, other: &GridPos) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    /// 8-directional path cost with straight steps 10 and diagonal steps 14
+    #[inline]
+    pub fn octile_distance(&self, other: &GridPos) -> i32 {
+        let dx = (self.x - other.x).abs();
+        let dy = (self.y - other.y).abs();
+        10 * dx.max(dy) + 4 * dx.min(dy)
+    }
+
+    /// Cells exactly `radius` Manhattan steps away (a diamond outline)
+    /// Empty for a negative radius.
+    pub fn ring(&self, radius: i32) -> Vec<GridPos> {
+        if radius < 0 {
+            return Vec::new();
+        }
+        if radius == 0 {
+            return vec![*self];
+        }
+
+        let mut cells = Vec::with_capacity(4 * radius as usize);
+        for i in 0..radius {
+            cells.push(GridPos::new(self.x + i, self.y - radius + i));
+            cells.push(GridPos::new(self.x + radius - i, self.y + i));
+            cells.push(GridPos::new(self.x - i, self.y + radius - i));
+            cells.push(GridPos::new(self.x - radius + i, self.y - i));
+        }
+        cells
+    }
+
+    /// All cells within `radius` Manhattan steps, nearest rings first
+    pub fn disk(&self, radius: i32) -> Vec<GridPos> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
 }
 
 /// A* pathfinding result
@@ -327,6 +764,25 @@ pub struct PathResult {
     pub path: Vec<GridPos>,
     pub total_cost: i32,
     pub found: bool,
+    /// Nodes popped from the open set, for comparing search strategies
+    #[serde(default)]
+    pub nodes_expanded: usize,
+}
+
+/// Search options for `PathfindingEngine::find_path_with_config`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathConfig {
+    /// Multiplier on the heuristic in the f-score (weighted A*)
+    /// 1.0 is plain A* and finds optimal paths; larger values search greedier,
+    /// expanding fewer nodes for paths at most `heuristic_weight` times the optimum.
+    /// As the weight grows the search approaches greedy best-first. Below 1.0 counts as 1.0.
+    pub heuristic_weight: f32,
+}
+
+impl Default for PathConfig {
+    fn default() -> Self {
+        Self { heuristic_weight: 1.0 }
+    }
 }
 
 /// Pathfinding engine using A* algorithm
@@ -342,12 +798,102 @@ impl PathfindingEngine {
         obstacles: &FxHashSet<GridPos>,
         grid_width: i32,
         grid_height: i32,
+    ) -> PathResult {
+        Self::find_path_with_config(start, goal, obstacles, grid_width, grid_height, &PathConfig::default())
+    }
+
+    /// `find_path` with search options, e.g. a heuristic weight trading path length for speed
+    pub fn find_path_with_config(
+        start: GridPos,
+        goal: GridPos,
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+        config: &PathConfig,
+    ) -> PathResult {
+        let weight = config.heuristic_weight.max(1.0);
+        Self::find_path_weighted(start, goal, obstacles, grid_width, grid_height, weight, |_| 1)
+    }
+
+    /// `find_path` biased toward open space, away from walls and the grid edge
+    /// Entering a cell costs `1 + wall_penalty * (PREFERRED_CLEARANCE - clearance)`
+    /// when its `obstacle_distance_field` value is below `PREFERRED_CLEARANCE`.
+    pub fn find_path_with_clearance(
+        start: GridPos,
+        goal: GridPos,
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+        wall_penalty: i32,
+    ) -> PathResult {
+        let clearance = Self::obstacle_distance_field(obstacles, grid_width, grid_height);
+        Self::find_path_weighted(start, goal, obstacles, grid_width, grid_height, 1.0, |pos| {
+            let free = clearance[pos.y as usize][pos.x as usize];
+            1 + wall_penalty.max(0) * (PREFERRED_CLEARANCE - free).max(0)
+        })
+    }
+
+    /// Each cell's 4-directional distance to the nearest obstacle, indexed `[y][x]`
+    /// Obstacle cells are 0; the area outside the grid counts as wall, so edge cells are at most 1.
+    pub fn obstacle_distance_field(
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+    ) -> Vec<Vec<i32>> {
+        let sources: Vec<GridPos> = obstacles.iter().copied().collect();
+        let dist = CombatEngine::bfs_distance_field(&sources, grid_width, grid_height);
+
+        (0..grid_height.max(0))
+            .map(|y| {
+                (0..grid_width.max(0))
+                    .map(|x| {
+                        let to_edge = (x + 1).min(y + 1).min(grid_width - x).min(grid_height - y);
+                        dist[(y * grid_width + x) as usize].min(to_edge)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Grow every obstacle into a `2 * inflation_radius + 1` square (Minkowski sum)
+    /// Paths planned around the result keep `inflation_radius` cells from walls, so
+    /// one-cell gaps the character can't fit through are closed. Clipped to the grid.
+    pub fn inflate_obstacles(
+        obstacles: &FxHashSet<GridPos>,
+        inflation_radius: i32,
+        grid_width: i32,
+        grid_height: i32,
+    ) -> FxHashSet<GridPos> {
+        let r = inflation_radius.max(0);
+        let mut inflated = FxHashSet::default();
+
+        for obstacle in obstacles {
+            for y in (obstacle.y - r).max(0)..=(obstacle.y + r).min(grid_height - 1) {
+                for x in (obstacle.x - r).max(0)..=(obstacle.x + r).min(grid_width - 1) {
+                    inflated.insert(GridPos::new(x, y));
+                }
+            }
+        }
+        inflated
+    }
+
+    /// 4-directional A* where entering a cell costs `step_cost(cell)` (at least 1)
+    /// and the heuristic is scaled by `heuristic_weight` (see `PathConfig`)
+    fn find_path_weighted(
+        start: GridPos,
+        goal: GridPos,
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+        heuristic_weight: f32,
+        step_cost: impl Fn(GridPos) -> i32,
     ) -> PathResult {
         if start == goal {
             return PathResult {
                 path: vec![start],
                 total_cost: 0,
                 found: true,
+                nodes_expanded: 0,
             };
         }
 
@@ -357,6 +903,7 @@ impl PathfindingEngine {
                 path: Vec::new(),
                 total_cost: -1,
                 found: false,
+                nodes_expanded: 0,
             };
         }
 
@@ -364,7 +911,7 @@ impl PathfindingEngine {
         let mut came_from: FxHashMap<GridPos, GridPos> = FxHashMap::default();
         let mut g_score: FxHashMap<GridPos, i32> = FxHashMap::default();
 
-        let h = |pos: &GridPos| pos.manhattan_distance(&goal);
+        let h = |pos: &GridPos| (pos.manhattan_distance(&goal) as f32 * heuristic_weight).round() as i32;
 
         g_score.insert(start, 0);
         open_set.push(start, Reverse(h(&start)));
@@ -372,7 +919,9 @@ impl PathfindingEngine {
         // 4-directional movement
         let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)];
 
+        let mut nodes_expanded = 0;
         while let Some((current, _)) = open_set.pop() {
+            nodes_expanded += 1;
             if current == goal {
                 // Reconstruct path
                 let mut path = vec![current];
@@ -387,6 +936,7 @@ impl PathfindingEngine {
                     total_cost: *g_score.get(&current).unwrap_or(&0),
                     path,
                     found: true,
+                    nodes_expanded,
                 };
             }
 
@@ -405,7 +955,7 @@ impl PathfindingEngine {
                     continue;
                 }
 
-                let tentative_g = current_g + 1; // Uniform cost
+                let tentative_g = current_g + step_cost(neighbor).max(1);
 
                 if tentative_g < *g_score.get(&neighbor).unwrap_or(&i32::MAX) {
                     came_from.insert(neighbor, current);
@@ -420,6 +970,7 @@ impl PathfindingEngine {
             path: Vec::new(),
             total_cost: -1,
             found: false,
+            nodes_expanded,
         }
     }
 
@@ -436,6 +987,7 @@ impl PathfindingEngine {
                 path: vec![start],
                 total_cost: 0,
                 found: true,
+                nodes_expanded: 0,
             };
         }
 
@@ -444,6 +996,7 @@ impl PathfindingEngine {
                 path: Vec::new(),
                 total_cost: -1,
                 found: false,
+                nodes_expanded: 0,
             };
         }
 
@@ -451,12 +1004,8 @@ impl PathfindingEngine {
         let mut came_from: FxHashMap<GridPos, GridPos> = FxHashMap::default();
         let mut g_score: FxHashMap<GridPos, i32> = FxHashMap::default();
 
-        // Use Chebyshev distance for 8-dir
-        let h = |pos: &GridPos| {
-            let dx = (pos.x - goal.x).abs();
-            let dy = (pos.y - goal.y).abs();
-            dx.max(dy)
-        };
+        // Octile distance matches the 10/14 step costs below
+        let h = |pos: &GridPos| pos.octile_distance(&goal);
 
         g_score.insert(start, 0);
         open_set.push(start, Reverse(h(&start)));
@@ -473,7 +1022,9 @@ impl PathfindingEngine {
             (-1, -1, 14),
         ];
 
+        let mut nodes_expanded = 0;
         while let Some((current, _)) = open_set.pop() {
+            nodes_expanded += 1;
             if current == goal {
                 let mut path = vec![current];
                 let mut node = current;
@@ -487,6 +1038,7 @@ impl PathfindingEngine {
                     total_cost: *g_score.get(&current).unwrap_or(&0),
                     path,
                     found: true,
+                    nodes_expanded,
                 };
             }
 
@@ -517,7 +1069,7 @@ impl PathfindingEngine {
                 if tentative_g < *g_score.get(&neighbor).unwrap_or(&i32::MAX) {
                     came_from.insert(neighbor, current);
                     g_score.insert(neighbor, tentative_g);
-                    let f_score = tentative_g + h(&neighbor) * 10;
+                    let f_score = tentative_g + h(&neighbor);
                     open_set.push(neighbor, Reverse(f_score));
                 }
             }
@@ -527,103 +1079,533 @@ impl PathfindingEngine {
             path: Vec::new(),
             total_cost: -1,
             found: false,
+            nodes_expanded,
         }
     }
 
-    /// Find nearest safe position (away from enemies)
-    pub fn find_safe_position(
-        current: GridPos,
-        enemies: &[GridPos],
+    /// All cells reachable from `start` in at most `steps` 4-directional moves
+    pub fn reachable_in_steps(
+        start: GridPos,
+        steps: usize,
         obstacles: &FxHashSet<GridPos>,
         grid_width: i32,
         grid_height: i32,
-        min_distance: i32,
-    ) -> Option<GridPos> {
-        // BFS to find nearest position that's far enough from all enemies
-        let mut visited: FxHashSet<GridPos> = FxHashSet::default();
-        let mut queue = vec![current];
-        visited.insert(current);
+    ) -> FxHashSet<GridPos> {
+        let mut reachable: FxHashSet<GridPos> = FxHashSet::default();
+        if start.x < 0 || start.x >= grid_width || start.y < 0 || start.y >= grid_height {
+            return reachable;
+        }
 
+        reachable.insert(start);
+        let mut frontier = vec![start];
         let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)];
 
-        while !queue.is_empty() {
-            let mut next_queue = Vec::new();
-
-            for pos in queue {
-                // Check if this position is safe (far from all enemies)
-                let is_safe = enemies.iter().all(|enemy| {
-                    pos.manhattan_distance(enemy) >= min_distance
-                });
-
-                if is_safe && !obstacles.contains(&pos) {
-                    return Some(pos);
-                }
+        for _ in 0..steps {
+            let mut next_frontier = Vec::new();
 
-                // Add neighbors
+            for pos in frontier {
                 for (dx, dy) in directions.iter() {
                     let neighbor = GridPos::new(pos.x + dx, pos.y + dy);
 
-                    if neighbor.x < 0 || neighbor.x >= grid_width 
+                    if neighbor.x < 0 || neighbor.x >= grid_width
                         || neighbor.y < 0 || neighbor.y >= grid_height {
                         continue;
                     }
 
-                    if obstacles.contains(&neighbor) || visited.contains(&neighbor) {
+                    if obstacles.contains(&neighbor) || !reachable.insert(neighbor) {
                         continue;
                     }
 
-                    visited.insert(neighbor);
-                    next_queue.push(neighbor);
+                    next_frontier.push(neighbor);
                 }
             }
 
-            queue = next_queue;
+            if next_frontier.is_empty() {
+                break;
+            }
+            frontier = next_frontier;
         }
 
-        None
+        reachable
     }
-}
 
-/// Combat decision for MOBA games
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CombatDecision {
-    pub action: CombatAction,
-    pub target_pos: Option<GridPos>,
-    pub priority: i32,
-    pub reason: String,
-}
+    /// Whether we can catch a target heading for `target_goal` before it arrives
+    /// Speeds are cells per turn. The target follows its shortest path; it is
+    /// intercepted if, on some turn, a cell it passes is in our reachable set
+    /// for that many turns. A target with no path (or no speed) stays put.
+    #[allow(clippy::too_many_arguments)]
+    pub fn intercept_possible(
+        self_pos: GridPos,
+        self_speed: i32,
+        target_pos: GridPos,
+        target_speed: i32,
+        target_goal: GridPos,
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+    ) -> bool {
+        if self_pos == target_pos {
+            return true;
+        }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum CombatAction {
-    Attack,
-    UseSkill,
-    Retreat,
-    MoveToPosition,
-    Wait,
-}
+        let self_speed = self_speed.max(0) as usize;
+        let route = Self::find_path(target_pos, target_goal, obstacles, grid_width, grid_height);
 
-/// Combat strategy engine for MOBA games
-pub struct CombatEngine;
+        if target_speed <= 0 || !route.found {
+            let everywhere = (grid_width.max(0) * grid_height.max(0)) as usize;
+            return Self::reachable_in_steps(self_pos, everywhere, obstacles, grid_width, grid_height)
+                .contains(&target_pos);
+        }
 
-impl CombatEngine {
-    /// Analyze combat situation and generate decisions
-    pub fn analyze_combat(
-        self_pos: GridPos,
-        self_hp_percent: f32,
-        enemies: &[(GridPos, f32)], // (position, hp_percent)
-        allies: &[GridPos],
-        skill_ready: &[bool],
-        in_tower_range: bool,
-    ) -> Vec<CombatDecision> {
-        let mut decisions = Vec::new();
+        let target_speed = target_speed as usize;
+        let last = route.path.len() - 1;
+        let turns = last.div_ceil(target_speed);
 
-        // 1. Survival priority - retreat if low HP
-        if self_hp_percent < 0.2 {
-            decisions.push(CombatDecision {
-                action: CombatAction::Retreat,
-                target_pos: None,
-                priority: 100,
-                reason: "HP critical, must retreat".to_string(),
+        for turn in 1..=turns {
+            let ours = Self::reachable_in_steps(self_pos, self_speed * turn, obstacles, grid_width, grid_height);
+            let from = (turn - 1) * target_speed + 1;
+            let to = (turn * target_speed).min(last);
+            if route.path[from..=to].iter().any(|cell| ours.contains(cell)) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Find nearest safe position (away from enemies)
+    pub fn find_safe_position(
+        current: GridPos,
+        enemies: &[GridPos],
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+        min_distance: i32,
+    ) -> Option<GridPos> {
+        // Safe = far enough from all enemies
+        Self::nearest_cell_where(current, obstacles, grid_width, grid_height, |pos| {
+            enemies.iter().all(|enemy| pos.manhattan_distance(enemy) >= min_distance)
+        })
+    }
+
+    /// Find nearest position whose weighted threat is below `max_threat`
+    /// Threat is the sum of `weight / manhattan_distance` over `enemies`
+    /// (position, threat weight); standing on an enemy is never safe.
+    pub fn find_safe_position_weighted(
+        current: GridPos,
+        enemies: &[(GridPos, f32)],
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+        max_threat: f32,
+    ) -> Option<GridPos> {
+        Self::nearest_cell_where(current, obstacles, grid_width, grid_height, |pos| {
+            let threat: f32 = enemies.iter()
+                .map(|(enemy, weight)| match pos.manhattan_distance(enemy) {
+                    0 => f32::INFINITY,
+                    d => weight / d as f32,
+                })
+                .sum();
+            threat < max_threat
+        })
+    }
+
+    /// Narrow passages: walkable strips at most `max_width` cells across whose
+    /// two long sides are obstacles (the map edge doesn't count as a wall)
+    /// A horizontal corridor is built from consecutive columns whose walkable
+    /// cross-section is the same; vertical corridors likewise from rows.
+    /// Corridors must be at least 2 cells long. Rects are in grid cells.
+    pub fn find_corridors(
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+        max_width: usize,
+    ) -> Vec<Rect> {
+        let mut corridors = Self::corridor_strips(obstacles, grid_width, grid_height, max_width, false);
+        corridors.extend(Self::corridor_strips(obstacles, grid_width, grid_height, max_width, true));
+        corridors
+    }
+
+    /// Horizontal corridors, or vertical ones with `vertical` (axes swapped)
+    fn corridor_strips(
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+        max_width: usize,
+        vertical: bool,
+    ) -> Vec<Rect> {
+        // `along` runs the corridor's length, `across` its width
+        let (along_len, across_len) = if vertical { (grid_height, grid_width) } else { (grid_width, grid_height) };
+        let blocked = |along: i32, across: i32| {
+            let pos = if vertical { GridPos::new(across, along) } else { GridPos::new(along, across) };
+            obstacles.contains(&pos)
+        };
+
+        // Cross-sections: walkable runs walled on both ends, keyed by (start, width)
+        let sections = |along: i32| -> Vec<(i32, i32)> {
+            let mut found = Vec::new();
+            let mut across = 1;
+            while across < across_len {
+                if !blocked(along, across - 1) || blocked(along, across) {
+                    across += 1;
+                    continue;
+                }
+                let start = across;
+                while across < across_len && !blocked(along, across) {
+                    across += 1;
+                }
+                let width = across - start;
+                if across < across_len && width as usize <= max_width {
+                    found.push((start, width));
+                }
+            }
+            found
+        };
+
+        let mut corridors = Vec::new();
+        // Open strips: (start, width) -> first `along` index
+        let mut open: FxHashMap<(i32, i32), i32> = FxHashMap::default();
+        for along in 0..=along_len {
+            let current: FxHashSet<(i32, i32)> = if along < along_len {
+                sections(along).into_iter().collect()
+            } else {
+                FxHashSet::default()
+            };
+
+            let mut closed: Vec<((i32, i32), i32)> = open.iter()
+                .filter(|(section, _)| !current.contains(section))
+                .map(|(&section, &first)| (section, first))
+                .collect();
+            closed.sort();
+            for ((start, width), first) in closed {
+                open.remove(&(start, width));
+                let length = along - first;
+                if length >= 2 {
+                    corridors.push(if vertical {
+                        Rect::new(start, first, width, length)
+                    } else {
+                        Rect::new(first, start, length, width)
+                    });
+                }
+            }
+
+            for section in current {
+                open.entry(section).or_insert(along);
+            }
+        }
+
+        corridors
+    }
+
+    /// BFS from `current` to the nearest open cell satisfying `is_safe`
+    fn nearest_cell_where(
+        current: GridPos,
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+        is_safe: impl Fn(&GridPos) -> bool,
+    ) -> Option<GridPos> {
+        let mut visited: FxHashSet<GridPos> = FxHashSet::default();
+        let mut queue = vec![current];
+        visited.insert(current);
+
+        let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+
+        while !queue.is_empty() {
+            let mut next_queue = Vec::new();
+
+            for pos in queue {
+                if is_safe(&pos) && !obstacles.contains(&pos) {
+                    return Some(pos);
+                }
+
+                // Add neighbors
+                for (dx, dy) in directions.iter() {
+                    let neighbor = GridPos::new(pos.x + dx, pos.y + dy);
+
+                    if neighbor.x < 0 || neighbor.x >= grid_width 
+                        || neighbor.y < 0 || neighbor.y >= grid_height {
+                        continue;
+                    }
+
+                    if obstacles.contains(&neighbor) || visited.contains(&neighbor) {
+                        continue;
+                    }
+
+                    visited.insert(neighbor);
+                    next_queue.push(neighbor);
+                }
+            }
+
+            queue = next_queue;
+        }
+
+        None
+    }
+}
+
+/// Combat decision for MOBA games
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombatDecision {
+    pub action: CombatAction,
+    pub target_pos: Option<GridPos>,
+    pub priority: i32,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CombatAction {
+    Attack,
+    UseSkill,
+    Retreat,
+    MoveToPosition,
+    Wait,
+    /// Stun an enemy before their cast completes
+    InterruptCast,
+}
+
+/// Team of a unit relative to the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Team {
+    Ally,
+    Enemy,
+    Neutral,
+}
+
+/// Unit in 3D world coordinates (y is up, as in Unity)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Target3D {
+    pub pos: (f32, f32, f32),
+    pub hp_percent: f32,
+    pub is_visible: bool,
+    pub team: Team,
+}
+
+/// Attackable target with its ranking score (higher is better)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RankedTarget {
+    /// Index into the `targets` slice
+    pub index: usize,
+    pub distance: f32,
+    pub score: f32,
+}
+
+/// Enemy cast in progress, as seen by `CombatEngine::plan_interrupt`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnemyCast {
+    pub caster_pos: GridPos,
+    pub cast_name: String,
+    pub cast_remaining_ms: f32,
+}
+
+/// Stun skill available for interrupts, see `CombatEngine::analyze_combat_with_casts`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StunSkill {
+    /// Index into `skill_ready`
+    pub skill_index: usize,
+    /// Seconds until the stun is off cooldown
+    pub cooldown_remaining: f32,
+    pub range: i32,
+    /// Delay from cast to impact
+    pub travel_time_ms: f32,
+}
+
+/// Skill description used by the combo planner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillInfo {
+    pub range: i32,
+    pub cooldown_ready: bool,
+    /// Higher casts earlier in the combo (e.g. stun > burst > execute)
+    pub combo_priority: i32,
+    pub damage: f32,
+}
+
+/// Combat strategy engine for MOBA games
+pub struct CombatEngine;
+
+impl CombatEngine {
+    /// Per-cell threat, indexed `[y][x]`
+    /// Each enemy adds `1 / (1 + manhattan_distance)`; corridor cells (see
+    /// `PathfindingEngine::find_corridors`) add `CORRIDOR_THREAT` as ambush points.
+    /// Obstacle cells are 0.
+    pub fn build_threat_heatmap(
+        enemies: &[GridPos],
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+    ) -> Vec<Vec<f32>> {
+        let mut heatmap = vec![vec![0.0f32; grid_width.max(0) as usize]; grid_height.max(0) as usize];
+
+        for (y, row) in heatmap.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                let pos = GridPos::new(x as i32, y as i32);
+                *cell = enemies.iter()
+                    .map(|enemy| 1.0 / (1 + pos.manhattan_distance(enemy)) as f32)
+                    .sum();
+            }
+        }
+
+        for corridor in PathfindingEngine::find_corridors(obstacles, grid_width, grid_height, CORRIDOR_MAX_WIDTH) {
+            for y in corridor.y..corridor.y + corridor.height {
+                for x in corridor.x..corridor.x + corridor.width {
+                    heatmap[y as usize][x as usize] += CORRIDOR_THREAT;
+                }
+            }
+        }
+
+        for obstacle in obstacles {
+            if let Some(cell) = heatmap.get_mut(obstacle.y as usize).and_then(|row| row.get_mut(obstacle.x as usize)) {
+                *cell = 0.0;
+            }
+        }
+
+        heatmap
+    }
+
+    /// Team-fight quality of standing at `self_pos`, in [-1.0, 1.0]
+    /// Sum of `-0.3 * goal_distance / max_dist`, `0.4 * enemy_distance / SAFE_RANGE`
+    /// and `0.3 * allies_within_5 / allies.len()`, each ratio capped at 1. The enemy
+    /// distance is read off `build_threat_heatmap` (1/threat - 1), so corridors
+    /// count as closer to danger. Obstacles and out-of-bounds cells score -1.
+    pub fn position_score(
+        self_pos: GridPos,
+        goal: GridPos,
+        enemies: &[GridPos],
+        allies: &[GridPos],
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+    ) -> f32 {
+        let heatmap = Self::build_threat_heatmap(enemies, obstacles, grid_width, grid_height);
+        Self::score_on_heatmap(self_pos, goal, allies, &heatmap, obstacles)
+    }
+
+    /// Highest-scoring 4-adjacent walkable cell by `position_score`; ties go to
+    /// the first of up, right, down, left
+    pub fn best_adjacent_position(
+        self_pos: GridPos,
+        goal: GridPos,
+        enemies: &[GridPos],
+        allies: &[GridPos],
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+    ) -> Option<GridPos> {
+        let heatmap = Self::build_threat_heatmap(enemies, obstacles, grid_width, grid_height);
+        self_pos.ring(1)
+            .into_iter()
+            .filter(|p| p.x >= 0 && p.y >= 0 && p.x < grid_width && p.y < grid_height && !obstacles.contains(p))
+            .map(|p| (p, Self::score_on_heatmap(p, goal, allies, &heatmap, obstacles)))
+            .fold(None, |best: Option<(GridPos, f32)>, (p, score)| match best {
+                Some((_, best_score)) if best_score >= score => best,
+                _ => Some((p, score)),
+            })
+            .map(|(p, _)| p)
+    }
+
+    fn score_on_heatmap(
+        pos: GridPos,
+        goal: GridPos,
+        allies: &[GridPos],
+        heatmap: &[Vec<f32>],
+        obstacles: &FxHashSet<GridPos>,
+    ) -> f32 {
+        let Some(&threat) = heatmap.get(pos.y.max(0) as usize)
+            .and_then(|row| row.get(pos.x.max(0) as usize))
+            .filter(|_| pos.x >= 0 && pos.y >= 0 && !obstacles.contains(&pos))
+        else {
+            return -1.0;
+        };
+
+        let max_dist = (heatmap.len() + heatmap.first().map_or(0, Vec::len)).max(1) as f32;
+        let goal_term = (pos.manhattan_distance(&goal) as f32 / max_dist).min(1.0);
+
+        let enemy_distance = if threat > 0.0 { 1.0 / threat - 1.0 } else { f32::INFINITY };
+        let enemy_term = (enemy_distance.max(0.0) / SAFE_RANGE).min(1.0);
+
+        let ally_term = if allies.is_empty() {
+            0.0
+        } else {
+            let nearby = allies.iter().filter(|a| pos.manhattan_distance(a) <= ALLY_SUPPORT_RADIUS).count();
+            nearby as f32 / allies.len() as f32
+        };
+
+        (-0.3 * goal_term + 0.4 * enemy_term + 0.3 * ally_term).clamp(-1.0, 1.0)
+    }
+
+    /// Analyze combat situation and generate decisions
+    pub fn analyze_combat(
+        self_pos: GridPos,
+        self_hp_percent: f32,
+        enemies: &[(GridPos, f32)], // (position, hp_percent)
+        allies: &[GridPos],
+        skill_ready: &[bool],
+        in_tower_range: bool,
+    ) -> Vec<CombatDecision> {
+        Self::analyze_combat_with_obstacles(
+            self_pos, self_hp_percent, enemies, allies, skill_ready, in_tower_range, None)
+    }
+
+    /// Analyze combat, attacking/casting only at enemies in line of sight
+    /// With `obstacles = None` every enemy is considered visible.
+    pub fn analyze_combat_with_obstacles(
+        self_pos: GridPos,
+        self_hp_percent: f32,
+        enemies: &[(GridPos, f32)], // (position, hp_percent)
+        allies: &[GridPos],
+        skill_ready: &[bool],
+        in_tower_range: bool,
+        obstacles: Option<&FxHashSet<GridPos>>,
+    ) -> Vec<CombatDecision> {
+        Self::analyze_combat_with_casts(
+            self_pos, self_hp_percent, enemies, allies, skill_ready, in_tower_range, obstacles, None, &[])
+    }
+
+    /// `analyze_combat_with_obstacles`, plus interrupting enemy casts
+    /// When `stun` can stop one of `casts` in time (see `plan_interrupt`), an
+    /// `InterruptCast` decision comes first, ahead of even a critical-HP retreat.
+    #[allow(clippy::too_many_arguments)]
+    pub fn analyze_combat_with_casts(
+        self_pos: GridPos,
+        self_hp_percent: f32,
+        enemies: &[(GridPos, f32)], // (position, hp_percent)
+        allies: &[GridPos],
+        skill_ready: &[bool],
+        in_tower_range: bool,
+        obstacles: Option<&FxHashSet<GridPos>>,
+        stun: Option<&StunSkill>,
+        casts: &[EnemyCast],
+    ) -> Vec<CombatDecision> {
+        let mut decisions = Vec::new();
+
+        // 0. Interrupt a dangerous cast while it can still be stopped
+        if let Some(stun) = stun {
+            let interrupt = Self::plan_interrupt(
+                self_pos,
+                stun.skill_index,
+                stun.cooldown_remaining,
+                stun.range,
+                stun.travel_time_ms,
+                casts,
+            );
+            decisions.extend(interrupt);
+        }
+
+        let visible_enemies: Vec<(GridPos, f32)> = match obstacles {
+            Some(obstacles) => enemies.iter()
+                .filter(|(pos, _)| Self::has_line_of_sight(self_pos, *pos, obstacles))
+                .copied()
+                .collect(),
+            None => enemies.to_vec(),
+        };
+
+        // 1. Survival priority - retreat if low HP
+        if self_hp_percent < 0.2 {
+            decisions.push(CombatDecision {
+                action: CombatAction::Retreat,
+                target_pos: None,
+                priority: 100,
+                reason: "HP critical, must retreat".to_string(),
             });
             return decisions;
         }
@@ -640,7 +1622,7 @@ impl CombatEngine {
         }
 
         // 3. Find killable target (low HP enemy)
-        let killable_enemies: Vec<_> = enemies.iter()
+        let killable_enemies: Vec<_> = visible_enemies.iter()
             .filter(|(pos, hp)| *hp < 0.3 && self_pos.manhattan_distance(pos) < 5)
             .collect();
 
@@ -655,8 +1637,8 @@ impl CombatEngine {
         }
 
         // 4. Use skill if available and enemies nearby
-        if skill_ready.get(0).copied().unwrap_or(false) && !enemies.is_empty() {
-            let closest_enemy = enemies.iter()
+        if skill_ready.first().copied().unwrap_or(false) && !visible_enemies.is_empty() {
+            let closest_enemy = visible_enemies.iter()
                 .min_by_key(|(pos, _)| self_pos.manhattan_distance(pos));
             
             if let Some((target, _)) = closest_enemy {
@@ -681,63 +1663,708 @@ impl CombatEngine {
             });
         }
 
-        // 6. Default: move to optimal position
-        if decisions.is_empty() {
-            decisions.push(CombatDecision {
-                action: CombatAction::Wait,
-                target_pos: None,
-                priority: 10,
-                reason: "No immediate action needed".to_string(),
-            });
+        // 6. Default: move to optimal position
+        if decisions.is_empty() {
+            decisions.push(CombatDecision {
+                action: CombatAction::Wait,
+                target_pos: None,
+                priority: 10,
+                reason: "No immediate action needed".to_string(),
+            });
+        }
+
+        // Sort by priority
+        decisions.sort_by_key(|d| Reverse(d.priority));
+        decisions
+    }
+
+    /// Plan an ordered skill combo against `target` (position, remaining HP)
+    /// Only ready skills whose range reaches the target are used, in descending
+    /// `combo_priority`; the sequence stops once predicted damage kills the target.
+    pub fn plan_combo(
+        self_pos: GridPos,
+        target: (GridPos, f32),
+        skills: &[SkillInfo],
+    ) -> Vec<CombatDecision> {
+        let (target_pos, target_hp) = target;
+        let distance = self_pos.manhattan_distance(&target_pos);
+
+        let mut usable: Vec<(usize, &SkillInfo)> = skills.iter()
+            .enumerate()
+            .filter(|(_, skill)| skill.cooldown_ready && distance <= skill.range)
+            .collect();
+        usable.sort_by_key(|(_, skill)| Reverse(skill.combo_priority));
+
+        let mut decisions = Vec::new();
+        let mut predicted_damage = 0.0;
+
+        for (step, (skill_idx, skill)) in usable.into_iter().enumerate() {
+            if predicted_damage >= target_hp {
+                break;
+            }
+
+            predicted_damage += skill.damage;
+            decisions.push(CombatDecision {
+                action: CombatAction::UseSkill,
+                target_pos: Some(target_pos),
+                priority: 70 - step as i32,
+                reason: format!("Combo step {}: skill {}", step + 1, skill_idx),
+            });
+        }
+
+        decisions
+    }
+
+    /// Whether a stun cast now (or as soon as it's off cooldown) lands before the target's cast ends
+    /// The target must be within `stun_range` (Manhattan); `stun_cooldown_remaining`
+    /// is in seconds and `stun_travel_time_ms` is the delay from cast to impact.
+    /// `stun_skill_index` only identifies the skill; the timing doesn't depend on it.
+    pub fn can_interrupt_cast(
+        _stun_skill_index: usize,
+        stun_cooldown_remaining: f32,
+        stun_range: i32,
+        stun_travel_time_ms: f32,
+        self_pos: GridPos,
+        target_pos: GridPos,
+        target_cast_remaining_ms: f32,
+    ) -> bool {
+        if self_pos.manhattan_distance(&target_pos) > stun_range {
+            return false;
+        }
+        let arrival_ms = stun_cooldown_remaining.max(0.0) * 1000.0 + stun_travel_time_ms.max(0.0);
+        arrival_ms < target_cast_remaining_ms
+    }
+
+    /// How much interrupting a cast is worth (0-100) from its name
+    /// Ultimates and heals rank highest; unlisted casts get 10.
+    pub fn interrupt_priority(target_cast_name: &str) -> u8 {
+        let name = target_cast_name.to_ascii_lowercase();
+        INTERRUPT_TABLE.iter()
+            .find(|(key, _)| name.contains(key))
+            .map_or(10, |&(_, priority)| priority)
+    }
+
+    /// Top-priority `InterruptCast` decision against the most valuable cast the stun can stop
+    /// Ties go to the cast closest to finishing. `analyze_combat_with_casts` puts it first.
+    pub fn plan_interrupt(
+        self_pos: GridPos,
+        stun_skill_index: usize,
+        stun_cooldown_remaining: f32,
+        stun_range: i32,
+        stun_travel_time_ms: f32,
+        casts: &[EnemyCast],
+    ) -> Option<CombatDecision> {
+        let cast = casts.iter()
+            .filter(|cast| Self::can_interrupt_cast(
+                stun_skill_index,
+                stun_cooldown_remaining,
+                stun_range,
+                stun_travel_time_ms,
+                self_pos,
+                cast.caster_pos,
+                cast.cast_remaining_ms,
+            ))
+            .max_by(|a, b| {
+                Self::interrupt_priority(&a.cast_name)
+                    .cmp(&Self::interrupt_priority(&b.cast_name))
+                    .then(b.cast_remaining_ms.total_cmp(&a.cast_remaining_ms))
+            })?;
+
+        Some(CombatDecision {
+            action: CombatAction::InterruptCast,
+            target_pos: Some(cast.caster_pos),
+            priority: INTERRUPT_PRIORITY,
+            reason: format!("Interrupt {} with skill {}", cast.cast_name, stun_skill_index),
+        })
+    }
+
+    /// Total damage deliverable within `burst_window_s` seconds
+    /// Skills are cast in descending damage-per-cast-second order, each once and
+    /// only after its cooldown; a skill is skipped if it doesn't finish inside the
+    /// window or deals less per second than auto-attacks. Time not spent casting
+    /// is filled with whole auto-attacks (`attack_speed` per second).
+    pub fn burst_damage(
+        skill_damages: &[f32],
+        skill_cooldowns_remaining: &[f32],
+        skill_cast_times: &[f32],
+        base_attack_damage: f32,
+        attack_speed: f32,
+        burst_window_s: f32,
+    ) -> f32 {
+        if burst_window_s <= 0.0 {
+            return 0.0;
+        }
+
+        let auto_dps = base_attack_damage.max(0.0) * attack_speed.max(0.0);
+        let rate = |damage: f32, cast: f32| if cast > 0.0 { damage / cast } else { f32::INFINITY };
+
+        let mut skills: Vec<(f32, f32, f32)> = skill_damages.iter()
+            .zip(skill_cooldowns_remaining)
+            .zip(skill_cast_times)
+            .map(|((&damage, &cooldown), &cast)| (damage, cooldown.max(0.0), cast.max(0.0)))
+            .filter(|&(damage, _, cast)| damage > 0.0 && rate(damage, cast) > auto_dps)
+            .collect();
+        skills.sort_by(|a, b| rate(b.0, b.2).partial_cmp(&rate(a.0, a.2)).unwrap_or(Ordering::Equal));
+
+        let mut clock = 0.0f32;
+        let mut casting_time = 0.0f32;
+        let mut damage = 0.0f32;
+        for (skill_damage, cooldown, cast) in skills {
+            // Auto-attack while waiting for the cooldown
+            let start = clock.max(cooldown);
+            if start + cast > burst_window_s {
+                continue;
+            }
+            clock = start + cast;
+            casting_time += cast;
+            damage += skill_damage;
+        }
+
+        let attacks = ((burst_window_s - casting_time) * attack_speed.max(0.0)).floor();
+        damage + attacks * base_attack_damage.max(0.0)
+    }
+
+    /// Rank visible, non-allied 3D targets that can be hit from `self_pos`
+    /// Targets beyond `attack_range` (Euclidean) or more than
+    /// `vertical_angle_limit_degrees` above/below the horizontal plane are dropped.
+    /// Score favours low HP (70%) and proximity (30%).
+    pub fn rank_targets_3d(
+        self_pos: (f32, f32, f32),
+        targets: &[Target3D],
+        attack_range: f32,
+        vertical_angle_limit_degrees: f32,
+    ) -> Vec<RankedTarget> {
+        let mut ranked: Vec<RankedTarget> = targets.iter()
+            .enumerate()
+            .filter(|(_, t)| t.is_visible && t.team != Team::Ally)
+            .filter_map(|(index, t)| {
+                let dx = t.pos.0 - self_pos.0;
+                let dy = t.pos.1 - self_pos.1;
+                let dz = t.pos.2 - self_pos.2;
+                let horizontal = (dx * dx + dz * dz).sqrt();
+                let distance = (horizontal * horizontal + dy * dy).sqrt();
+
+                if distance > attack_range {
+                    return None;
+                }
+                if dy.abs().atan2(horizontal).to_degrees() > vertical_angle_limit_degrees {
+                    return None;
+                }
+
+                let proximity = if attack_range > 0.0 { 1.0 - distance / attack_range } else { 1.0 };
+                let score = (1.0 - t.hp_percent.clamp(0.0, 1.0)) * 0.7 + proximity * 0.3;
+                Some(RankedTarget { index, distance, score })
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+        ranked
+    }
+
+    /// Keep only enemies with a clear line of sight from `self_pos`
+    pub fn filter_visible(
+        self_pos: GridPos,
+        enemies: &[GridPos],
+        obstacles: &FxHashSet<GridPos>,
+    ) -> Vec<GridPos> {
+        enemies.iter()
+            .filter(|enemy| Self::has_line_of_sight(self_pos, **enemy, obstacles))
+            .copied()
+            .collect()
+    }
+
+    /// Check the Bresenham line between two cells for obstacles (endpoints excluded)
+    pub fn has_line_of_sight(from: GridPos, to: GridPos, obstacles: &FxHashSet<GridPos>) -> bool {
+        let dx = (to.x - from.x).abs();
+        let dy = -(to.y - from.y).abs();
+        let sx = if from.x < to.x { 1 } else { -1 };
+        let sy = if from.y < to.y { 1 } else { -1 };
+        let mut err = dx + dy;
+        let mut current = from;
+
+        while current != to {
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                current.x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                current.y += sy;
+            }
+            if current != to && obstacles.contains(&current) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Best-effort escape when surrounded: the reachable cell within
+    /// `search_radius` steps that maximizes the distance to the nearest enemy
+    /// Ties go to the cell reached in fewer steps. Enemy cells block movement.
+    pub fn find_escape_gap(
+        self_pos: GridPos,
+        enemies: &[GridPos],
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+        search_radius: i32,
+    ) -> Option<GridPos> {
+        if self_pos.x < 0 || self_pos.x >= grid_width || self_pos.y < 0 || self_pos.y >= grid_height {
+            return None;
+        }
+
+        let nearest_enemy = |pos: &GridPos| {
+            enemies.iter().map(|e| pos.manhattan_distance(e)).min().unwrap_or(i32::MAX)
+        };
+
+        let mut visited: FxHashSet<GridPos> = FxHashSet::default();
+        let mut queue = VecDeque::new();
+        visited.insert(self_pos);
+        queue.push_back((self_pos, 0));
+
+        let mut best = self_pos;
+        let mut best_distance = nearest_enemy(&self_pos);
+
+        let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+        while let Some((pos, steps)) = queue.pop_front() {
+            let distance = nearest_enemy(&pos);
+            if distance > best_distance {
+                best = pos;
+                best_distance = distance;
+            }
+
+            if steps >= search_radius {
+                continue;
+            }
+
+            for (dx, dy) in directions.iter() {
+                let neighbor = GridPos::new(pos.x + dx, pos.y + dy);
+
+                if neighbor.x < 0 || neighbor.x >= grid_width
+                    || neighbor.y < 0 || neighbor.y >= grid_height {
+                    continue;
+                }
+
+                if obstacles.contains(&neighbor) || enemies.contains(&neighbor) || !visited.insert(neighbor) {
+                    continue;
+                }
+
+                queue.push_back((neighbor, steps + 1));
+            }
+        }
+
+        Some(best)
+    }
+
+    /// Calculate optimal attack position (maintain distance while attacking)
+    pub fn calculate_kite_position(
+        self_pos: GridPos,
+        target_pos: GridPos,
+        attack_range: i32,
+        obstacles: &FxHashSet<GridPos>,
+        grid_width: i32,
+        grid_height: i32,
+    ) -> Option<GridPos> {
+        let current_dist = self_pos.manhattan_distance(&target_pos);
+        
+        // If already at optimal range, stay
+        if current_dist == attack_range {
+            return Some(self_pos);
+        }
+
+        // Find position at attack range
+        let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+        let mut best_pos = None;
+        let mut best_diff = i32::MAX;
+
+        for (dx, dy) in directions.iter() {
+            let new_pos = GridPos::new(self_pos.x + dx, self_pos.y + dy);
+            
+            if new_pos.x < 0 || new_pos.x >= grid_width || new_pos.y < 0 || new_pos.y >= grid_height {
+                continue;
+            }
+            
+            if obstacles.contains(&new_pos) {
+                continue;
+            }
+
+            let new_dist = new_pos.manhattan_distance(&target_pos);
+            let diff = (new_dist - attack_range).abs();
+
+            if diff < best_diff {
+                best_diff = diff;
+                best_pos = Some(new_pos);
+            }
+        }
+
+        best_pos
+    }
+
+    /// Decide whether to recall (return to base)
+    /// Only HP and threat are weighed; mana, distance to base, objective timing
+    /// and the channel length are not yet.
+    /// - Never while `enemy_threat_score > 0.5`: the channel would be interrupted
+    /// - Always below 15% HP otherwise
+    /// - Below 25% HP when the area is safe (`enemy_threat_score < 0.3`)
+    pub fn should_recall(
+        self_hp_pct: f32,
+        _self_mana_pct: f32,
+        _distance_to_base: i32,
+        enemy_threat_score: f32,
+        _time_to_next_objective_s: f32,
+        _recall_duration_s: f32,
+    ) -> bool {
+        // Vulnerable during the whole channel
+        if enemy_threat_score > 0.5 {
+            return false;
+        }
+
+        if self_hp_pct < 0.15 {
+            return true;
+        }
+
+        self_hp_pct < 0.25 && enemy_threat_score < 0.3
+    }
+
+    /// Cast points for a two-skill AOE combo hitting the most enemies
+    /// Every cell within `skill_a_range` of `self_pos` is tried (in parallel) as
+    /// A's center; B lands at A + `skill_b_offset` and must be within
+    /// `skill_b_range`. An enemy counts once if either AOE (Manhattan radius)
+    /// covers it. Ties go to the cast nearest `self_pos`. None unless > 2 enemies are hit.
+    pub fn find_combo_aoe_position(
+        self_pos: GridPos,
+        enemies: &[GridPos],
+        skill_a_range: i32,
+        skill_a_aoe: i32,
+        skill_b_range: i32,
+        skill_b_aoe: i32,
+        skill_b_offset: GridPos,
+    ) -> Option<(GridPos, GridPos)> {
+        let candidates = self_pos.disk(skill_a_range);
+        let (hits, Reverse(index)) = candidates
+            .par_iter()
+            .enumerate()
+            .filter_map(|(i, a)| {
+                let b = GridPos::new(a.x + skill_b_offset.x, a.y + skill_b_offset.y);
+                if self_pos.manhattan_distance(&b) > skill_b_range {
+                    return None;
+                }
+                let hits = enemies.iter()
+                    .filter(|e| e.manhattan_distance(a) <= skill_a_aoe || e.manhattan_distance(&b) <= skill_b_aoe)
+                    .count();
+                Some((hits, Reverse(i)))
+            })
+            .max()?;
+
+        if hits <= 2 {
+            return None;
+        }
+        let a = candidates[index];
+        Some((a, GridPos::new(a.x + skill_b_offset.x, a.y + skill_b_offset.y)))
+    }
+
+    /// Group enemies whose Manhattan distance is within `radius` (single linkage)
+    /// Returns each cluster's centroid (rounded) and member indices, largest first.
+    pub fn cluster_enemies(enemies: &[GridPos], radius: i32) -> Vec<(GridPos, Vec<usize>)> {
+        let mut assigned = vec![false; enemies.len()];
+        let mut clusters = Vec::new();
+
+        for seed in 0..enemies.len() {
+            if assigned[seed] {
+                continue;
+            }
+
+            assigned[seed] = true;
+            let mut members = vec![seed];
+            let mut i = 0;
+            while i < members.len() {
+                let current = enemies[members[i]];
+                for (j, other) in enemies.iter().enumerate() {
+                    if !assigned[j] && current.manhattan_distance(other) <= radius {
+                        assigned[j] = true;
+                        members.push(j);
+                    }
+                }
+                i += 1;
+            }
+
+            members.sort_unstable();
+            let n = members.len() as f32;
+            let sum_x: i32 = members.iter().map(|&m| enemies[m].x).sum();
+            let sum_y: i32 = members.iter().map(|&m| enemies[m].y).sum();
+            let centroid = GridPos::new(
+                (sum_x as f32 / n).round() as i32,
+                (sum_y as f32 / n).round() as i32,
+            );
+            clusters.push((centroid, members));
+        }
+
+        // Stable sort keeps discovery order among equal sizes
+        clusters.sort_by_key(|(_, members)| Reverse(members.len()));
+        clusters
+    }
+
+    /// Contest an objective if it will be up by the time we get there
+    pub fn should_contest_objective(
+        timer: &ObjectiveTimers,
+        objective: &str,
+        current_time_s: f32,
+        travel_time_s: f32,
+    ) -> bool {
+        timer
+            .seconds_until_respawn(objective, current_time_s)
+            .is_some_and(|remaining| remaining <= travel_time_s)
+    }
+
+    /// Score map control around objectives in [-1.0, 1.0] (positive = ally advantage)
+    /// Each cell belongs to the faction with the nearest unit (BFS distance);
+    /// ties are contested. The score is the fraction of objective-adjacent
+    /// cells held by allies minus the fraction held by enemies.
+    pub fn map_control_score(
+        ally_positions: &[GridPos],
+        enemy_positions: &[GridPos],
+        objectives: &[GridPos],
+        grid_width: i32,
+        grid_height: i32,
+    ) -> f32 {
+        if grid_width <= 0 || grid_height <= 0 {
+            return 0.0;
+        }
+
+        let ally_dist = Self::bfs_distance_field(ally_positions, grid_width, grid_height);
+        let enemy_dist = Self::bfs_distance_field(enemy_positions, grid_width, grid_height);
+
+        // Cells on or next to an objective
+        let mut cells: FxHashSet<GridPos> = FxHashSet::default();
+        let directions = [(0, 0), (0, 1), (0, -1), (1, 0), (-1, 0)];
+        for objective in objectives {
+            for (dx, dy) in directions.iter() {
+                let cell = GridPos::new(objective.x + dx, objective.y + dy);
+                if cell.x >= 0 && cell.x < grid_width && cell.y >= 0 && cell.y < grid_height {
+                    cells.insert(cell);
+                }
+            }
+        }
+
+        if cells.is_empty() {
+            return 0.0;
+        }
+
+        let mut ally_cells = 0;
+        let mut enemy_cells = 0;
+        for cell in &cells {
+            let idx = (cell.y * grid_width + cell.x) as usize;
+            match ally_dist[idx].cmp(&enemy_dist[idx]) {
+                Ordering::Less => ally_cells += 1,
+                Ordering::Greater => enemy_cells += 1,
+                Ordering::Equal => {}
+            }
+        }
+
+        let total = cells.len() as f32;
+        (ally_cells as f32 / total - enemy_cells as f32 / total).clamp(-1.0, 1.0)
+    }
+
+    /// Multi-source BFS distance to the nearest source (row-major, i32::MAX if unreachable)
+    /// Empty when either dimension is <= 0 or the cell count doesn't fit in usize.
+    fn bfs_distance_field(sources: &[GridPos], grid_width: i32, grid_height: i32) -> Vec<i32> {
+        if grid_width <= 0 || grid_height <= 0 {
+            return Vec::new();
+        }
+        let width = grid_width as usize;
+        let Some(cell_count) = width.checked_mul(grid_height as usize) else {
+            return Vec::new();
+        };
+        let index = |pos: GridPos| pos.y as usize * width + pos.x as usize;
+
+        let mut dist = vec![i32::MAX; cell_count];
+        let mut queue = VecDeque::new();
+
+        for source in sources {
+            if source.x < 0 || source.x >= grid_width || source.y < 0 || source.y >= grid_height {
+                continue;
+            }
+            let idx = index(*source);
+            if dist[idx] != 0 {
+                dist[idx] = 0;
+                queue.push_back(*source);
+            }
+        }
+
+        let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+        while let Some(pos) = queue.pop_front() {
+            let d = dist[index(pos)];
+            for (dx, dy) in directions.iter() {
+                let neighbor = GridPos::new(pos.x + dx, pos.y + dy);
+                if neighbor.x < 0 || neighbor.x >= grid_width || neighbor.y < 0 || neighbor.y >= grid_height {
+                    continue;
+                }
+                let nidx = index(neighbor);
+                if dist[nidx] == i32::MAX {
+                    dist[nidx] = d + 1;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        dist
+    }
+}
+
+/// Respawn timers for map objectives (dragons, towers, buffs, ...)
+#[derive(Debug, Clone, Default)]
+pub struct ObjectiveTimers {
+    respawn_at: FxHashMap<String, f32>,
+}
+
+impl ObjectiveTimers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that an objective was taken at `current_game_time_s`
+    pub fn record_kill(&mut self, objective: &str, current_game_time_s: f32, respawn_delay_s: f32) {
+        self.respawn_at.insert(objective.to_string(), current_game_time_s + respawn_delay_s);
+    }
+
+    /// Seconds until the objective is back (0.0 if already up, None if never recorded)
+    pub fn seconds_until_respawn(&self, objective: &str, current_game_time_s: f32) -> Option<f32> {
+        self.respawn_at
+            .get(objective)
+            .map(|&at| (at - current_game_time_s).max(0.0))
+    }
+
+    /// Objectives respawning within the next `window_s` seconds, soonest first
+    pub fn list_upcoming_respawns(&self, current_time_s: f32, window_s: f32) -> Vec<(&str, f32)> {
+        let mut upcoming: Vec<(&str, f32)> = self.respawn_at
+            .iter()
+            .map(|(name, &at)| (name.as_str(), at - current_time_s))
+            .filter(|&(_, remaining)| remaining > 0.0 && remaining <= window_s)
+            .collect();
+        upcoming.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal).then_with(|| a.0.cmp(b.0)));
+        upcoming
+    }
+}
+
+/// Jungle camp state tracked by `JungleCampTracker`
+#[derive(Debug, Clone, Copy)]
+pub struct CampInfo {
+    /// Game time of the last kill; `NEG_INFINITY` if never killed
+    pub last_kill_time: f32,
+    pub respawn_interval: f32,
+    pub position: GridPos,
+    pub is_major: bool,
+}
+
+/// Respawn predictor for jungle camps, used to pick farming targets
+#[derive(Debug, Clone, Default)]
+pub struct JungleCampTracker {
+    pub camps: FxHashMap<String, CampInfo>,
+}
+
+impl JungleCampTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a camp; it starts up until its first recorded kill
+    pub fn add_camp(&mut self, name: &str, position: GridPos, respawn_interval: f32, is_major: bool) {
+        self.camps.insert(name.to_string(), CampInfo {
+            last_kill_time: f32::NEG_INFINITY,
+            respawn_interval,
+            position,
+            is_major,
+        });
+    }
+
+    /// Record that a registered camp was cleared at `game_time` (unknown names are ignored)
+    pub fn record_camp_kill(&mut self, name: &str, game_time: f32) {
+        if let Some(camp) = self.camps.get_mut(name) {
+            camp.last_kill_time = game_time;
         }
+    }
 
-        // Sort by priority
-        decisions.sort_by(|a, b| b.priority.cmp(&a.priority));
-        decisions
+    /// Seconds until the camp is back (0.0 if up, None if not registered)
+    pub fn seconds_until_respawn(&self, name: &str, game_time: f32) -> Option<f32> {
+        self.camps
+            .get(name)
+            .map(|camp| (camp.last_kill_time + camp.respawn_interval - game_time).max(0.0))
     }
 
-    /// Calculate optimal attack position (maintain distance while attacking)
-    pub fn calculate_kite_position(
+    /// Closest camp that is currently up, as (name, position, distance)
+    /// Ties on distance resolve by name so the choice doesn't depend on hash order.
+    pub fn nearest_ready_camp(&self, from: GridPos, game_time: f32) -> Option<(&str, GridPos, f32)> {
+        self.camps
+            .iter()
+            .filter(|(_, camp)| camp.last_kill_time + camp.respawn_interval <= game_time)
+            .map(|(name, camp)| {
+                let distance = (from.euclidean_distance_sq(&camp.position) as f32).sqrt();
+                (name.as_str(), camp.position, distance)
+            })
+            .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(Ordering::Equal).then_with(|| a.0.cmp(b.0)))
+    }
+}
+
+/// Queue of planned skill casts, executed in order as they become ready
+#[derive(Debug, Clone, Default)]
+pub struct SkillQueue {
+    queue: VecDeque<(usize, Option<GridPos>)>,
+}
+
+impl SkillQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a skill, optionally with its own cast target
+    pub fn enqueue(&mut self, skill_idx: usize, target: Option<GridPos>) {
+        self.queue.push_back((skill_idx, target));
+    }
+
+    /// Pop the next queued skill if it can be cast right now
+    /// The head must be off cooldown, no skill may still be casting, and its
+    /// target (queued target, else `target_pos`) must be within range.
+    /// Later entries never jump the head so the combo order is preserved.
+    pub fn dequeue_ready(
+        &mut self,
+        cooldowns: &[f32],
+        cast_times_remaining: &[f32],
         self_pos: GridPos,
+        skill_ranges: &[i32],
         target_pos: GridPos,
-        attack_range: i32,
-        obstacles: &FxHashSet<GridPos>,
-        grid_width: i32,
-        grid_height: i32,
-    ) -> Option<GridPos> {
-        let current_dist = self_pos.manhattan_distance(&target_pos);
-        
-        // If already at optimal range, stay
-        if current_dist == attack_range {
-            return Some(self_pos);
+    ) -> Option<(usize, Option<GridPos>)> {
+        let &(skill_idx, target) = self.queue.front()?;
+
+        if cast_times_remaining.iter().any(|&t| t > 0.0) {
+            return None;
         }
 
-        // Find position at attack range
-        let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)];
-        let mut best_pos = None;
-        let mut best_diff = i32::MAX;
+        let cooldown = cooldowns.get(skill_idx).copied().unwrap_or(f32::MAX);
+        if cooldown > 0.0 {
+            return None;
+        }
 
-        for (dx, dy) in directions.iter() {
-            let new_pos = GridPos::new(self_pos.x + dx, self_pos.y + dy);
-            
-            if new_pos.x < 0 || new_pos.x >= grid_width || new_pos.y < 0 || new_pos.y >= grid_height {
-                continue;
-            }
-            
-            if obstacles.contains(&new_pos) {
-                continue;
-            }
+        let range = skill_ranges.get(skill_idx).copied().unwrap_or(0);
+        if self_pos.manhattan_distance(&target.unwrap_or(target_pos)) > range {
+            return None;
+        }
 
-            let new_dist = new_pos.manhattan_distance(&target_pos);
-            let diff = (new_dist - attack_range).abs();
+        self.queue.pop_front()
+    }
 
-            if diff < best_diff {
-                best_diff = diff;
-                best_pos = Some(new_pos);
-            }
-        }
+    /// Discard all queued skills
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
 
-        best_pos
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
     }
 }
 
@@ -748,7 +2375,7 @@ mod tests {
     #[test]
     fn test_eliminate_find_moves() {
         let board = vec![
-            vec![1, 1, 2, 3, 4],
+            vec![1, 1, 2, 1, 4],
             vec![2, 2, 2, 4, 5],
             vec![3, 3, 3, 5, 6],
             vec![4, 4, 4, 6, 1],
@@ -757,6 +2384,74 @@ mod tests {
 
         let moves = EliminateEngine::find_all_moves(&board);
         assert!(!moves.is_empty());
+
+        let cancel = AtomicBool::new(true);
+        assert!(EliminateEngine::find_best_moves_cancellable(&board, 3, &cancel).is_none());
+        cancel.store(false, AtomicOrdering::Relaxed);
+        assert_eq!(EliminateEngine::find_best_moves_cancellable(&board, 3, &cancel), Some(EliminateEngine::find_best_moves(&board, 3)));
+    }
+
+    #[test]
+    fn test_find_moves_with_min_match() {
+        let match4 = EliminateRules { min_match: 4 };
+        let three = vec![
+            vec![1, 1, 2, 1, 3],
+            vec![4, 5, 6, 7, 4],
+            vec![5, 6, 7, 4, 5],
+        ];
+        assert!(EliminateEngine::find_all_moves(&three)
+            .iter()
+            .any(|mv| (mv.from_col, mv.to_col, mv.eliminates) == (2, 3, 3)));
+        assert!(EliminateEngine::find_all_moves_with_rules(&three, &match4).is_empty());
+
+        // Four in a row is just a plain match when four is the minimum
+        let mut four = three.clone();
+        four[0] = vec![1, 1, 1, 2, 1];
+        let moves = EliminateEngine::find_all_moves_with_rules(&four, &match4);
+        assert_eq!(moves.len(), 1);
+        assert_eq!((moves[0].eliminates, moves[0].special), (4, None));
+
+        let cascade = EliminateEngine::simulate_cascade_with_rules(&four, &moves[0], &match4);
+        assert_eq!(cascade.cleared_by_color[1], 4);
+    }
+
+    #[test]
+    fn test_find_moves_on_jagged_board() {
+        // Rows of different lengths used to index past the end of the short ones
+        let board = vec![
+            vec![1, 2, 1, 1, 3],
+            vec![3, 1],
+            vec![2, 3, 3, 1, 2, 3],
+        ];
+
+        let moves = EliminateEngine::find_all_moves(&board);
+        assert!(moves.iter().any(|mv| (mv.from_row, mv.from_col, mv.to_row, mv.to_col) == (0, 0, 0, 1)));
+        for mv in &moves {
+            assert!(mv.from_col < board[mv.from_row].len() && mv.to_col < board[mv.to_row].len());
+        }
+
+        let best = EliminateEngine::find_best_move_for_color(&board, 1).unwrap();
+        let cascade = EliminateEngine::simulate_cascade(&board, &best);
+        let lengths: Vec<usize> = cascade.board.iter().map(Vec::len).collect();
+        assert_eq!(lengths, vec![5, 2, 6]);
+        assert!(EliminateEngine::find_all_moves(&[vec![]]).is_empty());
+
+        // Holes are blockers: no piece falls into them and gets lost, so every
+        // piece is either still on the board or counted as cleared
+        let pieces = |b: &[Vec<u8>]| b.iter().flatten().filter(|&&c| c != 0).count();
+        for mv in &moves {
+            let cascade = EliminateEngine::simulate_cascade(&board, mv);
+            assert_eq!(pieces(&cascade.board) + cascade.total_cleared, pieces(&board), "{:?}", mv);
+        }
+
+        let mut falling = vec![vec![0, 5], vec![4], vec![0, 0]];
+        EliminateEngine::apply_gravity(&mut falling);
+        // The 4 drops to the floor; the 5 rests on the hole below it
+        assert_eq!(falling, vec![vec![0, 5], vec![0], vec![4, 0]]);
+
+        // A run broken by a hole doesn't match
+        let mut split = vec![vec![7, 1], vec![7], vec![1, 1]];
+        assert_eq!(EliminateEngine::remove_matches(&mut split, 3).iter().sum::<usize>(), 0);
     }
 
     #[test]
@@ -783,6 +2478,99 @@ mod tests {
         assert!(result.path.len() > 3); // Must go around
     }
 
+    #[test]
+    fn test_obstacle_distance_field() {
+        let mut obstacles = FxHashSet::default();
+        obstacles.insert(GridPos::new(4, 4));
+        let field = PathfindingEngine::obstacle_distance_field(&obstacles, 9, 9);
+
+        assert_eq!(field[4][4], 0);
+        assert_eq!(field[4][3], 1);
+        assert_eq!(field[2][4], 2);
+        // The grid edge counts as a wall too
+        assert_eq!(field[0][0], 1);
+        assert_eq!(field[4][0], 1);
+        assert_eq!(field[1][1], 2);
+        assert_eq!(field[2][2], 3);
+
+        let open = PathfindingEngine::obstacle_distance_field(&FxHashSet::default(), 7, 7);
+        let diagonal: Vec<i32> = (0..7).map(|i| open[i][i]).collect();
+        assert_eq!(diagonal, vec![1, 2, 3, 4, 3, 2, 1]);
+
+        // Degenerate grids have no cells rather than a bogus allocation
+        assert!(CombatEngine::bfs_distance_field(&[GridPos::new(0, 0)], -4, 3).is_empty());
+        assert!(PathfindingEngine::obstacle_distance_field(&obstacles, 9, -2).is_empty());
+        assert!(PathfindingEngine::obstacle_distance_field(&obstacles, -9, 2).iter().all(Vec::is_empty));
+    }
+
+    #[test]
+    fn test_inflate_obstacles() {
+        // Wall across x = 4 with a one-cell gap at y = 3
+        let obstacles: FxHashSet<GridPos> =
+            (0..7).filter(|&y| y != 3).map(|y| GridPos::new(4, y)).collect();
+        let (start, goal) = (GridPos::new(0, 3), GridPos::new(8, 3));
+        assert!(PathfindingEngine::find_path(start, goal, &obstacles, 9, 7).found);
+
+        let inflated = PathfindingEngine::inflate_obstacles(&obstacles, 1, 9, 7);
+        assert!(inflated.contains(&GridPos::new(3, 0)) && inflated.contains(&GridPos::new(5, 6)));
+        assert!(inflated.contains(&GridPos::new(4, 3)));
+        assert!(inflated.iter().all(|p| p.x >= 0 && p.y >= 0 && p.x < 9 && p.y < 7));
+        assert_eq!(inflated.len(), 3 * 7);
+        assert!(!PathfindingEngine::find_path(start, goal, &inflated, 9, 7).found);
+
+        assert_eq!(PathfindingEngine::inflate_obstacles(&obstacles, 0, 9, 7), obstacles);
+    }
+
+    #[test]
+    fn test_weighted_astar_quality_vs_speed() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        // 100x100 map with 20% scattered obstacles; node counts stand in for timing
+        let mut rng = StdRng::seed_from_u64(7);
+        let obstacles: FxHashSet<GridPos> = (0..100)
+            .flat_map(|y| (0..100).map(move |x| GridPos::new(x, y)))
+            .filter(|_| rng.gen_bool(0.2))
+            .filter(|p| *p != GridPos::new(0, 0) && *p != GridPos::new(99, 99))
+            .collect();
+        let (start, goal) = (GridPos::new(0, 0), GridPos::new(99, 99));
+
+        let runs: Vec<(f32, PathResult)> = [1.0, 1.1, 1.3, 1.5]
+            .iter()
+            .map(|&heuristic_weight| {
+                let config = PathConfig { heuristic_weight };
+                (heuristic_weight, PathfindingEngine::find_path_with_config(start, goal, &obstacles, 100, 100, &config))
+            })
+            .collect();
+
+        let optimal = &runs[0].1;
+        assert!(optimal.found);
+        assert_eq!(optimal.total_cost, PathfindingEngine::find_path(start, goal, &obstacles, 100, 100).total_cost);
+        for (weight, result) in &runs {
+            assert!(result.found);
+            assert!(result.total_cost as f32 <= optimal.total_cost as f32 * weight);
+        }
+        assert!(runs[3].1.nodes_expanded < optimal.nodes_expanded);
+    }
+
+    #[test]
+    fn test_find_path_with_clearance() {
+        let obstacles = FxHashSet::default();
+        let (start, goal) = (GridPos::new(0, 0), GridPos::new(10, 0));
+
+        let hugging = PathfindingEngine::find_path(start, goal, &obstacles, 11, 7);
+        assert_eq!(hugging.total_cost, 10);
+
+        // Walking along the top edge is expensive; the route dips into open space
+        let central = PathfindingEngine::find_path_with_clearance(start, goal, &obstacles, 11, 7, 5);
+        assert!(central.found);
+        assert_eq!(central.path.first(), Some(&start));
+        assert_eq!(central.path.last(), Some(&goal));
+        assert!(central.path.contains(&GridPos::new(5, 2)));
+
+        let unpenalized = PathfindingEngine::find_path_with_clearance(start, goal, &obstacles, 11, 7, 0);
+        assert_eq!(unpenalized.total_cost, 10);
+    }
+
     #[test]
     fn test_combat_analysis() {
         let self_pos = GridPos::new(5, 5);
@@ -801,4 +2589,712 @@ mod tests {
 
         assert!(!decisions.is_empty());
     }
+
+    #[test]
+    fn test_map_control_score() {
+        let objectives = vec![GridPos::new(2, 2)];
+        let allies = vec![GridPos::new(1, 2)];
+        let enemies = vec![GridPos::new(9, 9)];
+
+        let score = CombatEngine::map_control_score(&allies, &enemies, &objectives, 10, 10);
+        assert!((score - 1.0).abs() < 1e-6);
+
+        let score = CombatEngine::map_control_score(&enemies, &allies, &objectives, 10, 10);
+        assert!((score + 1.0).abs() < 1e-6);
+
+        // Symmetric positions leave the objective contested
+        let score = CombatEngine::map_control_score(
+            &[GridPos::new(0, 2)], &[GridPos::new(4, 2)], &objectives, 10, 10);
+        assert!(score.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_should_recall() {
+        // Low HP and safe
+        assert!(CombatEngine::should_recall(0.2, 0.5, 20, 0.1, 60.0, 8.0));
+        // Low HP but threatened
+        assert!(!CombatEngine::should_recall(0.2, 0.5, 20, 0.4, 60.0, 8.0));
+        // Critical HP recalls even under moderate threat
+        assert!(CombatEngine::should_recall(0.1, 0.5, 20, 0.45, 60.0, 8.0));
+        // ...but not when the channel would be interrupted
+        assert!(!CombatEngine::should_recall(0.1, 0.5, 20, 0.6, 60.0, 8.0));
+        // Healthy
+        assert!(!CombatEngine::should_recall(0.8, 0.5, 20, 0.0, 60.0, 8.0));
+        // Out of mana alone isn't a reason to leave
+        assert!(!CombatEngine::should_recall(0.4, 0.05, 20, 0.1, 60.0, 8.0));
+        // Objective timing doesn't hold a low-HP recall back
+        assert!(CombatEngine::should_recall(0.2, 0.5, 20, 0.1, 5.0, 8.0));
+    }
+
+    #[test]
+    fn test_find_combo_aoe_position() {
+        let self_pos = GridPos::new(0, 0);
+        // Three enemies around (4, 0) and two more around (4, 4)
+        let enemies = [
+            GridPos::new(4, 0), GridPos::new(5, 0), GridPos::new(4, 1),
+            GridPos::new(4, 4), GridPos::new(4, 5),
+        ];
+
+        // B follows straight down from A: both groups can be caught
+        let (a, b) = CombatEngine::find_combo_aoe_position(
+            self_pos, &enemies, 5, 1, 10, 1, GridPos::new(0, 4)).unwrap();
+        assert_eq!(b, GridPos::new(a.x, a.y + 4));
+        let hit = enemies.iter()
+            .filter(|e| e.manhattan_distance(&a) <= 1 || e.manhattan_distance(&b) <= 1)
+            .count();
+        assert_eq!(hit, 5);
+
+        // B stacked on A with tiny AOEs can't hit more than two
+        assert_eq!(
+            CombatEngine::find_combo_aoe_position(self_pos, &enemies, 5, 0, 5, 0, GridPos::new(0, 0)),
+            None
+        );
+        // Out of range entirely
+        assert_eq!(
+            CombatEngine::find_combo_aoe_position(self_pos, &enemies, 1, 1, 1, 1, GridPos::new(0, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cluster_enemies() {
+        let enemies = vec![
+            GridPos::new(0, 0),
+            GridPos::new(1, 0),
+            GridPos::new(20, 20),
+            GridPos::new(0, 1),
+            GridPos::new(21, 20),
+            GridPos::new(10, 0),
+        ];
+
+        let clusters = CombatEngine::cluster_enemies(&enemies, 2);
+        assert_eq!(clusters.len(), 3);
+        assert_eq!(clusters[0], (GridPos::new(0, 0), vec![0, 1, 3]));
+        assert_eq!(clusters[1].1, vec![2, 4]);
+        assert_eq!(clusters[2], (GridPos::new(10, 0), vec![5]));
+    }
+
+    #[test]
+    fn test_skill_queue_order() {
+        let mut queue = SkillQueue::new();
+        queue.enqueue(0, None);
+        queue.enqueue(2, Some(GridPos::new(3, 0)));
+
+        let self_pos = GridPos::new(0, 0);
+        let target = GridPos::new(2, 0);
+        let ranges = [3, 3, 3];
+
+        // Skill 0 still on cooldown blocks the whole combo
+        assert_eq!(queue.dequeue_ready(&[1.0, 0.0, 0.0], &[0.0; 3], self_pos, &ranges, target), None);
+        assert_eq!(queue.dequeue_ready(&[0.0; 3], &[0.0; 3], self_pos, &ranges, target), Some((0, None)));
+        // Skill 0 mid-cast
+        assert_eq!(queue.dequeue_ready(&[0.0; 3], &[0.5, 0.0, 0.0], self_pos, &ranges, target), None);
+        assert_eq!(
+            queue.dequeue_ready(&[0.0; 3], &[0.0; 3], self_pos, &ranges, target),
+            Some((2, Some(GridPos::new(3, 0))))
+        );
+        assert!(queue.is_empty());
+
+        queue.enqueue(1, Some(GridPos::new(10, 0)));
+        assert_eq!(queue.dequeue_ready(&[0.0; 3], &[0.0; 3], self_pos, &ranges, target), None);
+        queue.clear();
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn test_objective_timers() {
+        let mut timers = ObjectiveTimers::new();
+        timers.record_kill("dragon", 100.0, 300.0);
+        timers.record_kill("baron", 200.0, 420.0);
+
+        assert_eq!(timers.seconds_until_respawn("dragon", 350.0), Some(50.0));
+        assert_eq!(timers.seconds_until_respawn("dragon", 500.0), Some(0.0));
+        assert_eq!(timers.seconds_until_respawn("herald", 350.0), None);
+
+        let upcoming = timers.list_upcoming_respawns(350.0, 300.0);
+        assert_eq!(upcoming, vec![("dragon", 50.0), ("baron", 270.0)]);
+        assert_eq!(timers.list_upcoming_respawns(350.0, 60.0).len(), 1);
+
+        assert!(CombatEngine::should_contest_objective(&timers, "dragon", 350.0, 60.0));
+        assert!(!CombatEngine::should_contest_objective(&timers, "dragon", 350.0, 30.0));
+        assert!(!CombatEngine::should_contest_objective(&timers, "herald", 350.0, 30.0));
+    }
+
+    #[test]
+    fn test_evaluate_t_shape() {
+        let board = vec![
+            vec![3, 1, 3, 4],
+            vec![1, 2, 1, 4],
+            vec![4, 1, 5, 3],
+            vec![5, 1, 3, 5],
+        ];
+
+        let moves = EliminateEngine::find_all_moves(&board);
+        let mv = moves.iter()
+            .find(|m| (m.from_row, m.from_col, m.to_row, m.to_col) == (0, 1, 1, 1))
+            .expect("T-shape swap should be valid");
+        assert_eq!(mv.eliminates, 5);
+        assert!(mv.creates_special);
+        assert_eq!(mv.special, Some(SpecialType::TShape));
+    }
+
+    #[test]
+    fn test_evaluate_straight_five() {
+        let board = vec![
+            vec![1, 1, 2, 1, 1],
+            vec![2, 3, 1, 3, 2],
+        ];
+
+        let best = EliminateEngine::find_best_move(&board).unwrap();
+        assert_eq!(best.eliminates, 5);
+        assert_eq!(best.special, Some(SpecialType::Line5));
+    }
+
+    #[test]
+    fn test_find_best_move_with_priority() {
+        // Swapping (0,2)<->(0,3) clears three 1s; swapping (1,2)<->(2,2) clears six
+        let board = vec![
+            vec![1, 1, 4, 1, 5],
+            vec![2, 2, 3, 6, 7],
+            vec![3, 3, 2, 7, 6],
+        ];
+
+        let best = EliminateEngine::find_best_move(&board).unwrap();
+        assert_eq!((best.from_row, best.from_col, best.to_row, best.to_col), (1, 2, 2, 2));
+        assert_eq!(best.score, 60);
+
+        let best = EliminateEngine::find_best_move_with_priority(&board, &[(1, 3.0)]).unwrap();
+        assert_eq!((best.from_row, best.from_col, best.to_row, best.to_col), (0, 2, 0, 3));
+        assert_eq!(best.eliminates, 3);
+        assert_eq!(best.score, 90);
+    }
+
+    #[test]
+    fn test_find_best_move_for_color() {
+        // (2,2)<->(2,3) clears three 1s; (1,2)<->(2,2) clears a line of five 2s
+        let board = vec![
+            vec![3, 4, 5, 6, 7, 3],
+            vec![2, 2, 5, 2, 2, 4],
+            vec![1, 1, 2, 1, 4, 6],
+            vec![4, 5, 3, 7, 6, 5],
+        ];
+
+        let best = EliminateEngine::find_best_move(&board).unwrap();
+        assert_eq!((best.from_row, best.from_col, best.to_row, best.to_col), (1, 2, 2, 2));
+        let cascade = EliminateEngine::simulate_cascade(&board, &best);
+        assert_eq!(cascade.total_cleared, 5);
+        assert_eq!(cascade.cleared_by_color[2], 5);
+
+        let best = EliminateEngine::find_best_move_for_color(&board, 1).unwrap();
+        assert_eq!((best.from_row, best.from_col, best.to_row, best.to_col), (2, 2, 2, 3));
+        let cascade = EliminateEngine::simulate_cascade(&board, &best);
+        assert_eq!(cascade.cleared_by_color, [0, 3, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(cascade.cascades, 1);
+    }
+
+    #[test]
+    fn test_simulate_move_payload_round_trip() {
+        // What StrategyEngineNative.simulateMove receives and sends back
+        let board_json = "[[5,3,3,6],[3,4,2,5],[6,1,1,2],[7,5,4,1]]";
+        let move_json = r#"{"from_row":2,"from_col":3,"to_row":3,"to_col":3,"score":0,"eliminates":0,"creates_special":false}"#;
+
+        let board: Vec<Vec<u8>> = serde_json::from_str(board_json).unwrap();
+        let mv: EliminateMove = serde_json::from_str(move_json).unwrap();
+        let cascade = EliminateEngine::simulate_cascade(&board, &mv);
+
+        let json = serde_json::to_string(&crate::JsonEnvelope::new(&cascade)).unwrap();
+        assert!(json.contains("\"total_cleared\":6"));
+        assert!(json.contains("\"board\":[[0,0,0,0],[5,0,0,6],[6,4,2,5],[7,5,4,2]]"));
+
+        let parsed: crate::JsonEnvelope<CascadeResult> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.version, crate::SCHEMA_VERSION);
+        assert_eq!(parsed.data, cascade);
+    }
+
+    #[test]
+    fn test_board_history_undo() {
+        let start = vec![vec![1, 2, 1], vec![3, 1, 4]];
+        let mut history = BoardHistory::new(start.clone());
+        assert!(history.is_empty());
+
+        let first = EliminateMove::new(0, 1, 1, 1);
+        let after_first = EliminateEngine::simulate_move(&start, &first);
+        assert_eq!(after_first[0], vec![0, 0, 0]);
+        history.push(first);
+        assert_eq!(history.current(), after_first.as_slice());
+        // Only the written cells are kept: the swap and the three cleared pieces
+        assert_eq!(history.previous[0].0.len(), 5);
+
+        let second = EliminateMove::new(1, 0, 1, 2);
+        let after_second = EliminateEngine::simulate_move(&after_first, &second);
+        history.push(second);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.current(), after_second.as_slice());
+
+        assert_eq!(history.undo(), Some((after_first.as_slice(), second)));
+        assert_eq!(history.undo(), Some((start.as_slice(), first)));
+        assert_eq!(history.undo(), None);
+
+        // A cascade that drops pieces is undone too
+        let falling = vec![vec![5, 6, 7], vec![2, 1, 1], vec![1, 3, 4]];
+        let mut history = BoardHistory::new(falling.clone());
+        let mv = EliminateMove::new(1, 0, 2, 0);
+        history.push(mv);
+        assert_eq!(history.current(), EliminateEngine::simulate_move(&falling, &mv).as_slice());
+        assert_eq!(history.current()[..2], [vec![0, 0, 0], vec![5, 6, 7]]);
+        assert_eq!(history.undo(), Some((falling.as_slice(), mv)));
+
+        // Swapping back with the inverse restores a board without matches
+        let mut board = start.clone();
+        let mv = EliminateMove::new(1, 0, 1, 2);
+        for m in [mv, mv.inverse_move()] {
+            let piece = board[m.from_row][m.from_col];
+            board[m.from_row][m.from_col] = board[m.to_row][m.to_col];
+            board[m.to_row][m.to_col] = piece;
+        }
+        assert_eq!(board, start);
+        assert_eq!(mv.inverse_move().inverse_move(), mv);
+    }
+
+    #[test]
+    fn test_simulate_cascade_chain() {
+        // Clearing the 1s drops the top-row 3s next to the 3 in row 1
+        let board = vec![
+            vec![5, 3, 3, 6],
+            vec![3, 4, 2, 5],
+            vec![6, 1, 1, 2],
+            vec![7, 5, 4, 1],
+        ];
+        let cascade = EliminateEngine::simulate_cascade(&board, &EliminateMove::new(2, 3, 3, 3));
+        assert_eq!(cascade.cascades, 2);
+        assert_eq!(cascade.total_cleared, 6);
+        assert_eq!(cascade.cleared_by_color[1], 3);
+        assert_eq!(cascade.cleared_by_color[3], 3);
+        assert_eq!(cascade.board[3], vec![7, 5, 4, 2]);
+    }
+
+    #[test]
+    fn test_filter_visible() {
+        let self_pos = GridPos::new(0, 0);
+        let hidden = GridPos::new(4, 0);
+        let visible = GridPos::new(0, 4);
+        let mut obstacles = FxHashSet::default();
+        obstacles.insert(GridPos::new(2, 0)); // Wall between self and `hidden`
+
+        let result = CombatEngine::filter_visible(self_pos, &[hidden, visible], &obstacles);
+        assert_eq!(result, vec![visible]);
+
+        // Only the hidden enemy is low HP, so no attack once walls are considered
+        let enemies = vec![(hidden, 0.1), (visible, 0.9)];
+        let decisions = CombatEngine::analyze_combat_with_obstacles(
+            self_pos, 0.8, &enemies, &[GridPos::new(1, 1)], &[false], false, Some(&obstacles));
+        assert!(decisions.iter().all(|d| d.target_pos != Some(hidden)));
+
+        let decisions = CombatEngine::analyze_combat(
+            self_pos, 0.8, &enemies, &[GridPos::new(1, 1)], &[false], false);
+        assert_eq!(decisions[0].target_pos, Some(hidden));
+    }
+
+    #[test]
+    fn test_interrupt_cast() {
+        let self_pos = GridPos::new(0, 0);
+        let near = GridPos::new(3, 0);
+
+        // Ready stun, 200ms travel: beats a 500ms cast but not a 150ms one or one 1s off cooldown
+        assert!(CombatEngine::can_interrupt_cast(2, 0.0, 5, 200.0, self_pos, near, 500.0));
+        assert!(!CombatEngine::can_interrupt_cast(2, 0.0, 5, 200.0, self_pos, near, 150.0));
+        assert!(!CombatEngine::can_interrupt_cast(2, 1.0, 5, 200.0, self_pos, near, 500.0));
+        assert!(!CombatEngine::can_interrupt_cast(2, 0.0, 2, 200.0, self_pos, near, 500.0));
+
+        assert_eq!(CombatEngine::interrupt_priority("Mass Heal"), 90);
+        assert!(CombatEngine::interrupt_priority("ULTIMATE: Meteor") > CombatEngine::interrupt_priority("Recall"));
+        assert_eq!(CombatEngine::interrupt_priority("Fireball"), 10);
+
+        let casts = vec![
+            EnemyCast { caster_pos: near, cast_name: "Fireball".to_string(), cast_remaining_ms: 400.0 },
+            EnemyCast { caster_pos: GridPos::new(0, 4), cast_name: "Heal".to_string(), cast_remaining_ms: 900.0 },
+            EnemyCast { caster_pos: GridPos::new(9, 9), cast_name: "Ultimate".to_string(), cast_remaining_ms: 900.0 },
+        ];
+        let decision = CombatEngine::plan_interrupt(self_pos, 2, 0.0, 5, 200.0, &casts).unwrap();
+        assert_eq!(decision.action, CombatAction::InterruptCast);
+        assert_eq!(decision.target_pos, Some(GridPos::new(0, 4)));
+        assert!(decision.priority > 100);
+        assert!(CombatEngine::plan_interrupt(self_pos, 2, 5.0, 5, 200.0, &casts).is_none());
+
+        // analyze_combat puts the interrupt first, even with HP critical
+        let stun = StunSkill { skill_index: 2, cooldown_remaining: 0.0, range: 5, travel_time_ms: 200.0 };
+        let enemies = [(near, 0.9)];
+        let decisions = CombatEngine::analyze_combat_with_casts(
+            self_pos, 0.1, &enemies, &[], &[false, false, true], false, None, Some(&stun), &casts);
+        assert_eq!(decisions[0].action, CombatAction::InterruptCast);
+        assert_eq!(decisions[0].target_pos, Some(GridPos::new(0, 4)));
+        assert_eq!(decisions[1].action, CombatAction::Retreat);
+
+        let decisions = CombatEngine::analyze_combat_with_casts(
+            self_pos, 0.8, &enemies, &[], &[false], false, None, None, &casts);
+        assert!(decisions.iter().all(|d| d.action != CombatAction::InterruptCast));
+    }
+
+    #[test]
+    fn test_plan_combo() {
+        let skills = vec![
+            // 0: execute, 1: stun, 2: burst, 3: on cooldown, 4: out of range
+            SkillInfo { range: 3, cooldown_ready: true, combo_priority: 1, damage: 300.0 },
+            SkillInfo { range: 5, cooldown_ready: true, combo_priority: 3, damage: 50.0 },
+            SkillInfo { range: 4, cooldown_ready: true, combo_priority: 2, damage: 200.0 },
+            SkillInfo { range: 5, cooldown_ready: false, combo_priority: 4, damage: 500.0 },
+            SkillInfo { range: 1, cooldown_ready: true, combo_priority: 5, damage: 500.0 },
+        ];
+        let self_pos = GridPos::new(0, 0);
+        let target_pos = GridPos::new(2, 1);
+
+        let combo = CombatEngine::plan_combo(self_pos, (target_pos, 500.0), &skills);
+        let order: Vec<&str> = combo.iter().map(|d| d.reason.as_str()).collect();
+        assert_eq!(order, vec!["Combo step 1: skill 1", "Combo step 2: skill 2", "Combo step 3: skill 0"]);
+        assert!(combo.iter().all(|d| d.action == CombatAction::UseSkill && d.target_pos == Some(target_pos)));
+
+        // Stun + burst already kill a 200 HP target
+        let combo = CombatEngine::plan_combo(self_pos, (target_pos, 200.0), &skills);
+        assert_eq!(combo.len(), 2);
+    }
+
+    #[test]
+    fn test_best_move_tie_break() {
+        let mut a = EliminateMove::new(2, 1, 2, 2);
+        let mut b = EliminateMove::new(0, 3, 1, 3);
+        a.score = 30;
+        b.score = 30;
+        a.eliminates = 3;
+        b.eliminates = 3;
+
+        // The top-left move wins whichever order the candidates arrive in
+        assert_eq!(vec![a, b].into_iter().max(), Some(b));
+        assert_eq!(vec![b, a].into_iter().max(), Some(b));
+    }
+
+    #[test]
+    fn test_rank_targets_3d() {
+        let enemy = |pos, hp_percent| Target3D { pos, hp_percent, is_visible: true, team: Team::Enemy };
+        let targets = vec![
+            enemy((3.0, 0.0, 0.0), 0.9),
+            enemy((0.0, 0.0, 4.0), 0.2),
+            enemy((20.0, 0.0, 0.0), 0.1),  // out of range
+            enemy((1.0, 5.0, 0.0), 0.1),   // almost straight above
+            Target3D { pos: (1.0, 0.0, 1.0), hp_percent: 0.1, is_visible: false, team: Team::Enemy },
+            Target3D { pos: (1.0, 0.0, 1.0), hp_percent: 0.1, is_visible: true, team: Team::Ally },
+        ];
+
+        let ranked = CombatEngine::rank_targets_3d((0.0, 0.0, 0.0), &targets, 8.0, 45.0);
+        let order: Vec<usize> = ranked.iter().map(|r| r.index).collect();
+        assert_eq!(order, vec![1, 0]);
+        assert!((ranked[0].distance - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_human_like_delay_ms() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let board = vec![
+            vec![1, 1, 2, 1, 4],
+            vec![2, 3, 4, 5, 1],
+            vec![3, 4, 5, 1, 2],
+        ];
+        let plain = EliminateMove::new(0, 2, 0, 3);
+        let mut five = plain;
+        five.special = Some(SpecialType::Line5);
+
+        for seed in 0..20 {
+            let delay = EliminateEngine::human_like_delay_ms(
+                &board, &plain, 1000, (400, 1500), &mut StdRng::seed_from_u64(seed));
+            assert!((400..=1500).contains(&delay));
+
+            // Same jitter, but the obvious 5-match is decided faster
+            let fast = EliminateEngine::human_like_delay_ms(
+                &board, &five, 1000, (0, 5000), &mut StdRng::seed_from_u64(seed));
+            let slow = EliminateEngine::human_like_delay_ms(
+                &board, &plain, 1000, (0, 5000), &mut StdRng::seed_from_u64(seed));
+            assert!(fast < slow);
+        }
+    }
+
+    #[test]
+    fn test_find_escape_gap() {
+        // Enemies left, right and below; the open side is up (decreasing y)
+        let self_pos = GridPos::new(5, 5);
+        let enemies = vec![GridPos::new(3, 5), GridPos::new(7, 5), GridPos::new(5, 7)];
+        let obstacles = FxHashSet::default();
+
+        let escape = CombatEngine::find_escape_gap(self_pos, &enemies, &obstacles, 11, 11, 3);
+        assert_eq!(escape, Some(GridPos::new(5, 2)));
+    }
+
+    #[test]
+    fn test_deserialize_old_eliminate_move() {
+        // Shape emitted before `special` existed
+        let json = r#"{"from_row":0,"from_col":1,"to_row":0,"to_col":2,"score":30,
+            "eliminates":3,"creates_special":false}"#;
+        let mv: EliminateMove = serde_json::from_str(json).unwrap();
+        assert_eq!(mv.score, 30);
+        assert_eq!(mv.special, None);
+    }
+
+    #[test]
+    fn test_reachable_and_intercept() {
+        let obstacles: FxHashSet<GridPos> = [GridPos::new(1, 0), GridPos::new(1, 1)].into_iter().collect();
+
+        let reachable = PathfindingEngine::reachable_in_steps(GridPos::new(0, 0), 2, &obstacles, 5, 5);
+        let mut cells: Vec<(i32, i32)> = reachable.iter().map(|p| (p.x, p.y)).collect();
+        cells.sort();
+        assert_eq!(cells, vec![(0, 0), (0, 1), (0, 2)]);
+
+        // Target runs along row 4 from (0, 4) to (9, 4) at speed 1
+        let open = FxHashSet::default();
+        let target = GridPos::new(0, 4);
+        let goal = GridPos::new(9, 4);
+        assert!(PathfindingEngine::intercept_possible(
+            GridPos::new(6, 0), 1, target, 1, goal, &open, 10, 10));
+        assert!(!PathfindingEngine::intercept_possible(
+            GridPos::new(9, 9), 1, target, 2, GridPos::new(0, 0), &open, 10, 10));
+    }
+
+    #[test]
+    fn test_grid_pos_ring_and_disk() {
+        let center = GridPos::new(3, 3);
+        assert_eq!(center.ring(0), vec![center]);
+
+        let ring1 = center.ring(1);
+        assert_eq!(ring1.len(), 4);
+        assert!(ring1.iter().all(|p| p.manhattan_distance(&center) == 1));
+
+        let ring3: FxHashSet<GridPos> = center.ring(3).into_iter().collect();
+        assert_eq!(ring3.len(), 12);
+        assert!(ring3.iter().all(|p| p.manhattan_distance(&center) == 3));
+
+        let disk1 = center.disk(1);
+        assert_eq!(disk1.len(), 5);
+        assert_eq!(disk1[0], center);
+        assert_eq!(center.disk(2).len(), 13);
+        assert!(center.ring(-1).is_empty());
+    }
+
+    #[test]
+    fn test_diagonal_distances() {
+        let origin = GridPos::new(0, 0);
+        let cases = [
+            // (other, chebyshev, octile)
+            (GridPos::new(3, 0), 3, 30),
+            (GridPos::new(2, 2), 2, 28),
+            (GridPos::new(-5, 2), 5, 58),
+            (GridPos::new(1, -4), 4, 44),
+            (origin, 0, 0),
+        ];
+        for (other, chebyshev, octile) in cases {
+            assert_eq!(origin.chebyshev_distance(&other), chebyshev, "{:?}", other);
+            assert_eq!(origin.octile_distance(&other), octile, "{:?}", other);
+            assert_eq!(other.octile_distance(&origin), octile);
+        }
+
+        // With no obstacles the 8-dir path costs exactly the octile distance
+        let goal = GridPos::new(7, 3);
+        let path = PathfindingEngine::find_path_8dir(origin, goal, &FxHashSet::default(), 10, 10);
+        assert_eq!(path.total_cost, origin.octile_distance(&goal));
+    }
+
+    #[test]
+    fn test_jungle_camp_tracker() {
+        let mut tracker = JungleCampTracker::new();
+        tracker.add_camp("wolves", GridPos::new(3, 4), 60.0, false);
+        tracker.add_camp("red_buff", GridPos::new(10, 0), 90.0, true);
+
+        assert_eq!(tracker.seconds_until_respawn("wolves", 0.0), Some(0.0));
+        assert_eq!(tracker.seconds_until_respawn("drake", 0.0), None);
+        assert_eq!(tracker.nearest_ready_camp(GridPos::new(0, 0), 0.0), Some(("wolves", GridPos::new(3, 4), 5.0)));
+
+        tracker.record_camp_kill("wolves", 100.0);
+        assert_eq!(tracker.seconds_until_respawn("wolves", 130.0), Some(30.0));
+        assert_eq!(tracker.nearest_ready_camp(GridPos::new(0, 0), 130.0), Some(("red_buff", GridPos::new(10, 0), 10.0)));
+
+        tracker.record_camp_kill("red_buff", 120.0);
+        assert_eq!(tracker.nearest_ready_camp(GridPos::new(0, 0), 130.0), None);
+        assert_eq!(tracker.nearest_ready_camp(GridPos::new(0, 0), 160.0).map(|c| c.0), Some("wolves"));
+    }
+
+    #[test]
+    fn test_path_nodes_expanded() {
+        // Vertical wall at x = 5 with a gap at the bottom
+        let obstacles: FxHashSet<GridPos> = (0..9).map(|y| GridPos::new(5, y)).collect();
+        let start = GridPos::new(2, 2);
+        let goal = GridPos::new(8, 2);
+
+        let result = PathfindingEngine::find_path(start, goal, &obstacles, 10, 10);
+        assert!(result.found);
+        assert!(result.nodes_expanded > result.path.len());
+
+        let result_8dir = PathfindingEngine::find_path_8dir(start, goal, &obstacles, 10, 10);
+        assert!(result_8dir.found);
+        assert!(result_8dir.nodes_expanded > result_8dir.path.len());
+        // Optimal detour through the gap at (5, 9), no corner cutting
+        assert_eq!(result_8dir.total_cost, 176);
+
+        // A short wall across the diagonal: an overestimating heuristic hugs
+        // the wall and pays 146 instead of the optimal 134
+        let wall: FxHashSet<GridPos> = (4..8).map(|y| GridPos::new(3, y)).collect();
+        let around = PathfindingEngine::find_path_8dir(GridPos::new(0, 9), GridPos::new(9, 1), &wall, 10, 10);
+        assert_eq!(around.total_cost, 134);
+
+        let old: PathResult = serde_json::from_str(r#"{"path":[],"total_cost":-1,"found":false}"#).unwrap();
+        assert_eq!(old.nodes_expanded, 0);
+    }
+
+    #[test]
+    fn test_burst_damage() {
+        // Autos alone: 2 attacks/s for 3s
+        assert_eq!(CombatEngine::burst_damage(&[], &[], &[], 50.0, 2.0, 3.0), 300.0);
+
+        // 400 dmg / 0.5s skill ready now, 300 dmg / 1s skill ready at 1.0s,
+        // 100 dmg / 1s skill is worse than autos (100 dps) and is skipped
+        let damages = [300.0, 400.0, 100.0];
+        let cooldowns = [1.0, 0.0, 0.0];
+        let casts = [1.0, 0.5, 1.0];
+        // 700 from skills + floor((3 - 1.5) * 2) = 3 autos
+        assert_eq!(CombatEngine::burst_damage(&damages, &cooldowns, &casts, 50.0, 2.0, 3.0), 850.0);
+
+        // Window too short for the cooling-down skill to finish
+        assert_eq!(CombatEngine::burst_damage(&damages, &cooldowns, &casts, 50.0, 2.0, 1.5), 500.0);
+        assert_eq!(CombatEngine::burst_damage(&damages, &cooldowns, &casts, 50.0, 2.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_find_safe_position_weighted() {
+        let open = FxHashSet::default();
+        let me = GridPos::new(10, 5);
+        let enemy = GridPos::new(8, 5);
+
+        // threat 1.0 / d < 0.25 needs d >= 5; threat 4.0 / d < 0.25 needs d >= 17
+        let weak = PathfindingEngine::find_safe_position_weighted(me, &[(enemy, 1.0)], &open, 30, 11, 0.25).unwrap();
+        let strong = PathfindingEngine::find_safe_position_weighted(me, &[(enemy, 4.0)], &open, 30, 11, 0.25).unwrap();
+        assert_eq!(weak.manhattan_distance(&enemy), 5);
+        assert_eq!(strong.manhattan_distance(&enemy), 17);
+        assert!(strong.manhattan_distance(&me) > weak.manhattan_distance(&me));
+
+        assert_eq!(PathfindingEngine::find_safe_position_weighted(me, &[], &open, 30, 11, 0.25), Some(me));
+    }
+
+    #[test]
+    fn test_board_orientation() {
+        let board = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        assert_eq!(EliminateEngine::flip_horizontal(&board), vec![vec![3, 2, 1], vec![6, 5, 4], vec![9, 8, 7]]);
+        assert_eq!(EliminateEngine::flip_vertical(&board), vec![vec![7, 8, 9], vec![4, 5, 6], vec![1, 2, 3]]);
+        assert_eq!(EliminateEngine::transpose(&board), vec![vec![1, 4, 7], vec![2, 5, 8], vec![3, 6, 9]]);
+
+        assert_eq!(EliminateEngine::flip_horizontal(&EliminateEngine::flip_horizontal(&board)), board);
+        assert_eq!(EliminateEngine::flip_vertical(&EliminateEngine::flip_vertical(&board)), board);
+        assert_eq!(EliminateEngine::transpose(&EliminateEngine::transpose(&board)), board);
+
+        let wide = vec![vec![1, 2, 3]];
+        assert_eq!(EliminateEngine::transpose(&wide), vec![vec![1], vec![2], vec![3]]);
+
+        // A move found on the mirrored board maps back to the original cells
+        let mirrored = EliminateEngine::flip_horizontal(&board);
+        let mv = EliminateMove::new(0, 0, 0, 1);
+        assert_eq!(mirrored[mv.from_row][mv.from_col], 3);
+        let back = mv.flipped_horizontal(3);
+        assert_eq!(board[back.from_row][back.from_col], 3);
+        assert_eq!((back.to_row, back.to_col), (0, 1));
+        assert_eq!(mv.transposed().transposed(), mv);
+        assert_eq!(mv.flipped_vertical(3).from_row, 2);
+
+        // Empty boards don't underflow
+        assert_eq!(mv.flipped_horizontal(0), mv);
+        assert_eq!(mv.flipped_vertical(0), mv);
+        assert!(EliminateEngine::flip_horizontal(&[]).is_empty());
+        assert!(EliminateEngine::transpose(&[]).is_empty());
+
+        // Ragged boards are sized by the longest row
+        let ragged = vec![vec![1, 2], vec![3], vec![4, 5, 6]];
+        assert_eq!(EliminateEngine::transpose(&ragged), vec![vec![1, 3, 4], vec![2, 0, 5], vec![0, 0, 6]]);
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let board = vec![vec![6, 2, 0], vec![2, 6, 4]];
+        let (canonical, mapping) = EliminateEngine::canonicalize(&board);
+        assert_eq!(canonical, vec![vec![1, 2, 0], vec![2, 1, 3]]);
+        assert_eq!(mapping, vec![0, 0, 2, 0, 3, 0, 1]);
+
+        // Same layout with the colors permuted
+        let swapped = vec![vec![3, 5, 0], vec![5, 3, 1]];
+        assert_eq!(EliminateEngine::canonicalize(&swapped).0, canonical);
+
+        let different = vec![vec![3, 5, 0], vec![5, 5, 1]];
+        assert_ne!(EliminateEngine::canonicalize(&different).0, canonical);
+
+        // Every u8 color in use doesn't overflow the label counter
+        let all_colors = vec![(1..=255u8).rev().collect::<Vec<u8>>()];
+        let (canonical, _) = EliminateEngine::canonicalize(&all_colors);
+        assert_eq!(canonical[0], (1..=255u8).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_position_score() {
+        let obstacles = FxHashSet::default();
+        let goal = GridPos::new(10, 5);
+        let enemy = [GridPos::new(4, 5)];
+        let allies = [GridPos::new(1, 5), GridPos::new(2, 6)];
+
+        // Next to the enemy vs. out of its reach with allies in support
+        let exposed = CombatEngine::position_score(GridPos::new(5, 5), goal, &enemy, &allies, &obstacles, 20, 20);
+        let covered = CombatEngine::position_score(GridPos::new(0, 2), goal, &enemy, &allies, &obstacles, 20, 20);
+        assert!(covered > exposed, "{} <= {}", covered, exposed);
+        assert!((-1.0..=1.0).contains(&exposed) && (-1.0..=1.0).contains(&covered));
+
+        // No enemies, standing on the goal, every ally close: max score
+        let ideal = CombatEngine::position_score(goal, goal, &[], &[GridPos::new(9, 5)], &obstacles, 20, 20);
+        assert!((ideal - 0.7).abs() < 1e-6);
+        assert_eq!(CombatEngine::position_score(GridPos::new(-1, 0), goal, &[], &[], &obstacles, 20, 20), -1.0);
+
+        // Stepping away from the enemy (and towards the goal) beats stepping towards it
+        let goal = GridPos::new(8, 5);
+        let step = CombatEngine::best_adjacent_position(
+            GridPos::new(5, 5), goal, &enemy, &[], &obstacles, 20, 20).unwrap();
+        assert_eq!(step, GridPos::new(6, 5));
+
+        // With that cell walled off, up and down tie and up wins
+        let mut walls = FxHashSet::default();
+        walls.insert(GridPos::new(6, 5));
+        let step = CombatEngine::best_adjacent_position(
+            GridPos::new(5, 5), goal, &enemy, &[], &walls, 20, 20).unwrap();
+        assert_eq!(step, GridPos::new(5, 4));
+    }
+
+    #[test]
+    fn test_find_corridors_and_heatmap() {
+        // Horizontal corridor: walls on rows 3 and 5 for x 2..=8, open row 4
+        let mut obstacles: FxHashSet<GridPos> = FxHashSet::default();
+        for x in 2..=8 {
+            obstacles.insert(GridPos::new(x, 3));
+            obstacles.insert(GridPos::new(x, 5));
+        }
+        // Vertical corridor two cells wide: walls on columns 12 and 15 for y 0..=5
+        for y in 0..=5 {
+            obstacles.insert(GridPos::new(12, y));
+            obstacles.insert(GridPos::new(15, y));
+        }
+
+        let corridors = PathfindingEngine::find_corridors(&obstacles, 20, 10, 2);
+        assert!(corridors.contains(&Rect::new(2, 4, 7, 1)));
+        assert!(corridors.contains(&Rect::new(13, 0, 2, 6)));
+        assert_eq!(corridors.len(), 2);
+        assert!(PathfindingEngine::find_corridors(&obstacles, 20, 10, 1).len() == 1);
+
+        let heatmap = CombatEngine::build_threat_heatmap(&[], &obstacles, 20, 10);
+        assert_eq!(heatmap.len(), 10);
+        assert_eq!(heatmap[4][5], CORRIDOR_THREAT);
+        assert_eq!(heatmap[8][5], 0.0);
+        assert_eq!(heatmap[3][5], 0.0);
+
+        let heatmap = CombatEngine::build_threat_heatmap(&[GridPos::new(0, 9)], &FxHashSet::default(), 20, 10);
+        assert_eq!(heatmap[9][0], 1.0);
+        assert_eq!(heatmap[9][1], 0.5);
+    }
 }
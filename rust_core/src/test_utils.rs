@@ -0,0 +1,69 @@
+//! Synthetic frames shared by the unit tests
+
+use crate::image_engine::{ImageData, Rgb};
+
+/// Side in pixels of one cell drawn by `make_game_board`
+pub(crate) const BOARD_CELL_SIZE: usize = 40;
+
+/// Background behind health bars drawn by `make_health_bar_image`
+pub(crate) const BAR_BACKGROUND: Rgb = Rgb { r: 40, g: 40, b: 40 };
+
+/// Empty part of the track behind bars drawn by `make_health_bar_image`
+pub(crate) const BAR_TRACK: Rgb = Rgb { r: 5, g: 5, b: 5 };
+
+/// Image filled with a single color
+pub(crate) fn make_solid_image(width: usize, height: usize, color: Rgb) -> ImageData {
+    ImageData { width, height, pixels: vec![color; width * height], alpha: None }
+}
+
+/// Horizontal linear gradient from `from` (left column) to `to` (right column)
+pub(crate) fn make_gradient_image(w: usize, h: usize, from: Rgb, to: Rgb) -> ImageData {
+    let lerp = |a: u8, b: u8, t: f32| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    let row: Vec<Rgb> = (0..w)
+        .map(|x| {
+            let t = if w > 1 { x as f32 / (w - 1) as f32 } else { 0.0 };
+            Rgb::new(lerp(from.r, to.r, t), lerp(from.g, to.g, t), lerp(from.b, to.b, t))
+        })
+        .collect();
+
+    ImageData { width: w, height: h, pixels: row.repeat(h), alpha: None }
+}
+
+/// `width` x 30 frame with a 10px tall bar at y 10..20 filled to `health_percent` (0-1)
+/// The rest of the track is `BAR_TRACK`.
+pub(crate) fn make_health_bar_image(width: usize, health_percent: f32, bar_color: Rgb) -> ImageData {
+    let mut image = make_solid_image(width, 30, BAR_BACKGROUND);
+    let filled = (width as f32 * health_percent.clamp(0.0, 1.0)).round() as usize;
+    for y in 10..20 {
+        image.pixels[y * width..y * width + filled].fill(bar_color);
+        image.pixels[y * width + filled..(y + 1) * width].fill(BAR_TRACK);
+    }
+    image
+}
+
+/// Board of `BOARD_CELL_SIZE` cells colored by class index (row-major `colors`)
+/// Indices follow the eliminate classifier: 0 empty, 1 red, 2 orange, 3 yellow,
+/// 4 green, 5 cyan, 6 blue, 7 purple.
+pub(crate) fn make_game_board(rows: usize, cols: usize, colors: &[u8]) -> ImageData {
+    const PALETTE: [Rgb; 8] = [
+        Rgb { r: 0, g: 0, b: 0 },
+        Rgb { r: 220, g: 30, b: 30 },
+        Rgb { r: 230, g: 140, b: 20 },
+        Rgb { r: 200, g: 230, b: 20 },
+        Rgb { r: 30, g: 200, b: 60 },
+        Rgb { r: 20, g: 200, b: 200 },
+        Rgb { r: 30, g: 60, b: 220 },
+        Rgb { r: 160, g: 40, b: 220 },
+    ];
+
+    let width = cols * BOARD_CELL_SIZE;
+    let mut image = make_solid_image(width, rows * BOARD_CELL_SIZE, PALETTE[0]);
+    for (i, &class) in colors.iter().take(rows * cols).enumerate() {
+        let color = PALETTE[class as usize % PALETTE.len()];
+        let (x0, y0) = ((i % cols) * BOARD_CELL_SIZE, (i / cols) * BOARD_CELL_SIZE);
+        for y in y0..y0 + BOARD_CELL_SIZE {
+            image.pixels[y * width + x0..y * width + x0 + BOARD_CELL_SIZE].fill(color);
+        }
+    }
+    image
+}